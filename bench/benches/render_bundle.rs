@@ -0,0 +1,60 @@
+use agpu::RenderAttachmentBuild;
+use criterion::{criterion_group, criterion_main, Criterion};
+
+const DRAWS: usize = 1000;
+
+pub fn render_bundle(c: &mut Criterion) {
+    let gpu = agpu::Gpu::builder().build_headless().unwrap();
+    let pipeline = gpu.new_pipeline("bench_render_bundle").create();
+    let output = gpu
+        .new_texture("bench_texture")
+        .with_format(agpu::TextureFormat::Bgra8UnormSrgb)
+        .as_render_target()
+        .create_empty((2048, 2048));
+
+    c.bench_function("encode_per_frame", |b| {
+        b.iter(|| {
+            let mut encoder = gpu.create_command_encoder("bench_encoder");
+            {
+                let mut r = encoder
+                    .render_pass("bench_pass", &[output.attach_render().clear()])
+                    .begin();
+
+                r.set_pipeline(&pipeline);
+
+                for _ in 0..DRAWS {
+                    r.draw_triangle();
+                }
+            }
+
+            gpu.queue.submit([encoder.finish()]);
+            gpu.device.poll(agpu::wgpu::Maintain::Wait);
+        })
+    });
+
+    let formats = [agpu::TextureFormat::Bgra8UnormSrgb];
+    let bundle = {
+        let mut bundle_encoder = gpu.new_render_bundle("bench_bundle", &formats).begin();
+        bundle_encoder.set_pipeline(&pipeline);
+        for _ in 0..DRAWS {
+            bundle_encoder.draw_triangle();
+        }
+        bundle_encoder.finish()
+    };
+
+    c.bench_function("execute_bundle", |b| {
+        b.iter(|| {
+            let mut encoder = gpu.create_command_encoder("bench_encoder");
+            encoder
+                .render_pass("bench_pass", &[output.attach_render().clear()])
+                .begin()
+                .execute_bundle(&bundle);
+
+            gpu.queue.submit([encoder.finish()]);
+            gpu.device.poll(agpu::wgpu::Maintain::Wait);
+        })
+    });
+}
+
+criterion_group!(benches, render_bundle);
+criterion_main!(benches);
@@ -0,0 +1,77 @@
+//! Updates particle positions in a compute pass, then draws the particles in a
+//! render pass recorded on the same encoder
+
+use agpu::prelude::*;
+use bytemuck::{Pod, Zeroable};
+
+const PARTICLE_COUNT: u32 = 4096;
+const WORKGROUP_SIZE: u32 = 64;
+
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable, VertexLayout)]
+struct Particle {
+    pos: [f32; 2],
+    vel: [f32; 2],
+}
+
+/// Spreads the particles out with a simple hash, so no rng is needed
+fn create_particles() -> Vec<Particle> {
+    let hash = |i: u32, seed: u32| {
+        let n = i.wrapping_mul(0x9E37_79B9) ^ seed.wrapping_mul(0x85EB_CA6B);
+        let n = (n ^ (n >> 15)).wrapping_mul(0x2C1B_3C6D);
+        (n ^ (n >> 12)) as f32 / u32::MAX as f32 * 2.0 - 1.0
+    };
+    (0..PARTICLE_COUNT)
+        .map(|i| Particle {
+            pos: [hash(i, 1), hash(i, 2)],
+            vel: [hash(i, 3) * 0.005, hash(i, 4) * 0.005],
+        })
+        .collect()
+}
+
+fn main() -> Result<(), BoxError> {
+    let program = agpu::GpuProgram::builder("Compute particles example").build()?;
+    let gpu = program.gpu.clone();
+
+    // The particles are written as storage in the compute pass, then read as
+    // vertices in the render pass
+    let particle_buffer = gpu
+        .new_buffer("Particle buffer")
+        .as_storage_buffer()
+        .as_vertex_buffer()
+        .create(&create_particles());
+    let bind_group = gpu.create_bind_group(&[particle_buffer.bind_storage().in_compute()]);
+
+    let compute_pipeline = gpu
+        .new_compute()
+        .with_shader(include_bytes!("shader/particles.wgsl"))
+        .with_bind_groups(&[bind_group.layout.inner()])
+        .create();
+
+    let vertex_layouts = &[Particle::vertex_buffer_layout::<0>()];
+    let render_pipeline = gpu
+        .new_pipeline("Particle render pipeline")
+        .with_vertex_fragment(include_bytes!("shader/particles.wgsl"))
+        .with_vertex_layouts(vertex_layouts)
+        .vertex_points()
+        .create();
+
+    program.run_draw(move |frame| {
+        let mut encoder = frame.create_encoder("Particle encoder");
+
+        encoder
+            .compute_pass("Particle update pass")
+            .with_pipeline(&compute_pipeline)
+            .begin()
+            .set_bind_group(0, &bind_group, &[])
+            .dispatch_for([PARTICLE_COUNT, 1, 1], [WORKGROUP_SIZE, 1, 1]);
+
+        // Recorded after the compute pass, so it sees the updated positions
+        encoder
+            .render_pass("Particle render pass", &[frame.attach_render().clear()])
+            .with_pipeline(&render_pipeline)
+            .begin()
+            .set_vertex_buffer(0, particle_buffer.slice(..))
+            .draw(0..PARTICLE_COUNT, 0..1);
+    })
+}
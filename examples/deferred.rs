@@ -0,0 +1,70 @@
+//! Renders a triangle into a three-target G-buffer, then composites the
+//! targets onto the screen
+
+use agpu::prelude::*;
+
+struct State {
+    gbuffer: agpu::GBuffer,
+    bind_group: agpu::BindGroup,
+}
+
+fn gbuffer_bind_group(gpu: &agpu::Gpu, gbuffer: &agpu::GBuffer) -> agpu::BindGroup {
+    gpu.create_bind_group_fragment(&gbuffer.bindings())
+}
+
+fn main() -> Result<(), BoxError> {
+    let program = agpu::GpuProgram::builder("Deferred example").build()?;
+    let gpu = program.gpu.clone();
+
+    // Albedo, normal and position targets, matching the shader's
+    // `location(0..3)` outputs
+    let gbuffer = gpu
+        .new_gbuffer((program.viewport.width(), program.viewport.height()))
+        .with_target("Albedo", wgpu::TextureFormat::Rgba8Unorm)
+        .with_target("Normal", wgpu::TextureFormat::Rgba16Float)
+        .with_target("Position", wgpu::TextureFormat::Rgba16Float)
+        .create();
+    let bind_group = gbuffer_bind_group(&gpu, &gbuffer);
+
+    let color_targets = gbuffer.color_targets();
+    let geometry_pipeline = gpu
+        .new_pipeline("Geometry pipeline")
+        .with_vertex_fragment(include_bytes!("shader/deferred.wgsl"))
+        .with_fragment_targets(&color_targets)
+        .create();
+
+    let composite_pipeline = gpu
+        .new_pipeline("Composite pipeline")
+        .with_vertex_fragment(include_bytes!("shader/deferred.wgsl"))
+        .with_vertex_entry("vs_composite")
+        .with_fragment_entry("fs_composite")
+        .with_bind_groups(&[&bind_group.layout])
+        .create();
+
+    let state = State {
+        gbuffer,
+        bind_group,
+    };
+    program.run_draw_with(state, move |state, program, frame| {
+        // The G-buffer must match the size of the window
+        if let Some(size) = frame.resized_to {
+            state.gbuffer.resize(size);
+            state.bind_group = gbuffer_bind_group(&program.gpu, &state.gbuffer);
+        }
+
+        let mut encoder = frame.create_encoder("Deferred encoder");
+
+        encoder
+            .render_pass("Geometry pass", &state.gbuffer.attachments())
+            .with_pipeline(&geometry_pipeline)
+            .begin()
+            .draw_triangle();
+
+        encoder
+            .render_pass("Composite pass", &[frame.attach_render().clear()])
+            .with_pipeline(&composite_pipeline)
+            .begin()
+            .set_bind_group(0, &state.bind_group, &[])
+            .draw_triangle();
+    })
+}
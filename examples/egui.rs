@@ -37,7 +37,7 @@ fn main() {
 
     let mut egui = agpu::egui::Egui::new(gpu.clone(), viewport.width(), viewport.height());
 
-    // let mut stat_counts = [0; 5];
+    let mut stat_counts: Vec<(&str, u64)> = vec![];
     let mut timestamps: Vec<(String, f32)> = vec![];
 
     // Start the event loop
@@ -83,16 +83,12 @@ fn main() {
                                 })
                                 .show_inside(ui, |ui| {
                                     egui::Grid::new("stats grid").show(ui, |ui| {
-                                        // // Shows stat counts
-                                        // FIXME: Stat counts are broken and cause gpu to hang
-                                        // for (count, label) in stat_counts
-                                        //     .iter()
-                                        //     .zip(thermite_core::PIPELINE_STATISTICS_LABELS)
-                                        // {
-                                        //     ui.label(label);
-                                        //     ui.label(count);
-                                        //     ui.end_row();
-                                        // }
+                                        // Shows stat counts
+                                        for (label, count) in &stat_counts {
+                                            ui.label(*label);
+                                            ui.label(count.to_string());
+                                            ui.end_row();
+                                        }
 
                                         for (label, value) in &timestamps {
                                             ui.label(label);
@@ -155,9 +151,7 @@ fn main() {
                     egui.draw(&mut ui_pass, viewport.width(), viewport.height());
                 }
 
-                // if let Ok(stats) = gpu.total_statistics() {
-                //     stat_counts = stats;
-                // };
+                stat_counts = gpu.statistics_report();
                 timestamps = gpu.timestamp_report();
             }
 
@@ -0,0 +1,90 @@
+use agpu::prelude::*;
+use bytemuck::{Pod, Zeroable};
+
+const BACKGROUND: u32 = 0x10_10_18_FF;
+/// The number of instances along each side of the grid
+const GRID: usize = 12;
+
+/// The base quad, in slot 0
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable, VertexLayout)]
+struct Vertex {
+    position: [f32; 2],
+}
+
+/// The per-instance data, in slot 1.
+/// The fields start at shader location 1, after the quad, see `shader/instancing.wgsl`.
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable, VertexLayoutInstance)]
+struct Instance {
+    offset: [f32; 2],
+    scale: f32,
+    rotation: f32,
+    color: [f32; 4],
+}
+
+fn create_instances() -> Vec<Instance> {
+    let cell = 2.0 / GRID as f32;
+    (0..GRID * GRID)
+        .map(|i| {
+            let (x, y) = ((i % GRID) as f32, (i / GRID) as f32);
+            let t = i as f32 / (GRID * GRID) as f32;
+            Instance {
+                offset: [-1.0 + cell * (x + 0.5), -1.0 + cell * (y + 0.5)],
+                scale: cell * 0.35,
+                rotation: t * std::f32::consts::TAU,
+                color: [x / GRID as f32, y / GRID as f32, 1.0 - t, 1.0],
+            }
+        })
+        .collect()
+}
+
+fn main() -> Result<(), agpu::BoxError> {
+    let program = agpu::GpuProgram::builder("Instancing").build()?;
+    let gpu = program.gpu.clone();
+
+    let vertex_buffer = gpu.new_vertex_buffer("Quad vertices").create(&[
+        Vertex {
+            position: [-1.0, -1.0],
+        },
+        Vertex {
+            position: [1.0, -1.0],
+        },
+        Vertex {
+            position: [1.0, 1.0],
+        },
+        Vertex {
+            position: [-1.0, 1.0],
+        },
+    ]);
+    let index_buffer = gpu
+        .new_buffer("Quad indices")
+        .as_index_buffer()
+        .create(&[0_u16, 1, 2, 0, 2, 3]);
+    let instances = create_instances();
+    let instance_buffer = gpu.new_vertex_buffer("Instances").create(&instances);
+
+    // The quad uses shader location 0, and the instance fields locations 1 to 4
+    let vertex_layouts = VertexLayouts::new().add::<Vertex>().add::<Instance>();
+    let vertex_layouts = vertex_layouts.layouts();
+    let pipeline = gpu
+        .new_pipeline("Instancing pipeline")
+        .with_vertex_fragment(include_bytes!("shader/instancing.wgsl"))
+        .with_vertex_layouts(&vertex_layouts)
+        .create();
+
+    program.run_draw(move |frame| {
+        let mut encoder = frame.create_encoder("Instancing encoder");
+        encoder
+            .render_pass(
+                "Instancing pass",
+                &[frame.attach_render().clear_color(BACKGROUND)],
+            )
+            .with_pipeline(&pipeline)
+            .begin()
+            .set_typed_vertex_buffer(0, &vertex_buffer)
+            .set_typed_vertex_buffer(1, &instance_buffer)
+            .set_index_buffer_auto(&index_buffer)
+            .draw_indexed_instanced(6, instance_buffer.len());
+    })
+}
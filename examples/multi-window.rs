@@ -0,0 +1,40 @@
+//! Draws to a main window and a second window that share the same gpu.
+//! Closing the second window keeps the program running.
+
+use agpu::prelude::*;
+use agpu::Event;
+use winit::{dpi::LogicalSize, window::WindowBuilder};
+
+fn main() -> Result<(), agpu::BoxError> {
+    let program = agpu::GpuProgram::builder("Main window").build()?;
+    let gpu = program.gpu.clone();
+
+    // The program keeps the only reference, so the window is destroyed when closed
+    let second_id = program
+        .create_window(
+            WindowBuilder::new()
+                .with_title("Second window")
+                .with_inner_size(LogicalSize::new(320, 240)),
+        )?
+        .id();
+
+    let example_pipeline = program
+        .gpu
+        .new_pipeline("Example pipeline")
+        .with_vertex_fragment(include_bytes!("shader/triangle.wgsl"))
+        .create();
+
+    program.run(move |event, _, _, _| {
+        let (frame, color) = match event {
+            Event::RedrawFrame(frame) => (frame, 0x202020),
+            Event::RedrawWindowFrame(id, frame) if id == second_id => (frame, 0x404060),
+            _ => return,
+        };
+        let mut encoder = gpu.create_command_encoder("Example encoder");
+        encoder
+            .render_pass("Example pass", &[frame.attach_render().clear_color(color)])
+            .begin()
+            .set_pipeline(&example_pipeline)
+            .draw_triangle();
+    })
+}
@@ -0,0 +1,60 @@
+//! Draws an outline around a triangle using the stencil buffer.
+//! The triangle marks its pixels in the stencil buffer, then a larger copy of the
+//! triangle is drawn only where the stencil was not marked.
+
+use agpu::prelude::*;
+use agpu::wgpu::{CompareFunction, StencilFaceState, StencilOperation};
+
+fn main() -> Result<(), agpu::BoxError> {
+    tracing_subscriber::fmt::init();
+    // The viewport's depth texture holds the stencil, and is resized with the window
+    let program = agpu::GpuProgram::builder("Outline")
+        .with_depth_format(TextureFormat::Depth24PlusStencil8)
+        .build()?;
+    let gpu = program.gpu.clone();
+
+    // Writes the reference value wherever the triangle is drawn
+    let mask_pipeline = gpu
+        .new_pipeline("Mask pipeline")
+        .with_vertex_fragment(include_bytes!("shader/outline.wgsl"))
+        .with_depth_stencil()
+        .depth_write(false)
+        .depth_compare(CompareFunction::Always)
+        .stencil(StencilFaceState {
+            compare: CompareFunction::Always,
+            fail_op: StencilOperation::Keep,
+            depth_fail_op: StencilOperation::Keep,
+            pass_op: StencilOperation::Replace,
+        })
+        .create();
+
+    // Draws only where the stencil does not hold the reference value
+    let outline_pipeline = gpu
+        .new_pipeline("Outline pipeline")
+        .with_vertex_fragment(include_bytes!("shader/outline.wgsl"))
+        .with_vertex_entry("vs_outline")
+        .with_depth_stencil()
+        .depth_write(false)
+        .depth_compare(CompareFunction::Always)
+        .stencil(StencilFaceState {
+            compare: CompareFunction::NotEqual,
+            fail_op: StencilOperation::Keep,
+            depth_fail_op: StencilOperation::Keep,
+            pass_op: StencilOperation::Keep,
+        })
+        .stencil_write_mask(0)
+        .create();
+
+    program.run_draw(move |frame| {
+        let mut encoder = gpu.create_command_encoder("Outline encoder");
+        let mut render_pass = encoder
+            .render_pass("Outline pass", &[frame.attach_render().clear()])
+            .with_depth(frame.attach_depth_stencil().clear())
+            .begin();
+        render_pass
+            .set_stencil_reference(1)
+            .set_pipeline(&mask_pipeline)
+            .draw_triangle();
+        render_pass.set_pipeline(&outline_pipeline).draw_triangle();
+    })
+}
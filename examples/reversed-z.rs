@@ -0,0 +1,38 @@
+//! Reversed-Z depth testing, where near is 1.0 and far is 0.0.
+//! The near (red) triangle is drawn first, but still covers the far (blue) triangle.
+
+use agpu::prelude::*;
+
+fn main() -> Result<(), agpu::BoxError> {
+    let program = agpu::GpuProgram::builder("Reversed-Z").build()?;
+    let gpu = program.gpu.clone();
+
+    let near_pipeline = gpu
+        .new_pipeline("Near pipeline")
+        .with_vertex_fragment(include_bytes!("shader/reversed-z.wgsl"))
+        .with_vertex_entry("vs_near")
+        .with_fragment_entry("fs_near")
+        .with_depth()
+        .reverse_z()
+        .create();
+
+    let far_pipeline = gpu
+        .new_pipeline("Far pipeline")
+        .with_vertex_fragment(include_bytes!("shader/reversed-z.wgsl"))
+        .with_vertex_entry("vs_far")
+        .with_fragment_entry("fs_far")
+        .with_depth()
+        .reverse_z()
+        .create();
+
+    program.run_draw(move |frame| {
+        let mut encoder = gpu.create_command_encoder("Reversed-Z encoder");
+        // Far is 0.0 with reversed-Z, so the depth is cleared to 0.0 instead of 1.0
+        let mut render_pass = encoder
+            .render_pass("Reversed-Z pass", &[frame.attach_render().clear()])
+            .with_depth(frame.attach_depth().clear_depth_val(0.0))
+            .begin();
+        render_pass.set_pipeline(&near_pipeline).draw_triangle();
+        render_pass.set_pipeline(&far_pipeline).draw_triangle();
+    })
+}
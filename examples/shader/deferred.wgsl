@@ -0,0 +1,71 @@
+struct GeometryOutput {
+  [[builtin(position)]] position: vec4<f32>;
+  [[location(0)]] color: vec4<f32>;
+  [[location(1)]] normal: vec3<f32>;
+};
+
+[[stage(vertex)]]
+fn vs_main([[builtin(vertex_index)]] vertex_index: u32) -> GeometryOutput {
+    var out: GeometryOutput;
+    let x = f32(i32(vertex_index) - 1) / 2.0;
+    let y = f32(i32(vertex_index & 1u) * 2 - 1) / 2.0;
+    out.position = vec4<f32>(x, y, 0.0, 1.0);
+    out.color = vec4<f32>(f32(vertex_index == 1u), f32(vertex_index == 0u), f32(vertex_index == 2u), 1.0);
+    out.normal = normalize(vec3<f32>(x, y, 0.5));
+    return out;
+}
+
+struct GBufferOutput {
+  [[location(0)]] albedo: vec4<f32>;
+  [[location(1)]] normal: vec4<f32>;
+  [[location(2)]] position: vec4<f32>;
+};
+
+[[stage(fragment)]]
+fn fs_main(in: GeometryOutput) -> GBufferOutput {
+    var out: GBufferOutput;
+    out.albedo = in.color;
+    out.normal = vec4<f32>(in.normal * 0.5 + 0.5, 1.0);
+    out.position = vec4<f32>(in.position.z, 0.0, 0.0, 1.0);
+    return out;
+}
+
+struct CompositeOutput {
+  [[builtin(position)]] position: vec4<f32>;
+  [[location(0)]] uv: vec2<f32>;
+};
+
+[[stage(vertex)]]
+fn vs_composite([[builtin(vertex_index)]] vertex_index: u32) -> CompositeOutput {
+    var out: CompositeOutput;
+    let uv = vec2<f32>(f32((vertex_index << 1u) & 2u), f32(vertex_index & 2u));
+    out.position = vec4<f32>(uv * 2.0 - 1.0, 0.0, 1.0);
+    out.uv = vec2<f32>(uv.x, 1.0 - uv.y);
+    return out;
+}
+
+[[group(0), binding(0)]]
+var t_albedo: texture_2d<f32>;
+[[group(0), binding(1)]]
+var t_normal: texture_2d<f32>;
+[[group(0), binding(2)]]
+var t_position: texture_2d<f32>;
+
+// Shades the albedo with a directional light, and shows the raw normal and
+// position targets in the left and right thirds of the screen.
+[[stage(fragment)]]
+fn fs_composite(in: CompositeOutput) -> [[location(0)]] vec4<f32> {
+    let size = textureDimensions(t_albedo);
+    let coords = vec2<i32>(in.uv * vec2<f32>(size));
+    let albedo = textureLoad(t_albedo, coords, 0);
+    let normal = textureLoad(t_normal, coords, 0);
+    let position = textureLoad(t_position, coords, 0);
+    if (in.uv.x < 1.0 / 3.0) {
+        return normal;
+    }
+    if (in.uv.x > 2.0 / 3.0) {
+        return position;
+    }
+    let light = max(dot(normal.xyz * 2.0 - 1.0, normalize(vec3<f32>(0.5, 0.5, 1.0))), 0.0);
+    return vec4<f32>(albedo.rgb * light, albedo.a);
+}
@@ -0,0 +1,35 @@
+struct VertexInput {
+  [[location(0)]] position: vec2<f32>;
+};
+
+struct InstanceInput {
+  [[location(1)]] offset: vec2<f32>;
+  [[location(2)]] scale: f32;
+  [[location(3)]] rotation: f32;
+  [[location(4)]] color: vec4<f32>;
+};
+
+struct VertexOutput {
+  [[builtin(position)]] position: vec4<f32>;
+  [[location(0)]] color: vec4<f32>;
+};
+
+[[stage(vertex)]]
+fn vs_main(vertex: VertexInput, instance: InstanceInput) -> VertexOutput {
+    let c = cos(instance.rotation);
+    let s = sin(instance.rotation);
+    let rotated = vec2<f32>(
+        vertex.position.x * c - vertex.position.y * s,
+        vertex.position.x * s + vertex.position.y * c,
+    );
+
+    var out: VertexOutput;
+    out.position = vec4<f32>(rotated * instance.scale + instance.offset, 0.0, 1.0);
+    out.color = instance.color;
+    return out;
+}
+
+[[stage(fragment)]]
+fn fs_main(in: VertexOutput) -> [[location(0)]] vec4<f32> {
+    return in.color;
+}
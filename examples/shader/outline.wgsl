@@ -0,0 +1,30 @@
+struct VertexOutput {
+    [[builtin(position)]] position: vec4<f32>;
+    [[location(0)]] color: vec3<f32>;
+};
+
+fn triangle(index: u32, scale: f32) -> vec4<f32> {
+    let angle = f32(index) * 2.0943951 + 1.5707963;
+    return vec4<f32>(cos(angle) * scale * 0.6, sin(angle) * scale * 0.6, 0.5, 1.0);
+}
+
+[[stage(vertex)]]
+fn vs_main([[builtin(vertex_index)]] index: u32) -> VertexOutput {
+    var out: VertexOutput;
+    out.position = triangle(index, 1.0);
+    out.color = vec3<f32>(f32(index == 0u), f32(index == 1u), f32(index == 2u));
+    return out;
+}
+
+[[stage(vertex)]]
+fn vs_outline([[builtin(vertex_index)]] index: u32) -> VertexOutput {
+    var out: VertexOutput;
+    out.position = triangle(index, 1.15);
+    out.color = vec3<f32>(1.0, 0.6, 0.0);
+    return out;
+}
+
+[[stage(fragment)]]
+fn fs_main(in: VertexOutput) -> [[location(0)]] vec4<f32> {
+    return vec4<f32>(in.color, 1.0);
+}
@@ -0,0 +1,47 @@
+struct Particle {
+    pos: vec2<f32>;
+    vel: vec2<f32>;
+};
+
+struct Particles {
+    data: array<Particle>;
+};
+
+[[group(0), binding(0)]]
+var<storage, read_write> particles: Particles;
+
+// Moves each particle by its velocity, bouncing off the edges of the screen
+[[stage(compute), workgroup_size(64)]]
+fn main([[builtin(global_invocation_id)]] id: vec3<u32>) {
+    let i = id.x;
+    if (i >= arrayLength(&particles.data)) {
+        return;
+    }
+    var particle = particles.data[i];
+    particle.pos = particle.pos + particle.vel;
+    if (abs(particle.pos.x) > 1.0) {
+        particle.vel.x = -particle.vel.x;
+    }
+    if (abs(particle.pos.y) > 1.0) {
+        particle.vel.y = -particle.vel.y;
+    }
+    particles.data[i] = particle;
+}
+
+struct VertexOutput {
+  [[builtin(position)]] position: vec4<f32>;
+  [[location(0)]] color: vec4<f32>;
+};
+
+[[stage(vertex)]]
+fn vs_main([[location(0)]] pos: vec2<f32>, [[location(1)]] vel: vec2<f32>) -> VertexOutput {
+    var out: VertexOutput;
+    out.position = vec4<f32>(pos, 0.0, 1.0);
+    out.color = vec4<f32>(normalize(abs(vel)), 1.0, 1.0);
+    return out;
+}
+
+[[stage(fragment)]]
+fn fs_main(in: VertexOutput) -> [[location(0)]] vec4<f32> {
+    return in.color;
+}
@@ -0,0 +1,26 @@
+// Two overlapping triangles. With reversed-Z, the depth of the near triangle is
+// greater than the depth of the far triangle.
+
+[[stage(vertex)]]
+fn vs_near([[builtin(vertex_index)]] index: u32) -> [[builtin(position)]] vec4<f32> {
+    let x = f32(i32(index) - 1);
+    let y = f32(i32(index & 1u) * 2 - 1);
+    return vec4<f32>(x * 0.8 - 0.2, y * 0.8, 0.9, 1.0);
+}
+
+[[stage(vertex)]]
+fn vs_far([[builtin(vertex_index)]] index: u32) -> [[builtin(position)]] vec4<f32> {
+    let x = f32(i32(index) - 1);
+    let y = f32(i32(index & 1u) * 2 - 1);
+    return vec4<f32>(x * 0.8 + 0.2, y * 0.8, 0.1, 1.0);
+}
+
+[[stage(fragment)]]
+fn fs_near() -> [[location(0)]] vec4<f32> {
+    return vec4<f32>(1.0, 0.0, 0.0, 1.0);
+}
+
+[[stage(fragment)]]
+fn fs_far() -> [[location(0)]] vec4<f32> {
+    return vec4<f32>(0.0, 0.0, 1.0, 1.0);
+}
@@ -0,0 +1,40 @@
+struct Locals {
+    light_transform: mat4x4<f32>;
+    view_transform: mat4x4<f32>;
+};
+[[group(0), binding(0)]]
+var<uniform> r_locals: Locals;
+
+[[stage(vertex)]]
+fn vs_main([[location(0)]] position: vec4<f32>) -> [[builtin(position)]] vec4<f32> {
+    return r_locals.light_transform * position;
+}
+
+struct VertexOutput {
+    [[builtin(position)]] position: vec4<f32>;
+    [[location(0)]] shadow_position: vec4<f32>;
+};
+
+// Renders the scene from the camera, with the position in the shadow map
+[[stage(vertex)]]
+fn vs_scene([[location(0)]] position: vec4<f32>) -> VertexOutput {
+    var out: VertexOutput;
+    out.position = r_locals.view_transform * position;
+    out.shadow_position = r_locals.light_transform * position;
+    return out;
+}
+
+[[group(1), binding(0)]]
+var t_shadow: texture_depth_2d;
+[[group(1), binding(1)]]
+var s_shadow: sampler_comparison;
+
+[[stage(fragment)]]
+fn fs_scene(in: VertexOutput) -> [[location(0)]] vec4<f32> {
+    let position = in.shadow_position.xyz / in.shadow_position.w;
+    // Clip space is y-up, while texture coordinates are y-down
+    let uv = position.xy * vec2<f32>(0.5, -0.5) + 0.5;
+    // The bias avoids surfaces shadowing themselves
+    let lit = textureSampleCompare(t_shadow, s_shadow, uv, position.z - 0.005);
+    return vec4<f32>(vec3<f32>(0.2 + 0.8 * lit), 1.0);
+}
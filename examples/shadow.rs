@@ -0,0 +1,166 @@
+//! Renders a cube into a depth-only shadow map, then renders the cube on a
+//! ground plane, sampling the shadow map with a comparison sampler
+
+use agpu::{nalgebra, prelude::*};
+use bytemuck::{Pod, Zeroable};
+
+const SHADOW_SIZE: u32 = 1024;
+
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable, VertexLayout)]
+struct Vertex {
+    pos: [f32; 4],
+}
+
+fn vertex(pos: [i8; 3]) -> Vertex {
+    Vertex {
+        pos: [pos[0] as f32, pos[1] as f32, pos[2] as f32, 1.0],
+    }
+}
+
+fn create_vertices() -> (Vec<Vertex>, Vec<u16>) {
+    let vertex_data = [
+        // cube
+        vertex([-1, -1, -1]),
+        vertex([1, -1, -1]),
+        vertex([1, 1, -1]),
+        vertex([-1, 1, -1]),
+        vertex([-1, -1, 1]),
+        vertex([1, -1, 1]),
+        vertex([1, 1, 1]),
+        vertex([-1, 1, 1]),
+        // ground
+        vertex([-4, -4, -1]),
+        vertex([4, -4, -1]),
+        vertex([4, 4, -1]),
+        vertex([-4, 4, -1]),
+    ];
+
+    let index_data: &[u16] = &[
+        4, 5, 6, 6, 7, 4, // top
+        3, 2, 1, 1, 0, 3, // bottom
+        1, 2, 6, 6, 5, 1, // right
+        4, 7, 3, 3, 0, 4, // left
+        2, 3, 7, 7, 6, 2, // front
+        0, 1, 5, 5, 4, 0, // back
+        8, 9, 10, 10, 11, 8, // ground
+    ];
+
+    (vertex_data.to_vec(), index_data.to_vec())
+}
+
+fn light_camera() -> Camera {
+    let mut camera = Camera::orthographic(10.0, 1.0, 1.0, 20.0);
+    camera.look_at(
+        [3.0, -4.0, 6.0].into(),
+        [0.0, 0.0, 0.0].into(),
+        nalgebra::Vector3::z(),
+    );
+    camera
+}
+
+fn view_camera(aspect_ratio: f32) -> Camera {
+    let mut camera = Camera::perspective(45.0_f32.to_radians(), aspect_ratio, 1.0, 30.0);
+    camera.look_at(
+        [6.0, -9.0, 6.0].into(),
+        [0.0, 0.0, 0.0].into(),
+        nalgebra::Vector3::z(),
+    );
+    camera
+}
+
+/// The light and view matrices, matching `Locals` in the shader
+fn locals(light: &Camera, view: &Camera) -> Vec<u8> {
+    [light.as_uniform_bytes(), view.as_uniform_bytes()].concat()
+}
+
+fn main() -> Result<(), BoxError> {
+    let program = agpu::GpuProgram::builder("Shadow map example").build()?;
+    let gpu = program.gpu.clone();
+
+    let (vertex_data, index_data) = create_vertices();
+    let vertex_buffer = gpu
+        .new_buffer("Vertex buffer")
+        .as_vertex_buffer()
+        .create(&vertex_data);
+    let index_buffer = gpu
+        .new_buffer("Index buffer")
+        .as_index_buffer()
+        .create(&index_data);
+
+    let light = light_camera();
+    let mut view = view_camera(program.viewport.aspect_ratio());
+    let uniform_buf = gpu
+        .new_buffer("Locals uniform buffer")
+        .as_uniform_buffer()
+        .allow_copy_to()
+        .create(&locals(&light, &view));
+    let locals_bind_group = gpu.create_bind_group(&[uniform_buf.bind_uniform().in_vertex()]);
+
+    // The shadow map is a depth texture with no color counterpart
+    let shadow_map = gpu
+        .new_texture("Shadow map")
+        .as_depth()
+        .allow_binding()
+        .create_empty((SHADOW_SIZE, SHADOW_SIZE));
+    // A comparison sampler returns 1.0 where the depth passes the test, i.e. is lit
+    let shadow_sampler = gpu
+        .new_sampler("Shadow sampler")
+        .linear_filter()
+        .comparator(CompareFunction::LessEqual)
+        .create();
+    let shadow_bind_group =
+        gpu.create_bind_group_fragment(&[shadow_map.bind_depth()?, shadow_sampler.bind()]);
+
+    let vertex_layouts = &[Vertex::vertex_buffer_layout::<0>()];
+    let shadow_pipeline = gpu
+        .new_pipeline("Shadow pipeline")
+        .with_vertex(include_bytes!("shader/shadow.wgsl"))
+        .with_vertex_entry("vs_main")
+        .no_fragment()
+        .with_vertex_layouts(vertex_layouts)
+        .with_bind_groups(&[&locals_bind_group.layout])
+        .with_depth()
+        .create();
+
+    let scene_pipeline = gpu
+        .new_pipeline("Scene pipeline")
+        .with_vertex_fragment(include_bytes!("shader/shadow.wgsl"))
+        .with_vertex_entry("vs_scene")
+        .with_fragment_entry("fs_scene")
+        .with_vertex_layouts(vertex_layouts)
+        .with_bind_groups(&[&locals_bind_group.layout, &shadow_bind_group.layout])
+        .with_depth()
+        .create();
+
+    program.on_resize(move |_, width, height| {
+        view.update_aspect(width as f32 / height as f32);
+        uniform_buf.write_unchecked(&locals(&light, &view));
+    });
+
+    program.run_draw(move |frame| {
+        let mut encoder = frame.create_encoder("Shadow encoder");
+
+        // Render the scene into the shadow map
+        encoder
+            .depth_pass("Shadow pass", shadow_map.attach_depth().clear_depth())
+            .with_pipeline(&shadow_pipeline)
+            .begin()
+            .set_vertex_buffer(0, vertex_buffer.slice(..))
+            .set_index_buffer(index_buffer.slice(..))
+            .set_bind_group(0, &locals_bind_group, &[])
+            .draw_one_indexed(index_data.len() as _);
+
+        // Render the scene from the camera, shadowed where the shadow map is closer
+        encoder
+            .render_pass("Scene pass", &[frame.attach_render().clear()])
+            .with_depth(frame.attach_depth().clear_depth())
+            .with_pipeline(&scene_pipeline)
+            .begin()
+            .set_vertex_buffer(0, vertex_buffer.slice(..))
+            .set_index_buffer(index_buffer.slice(..))
+            .set_bind_group(0, &locals_bind_group, &[])
+            .set_bind_group(1, &shadow_bind_group, &[])
+            .draw_one_indexed(index_data.len() as _);
+    })
+}
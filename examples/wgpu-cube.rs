@@ -1,4 +1,4 @@
-use agpu::prelude::*;
+use agpu::{nalgebra, prelude::*};
 use bytemuck::{Pod, Zeroable};
 
 const BABY_BLUE: u32 = 0x20_40_60_FF;
@@ -81,14 +81,14 @@ fn create_texels(size: usize) -> Vec<u8> {
         .collect()
 }
 
-fn generate_matrix(aspect_ratio: f32) -> nalgebra::Matrix4<f32> {
-    let mx_projection = nalgebra::Perspective3::new(aspect_ratio, 45.0_f32.to_radians(), 1.0, 10.0);
-    let mx_view = nalgebra::Matrix4::look_at_rh(
-        &[1.5, -5.0, 3.0].into(),
-        &[0.0, 0.0, 0.0].into(),
-        &nalgebra::Vector3::z(),
+fn create_camera(aspect_ratio: f32) -> Camera {
+    let mut camera = Camera::perspective(45.0_f32.to_radians(), aspect_ratio, 1.0, 10.0);
+    camera.look_at(
+        [1.5, -5.0, 3.0].into(),
+        [0.0, 0.0, 0.0].into(),
+        nalgebra::Vector3::z(),
     );
-    mx_projection.to_homogeneous() * mx_view
+    camera
 }
 
 fn main() -> Result<(), BoxError> {
@@ -101,10 +101,7 @@ fn main() -> Result<(), BoxError> {
     let (vertex_data, index_data) = create_vertices();
 
     // Create the vertex buffer
-    let vertex_buffer = gpu
-        .new_buffer("Vertex buffer")
-        .as_vertex_buffer()
-        .create(&vertex_data);
+    let vertex_buffer = gpu.new_vertex_buffer("Vertex buffer").create(&vertex_data);
     let index_buffer = gpu
         .new_buffer("Index buffer")
         .as_index_buffer()
@@ -120,19 +117,19 @@ fn main() -> Result<(), BoxError> {
         .create((size, size), &texels);
 
     // Create other resources
-    let mx = generate_matrix(program.viewport.aspect_ratio());
+    let mut camera = create_camera(program.viewport.aspect_ratio());
     let uniform_buf = gpu
         .new_buffer("Uniform Buffer")
         .as_uniform_buffer()
         .allow_copy_to()
-        .create(mx.as_ref());
+        .create(camera.as_uniform_bytes());
 
     let bind_group = gpu.create_bind_group(&[
         uniform_buf.bind_uniform().in_vertex(),
         texture.bind_texture().sample_uint().in_fragment(),
     ]);
 
-    let vertex_layouts = &[Vertex::vertex_buffer_layout::<0>()];
+    let vertex_layouts = &[vertex_buffer.layout::<0>()];
     let layout: [&wgpu::BindGroupLayout; 1] = [&bind_group.layout];
     let pipeline_builder = gpu
         .new_pipeline("Cube pipeline")
@@ -147,8 +144,8 @@ fn main() -> Result<(), BoxError> {
         .create();
 
     program.on_resize(move |_, width, height| {
-        let mx = generate_matrix(width as f32 / height as f32);
-        uniform_buf.write_unchecked(mx.as_ref());
+        camera.update_aspect(width as f32 / height as f32);
+        uniform_buf.write_unchecked(camera.as_uniform_bytes());
     });
 
     program.run_draw(move |frame| {
@@ -157,7 +154,7 @@ fn main() -> Result<(), BoxError> {
             .render_pass("Main pass", &[frame.attach_render().clear_color(BABY_BLUE)])
             .begin();
         rpass
-            .set_vertex_buffer(0, vertex_buffer.slice(..))
+            .set_typed_vertex_buffer(0, &vertex_buffer)
             .set_index_buffer(index_buffer.slice(..))
             .set_bind_group(0, &bind_group.inner, &[])
             .set_pipeline(&pipeline)
@@ -1,9 +1,9 @@
-use agpu::{prelude::*, GpuProgram};
+use agpu::{prelude::*, GpuProgram, RenderGraph};
 use agpu_pp::bloom::Bloom;
 
 fn main() -> Result<(), BoxError> {
     println!("Hello, world!");
-    let mut program = GpuProgram::builder("Bloom example").with_framerate(42069999.0);
+    let mut program = GpuProgram::builder("Bloom example").uncapped();
     program.gpu = program.gpu.with_backends(agpu::Backends::VULKAN);
     let program = program.build()?;
     let gpu = program.gpu.clone();
@@ -19,12 +19,16 @@ fn main() -> Result<(), BoxError> {
             bloom.resize(width, height);
         }
 
-        frame
-            .render_pass("bloom pass")
-            .with_pipeline(&example_pipeline)
-            .begin()
-            .draw_triangle();
-
-        bloom.apply(&frame.view, &mut frame.encoder)
+        // The passes are added out of order; the graph sorts them by their dependencies
+        let mut graph = RenderGraph::new();
+        bloom.add_to_graph(&mut graph, &frame.view, "scene", "output");
+        graph.add_pass("scene", &[], &["scene"], |encoder| {
+            encoder
+                .render_pass("scene pass", &[frame.view.attach_render()])
+                .with_pipeline(&example_pipeline)
+                .begin()
+                .draw_triangle();
+        });
+        graph.execute(&mut frame.encoder).unwrap();
     });
 }
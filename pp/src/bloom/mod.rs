@@ -1,15 +1,14 @@
 use agpu::prelude::*;
 use agpu::pub_const_flag;
 use agpu::wgpu;
+use agpu::RenderGraph;
 use bytemuck::Pod;
 use bytemuck::Zeroable;
 
-type Texture = agpu::Texture<D2>;
-
 pub_const_flag!(SAMPLED_ATTACHMENT: wgpu::TextureUsages = RENDER_ATTACHMENT | TEXTURE_BINDING);
 
 pub struct BoundTexture {
-    pub texture: Texture,
+    pub texture: PooledTexture,
     pub binding: BindGroup,
 }
 
@@ -28,17 +27,24 @@ impl Default for BloomData {
     }
 }
 
+/// Matches the std140 layout of the settings uniform in the add shader.
 #[derive(Pod, Zeroable, Copy, Clone, Debug)]
 #[repr(C)]
 pub struct BloomSettings {
-    pub aspect: f32,
+    /// Color the bloom contribution is multiplied by
+    pub tint: [f32; 3],
+    /// Strength the bloom contribution is multiplied by
     pub intensity: f32,
+    pub aspect: f32,
+    _padding: [f32; 3],
 }
 impl Default for BloomSettings {
     fn default() -> Self {
         Self {
-            aspect: 1.0,
+            tint: [1.0; 3],
             intensity: 1.0,
+            aspect: 1.0,
+            _padding: [0.0; 3],
         }
     }
 }
@@ -59,6 +65,7 @@ pub struct Bloom {
     data_buffer: Buffer,
     settings: BloomSettings,
     settings_buffer: Buffer,
+    settings_bind_group: BindGroup,
     linear_sampler: Sampler,
     nearest_sampler: Sampler,
 }
@@ -69,11 +76,6 @@ fn tex_size(mip: u32, width: u32, height: u32) -> D2 {
         (height / (2_u32.pow(mip as u32))).max(1),
     )
 }
-// Following ISS #70887 we can use int_log
-fn mip_count(size: u32) -> u32 {
-    (size as f32).log2().ceil() as u32
-}
-
 fn create_mips(
     gpu: &Gpu,
     linear_sampler: &Sampler,
@@ -81,8 +83,7 @@ fn create_mips(
     width: u32,
     height: u32,
 ) -> Vec<BoundTexture> {
-    let major_dimension = width.max(height);
-    let mip_count = mip_count(major_dimension);
+    let mip_count = agpu::mip_level_count(width, height);
 
     (0..mip_count)
         .map(|i| {
@@ -91,12 +92,8 @@ fn create_mips(
                 i,
                 tex_size(i, width, height)
             );
-            let texture = gpu
-                .new_texture(&("Downchain mip".to_string() + &i.to_string()))
-                .with_format(*format)
-                .allow_binding()
-                .as_render_target()
-                .create_empty(tex_size(i, width, height));
+            let texture =
+                gpu.acquire_texture(tex_size(i, width, height), *format, SAMPLED_ATTACHMENT);
             let binding = gpu.create_bind_group(&[linear_sampler.bind(), texture.bind()]);
             BoundTexture { texture, binding }
         })
@@ -111,7 +108,6 @@ pub fn pipeline_builder<'a>(
     gpu.new_pipeline("Bloom pipeline builder")
         .with_fragment_targets(targets)
         .with_bind_groups(bind_group)
-        .with_vertex(include_bytes!("shader/screen.vert.spv"))
 }
 
 impl Bloom {
@@ -132,7 +128,9 @@ impl Bloom {
         let settings_buffer = gpu
             .new_buffer("Bloom settings buffer")
             .as_uniform_buffer()
+            .allow_copy_to()
             .create(&[BloomSettings::default()]);
+        let settings_bind_group = gpu.create_bind_group_fragment(&[settings_buffer.bind()]);
 
         // New usage: Construct "group" as array
         let _buffer_binding = [data_buffer.bind(), settings_buffer.bind()].create_group();
@@ -151,11 +149,15 @@ impl Bloom {
             .with_fragment(include_bytes!("shader/filter-up.frag.spv"))
             .with_fragment_targets(&[format.target().blend_add()])
             .create();
-        let add_pipeline = pipeline_builder(gpu, &[format.target()], &[binding_layout])
-            .with_label("Bloom add pipeline")
-            .with_fragment(include_bytes!("shader/add.frag.spv"))
-            .with_fragment_targets(&[format.target().blend_add()])
-            .create();
+        let add_pipeline = pipeline_builder(
+            gpu,
+            &[format.target()],
+            &[binding_layout, &settings_bind_group.layout],
+        )
+        .with_label("Bloom add pipeline")
+        .with_fragment(include_bytes!("shader/add.frag.spv"))
+        .with_fragment_targets(&[format.target().blend_add()])
+        .create();
 
         Self {
             gpu: gpu.clone(),
@@ -174,35 +176,93 @@ impl Bloom {
             nearest_sampler,
             data_buffer,
             settings_buffer,
+            settings_bind_group,
         }
     }
 
+    /// Sets the strength of the bloom added to the target. Defaults to 1.0.
+    /// This only updates a uniform, so it is cheap to animate every frame.
+    pub fn set_intensity(&mut self, intensity: f32) {
+        self.settings.intensity = intensity;
+        self.settings_buffer.write_unchecked(&[self.settings]);
+    }
+
+    /// Sets the color the bloom added to the target is multiplied by.
+    /// Defaults to white. See `set_intensity()`.
+    pub fn set_tint(&mut self, tint: [f32; 3]) {
+        self.settings.tint = tint;
+        self.settings_buffer.write_unchecked(&[self.settings]);
+    }
+
     pub fn resize(&mut self, width: u32, height: u32) {
         self.width = width;
         self.height = height;
 
         dbg!(width, height);
+        // Release the old mips first, so that mips of the same size can be reused
+        self.mips.clear();
         self.mips = create_mips(&self.gpu, &self.linear_sampler, self.format, width, height);
     }
 
-    pub fn apply<'a>(&'a mut self, target: &TextureView, encoder: &mut CommandEncoder) {
+    /// Applies bloom to the target texture.
+    pub fn apply(&mut self, target: &TextureView, encoder: &mut CommandEncoder) {
+        let mut graph = RenderGraph::new();
+        graph.import("bloom input");
+        self.add_to_graph(&mut graph, target, "bloom input", "bloom output");
+        graph
+            .execute(encoder)
+            .expect("bloom render graph is invalid");
+    }
+
+    /// Adds the bloom passes to a render graph, so they can be ordered with other effects.
+    /// The passes read the `input` resource and write the `output` resource, which both
+    /// refer to the target texture.
+    pub fn add_to_graph<'a>(
+        &'a mut self,
+        graph: &mut RenderGraph<'a>,
+        target: &'a TextureView,
+        input: &str,
+        output: &str,
+    ) {
         self.threshold_bind_group
-            .rebind(&[self.nearest_sampler.bind(), target.bind()]);
+            .rebind(&[self.nearest_sampler.bind(), target.bind()])
+            .expect("bloom target must be a filterable 2D texture");
+        let this: &'a Self = self;
+        let last = this.mips.len() - 1;
 
         // Threshold pass
-        self.threshold_pass(encoder, &self.threshold_bind_group);
+        graph.add_pass("bloom threshold", &[input], &[&down(0)], move |encoder| {
+            this.threshold_pass(encoder, &this.threshold_bind_group)
+        });
 
         // Downsample along down mip chain.
-        self.down_pass(encoder);
+        for mip in 1..=last {
+            graph.add_pass(
+                "bloom down",
+                &[&down(mip - 1)],
+                &[&down(mip)],
+                move |encoder| this.down_pass(encoder, mip),
+            );
+        }
 
-        // Upsample along up mip chain.
-        self.up_pass(encoder);
+        // Upsample along up mip chain, blending into the downsampled mips.
+        for mip in (0..last).rev() {
+            graph.add_pass(
+                "bloom up",
+                &[&up(mip + 1, last), &down(mip)],
+                &[&up(mip, last)],
+                move |encoder| this.up_pass(encoder, mip),
+            );
+        }
 
         // Render to the final target
-        self.add_pass(encoder, target);
+        graph.add_pass("bloom add", &[&up(0, last)], &[output], move |encoder| {
+            this.add_pass(encoder, target)
+        });
     }
 
     fn threshold_pass(&self, encoder: &mut CommandEncoder, bind_group: &BindGroup) {
+        let mut encoder = encoder.debug_group("bloom/threshold");
         let mut r = encoder
             .render_pass(
                 "bloom threshold pass",
@@ -211,50 +271,56 @@ impl Bloom {
             .begin();
         r.set_pipeline(&self.threshold_pipeline);
         r.set_bind_group(0, bind_group, &[]);
-        r.draw_triangle();
+        r.draw_fullscreen();
     }
 
-    fn down_pass(&self, encoder: &mut CommandEncoder) {
-        for (binding, texture) in self.mips[0..self.mips.len() - 1]
-            .iter()
-            .map(|mip| &mip.binding)
-            .zip(self.mips[1..self.mips.len()].iter().map(|mip| &mip.texture))
-        {
-            let mut r = encoder
-                .render_pass("bloom down pass", &[texture.attach_render().clear()])
-                .begin();
-            r.set_pipeline(&self.down_pipeline);
-            r.set_bind_group(0, binding, &[]);
-            r.draw_triangle();
-        }
+    /// Downsamples `mip - 1` into `mip`.
+    fn down_pass(&self, encoder: &mut CommandEncoder, mip: usize) {
+        let mut encoder = encoder.debug_group(&format!("bloom/down {}", mip));
+        let mut r = encoder
+            .render_pass(
+                "bloom down pass",
+                &[self.mips[mip].texture.attach_render().clear()],
+            )
+            .begin();
+        r.set_pipeline(&self.down_pipeline);
+        r.set_bind_group(0, &self.mips[mip - 1].binding, &[]);
+        r.draw_fullscreen();
     }
 
-    fn up_pass(&self, encoder: &mut CommandEncoder) {
-        for (binding, texture) in self.mips[1..self.mips.len()]
-            .iter()
-            .map(|mip| &mip.binding)
-            .zip(
-                self.mips[0..self.mips.len() - 1]
-                    .iter()
-                    .map(|mip| &mip.texture),
-            )
-            .rev()
-        {
-            let mut r = encoder
-                .render_pass("bloom up pass", &[texture.attach_render()])
-                .begin();
-            r.set_pipeline(&self.up_pipeline);
-            r.set_bind_group(0, binding, &[]);
-            r.draw(0..3, 0..1);
-        }
+    /// Upsamples `mip + 1` and blends it into `mip`.
+    fn up_pass(&self, encoder: &mut CommandEncoder, mip: usize) {
+        let mut encoder = encoder.debug_group(&format!("bloom/up {}", mip));
+        let mut r = encoder
+            .render_pass("bloom up pass", &[self.mips[mip].texture.attach_render()])
+            .begin();
+        r.set_pipeline(&self.up_pipeline);
+        r.set_bind_group(0, &self.mips[mip + 1].binding, &[]);
+        r.draw_fullscreen();
     }
 
     fn add_pass(&self, encoder: &mut CommandEncoder, target: &TextureView) {
+        let mut encoder = encoder.debug_group("bloom/add");
         let mut r = encoder
             .render_pass("bloom add pass", &[target.attach_render()])
             .begin();
         r.set_pipeline(&self.add_pipeline);
         r.set_bind_group(0, &self.mips[0].binding, &[]);
-        r.draw_triangle();
+        r.set_bind_group(1, &self.settings_bind_group, &[]);
+        r.draw_fullscreen();
+    }
+}
+
+/// Graph resource for a mip after downsampling.
+fn down(mip: usize) -> String {
+    format!("bloom down {}", mip)
+}
+
+/// Graph resource for a mip after upsampling. The last mip is not upsampled into.
+fn up(mip: usize, last: usize) -> String {
+    if mip == last {
+        down(mip)
+    } else {
+        format!("bloom up {}", mip)
     }
 }
@@ -3,6 +3,12 @@
 layout(set = 0, binding = 0) uniform sampler s;
 layout(set = 0, binding = 1) uniform texture2D t;
 
+layout(set = 1, binding = 0) uniform BloomSettings {
+    vec3 tint;
+    float intensity;
+    float aspect;
+} settings;
+
 layout(location = 0) in vec2 in_uv;
 layout(location = 0) out vec4 outColor;
 
@@ -13,5 +19,5 @@ float max_comp(vec3 v) {
 void main() {
     vec4 sampled = texture(sampler2D(t, s), in_uv);
 
-    outColor = sampled;
-}
\ No newline at end of file
+    outColor = vec4(sampled.rgb * settings.tint * settings.intensity, sampled.a);
+}
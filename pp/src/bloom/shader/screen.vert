@@ -1,10 +0,0 @@
-#version 460
-
-layout (location = 0) out vec2 outUV;
-
-void main() 
-{
-    outUV = vec2((gl_VertexIndex << 1) & 2, gl_VertexIndex & 2);
-    gl_Position = vec4(outUV * 2.0f + -1.0f, 0.0f, 1.0f);
-    outUV = vec2(outUV.x, 1 - outUV.y);
-}
\ No newline at end of file
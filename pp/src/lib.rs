@@ -2,7 +2,8 @@
 //! Some important limitations:
 //! - Swapchain texture must be in BGRA format.
 //! - Texture in BGRA format cannot be bound as storage texture (write in shader).
-//! - Texture cannot be copied to another texture with a different format.
+//! - Texture cannot be copied to another texture with a different format, use `Gpu::blit()`
+//!   or `Gpu::blit_texture()` instead, e.g. to copy an HDR target into the swapchain.
 
 pub mod bloom;
 // pub use Bloom;
@@ -1,4 +1,7 @@
-use std::borrow::Cow;
+use std::{
+    borrow::Cow,
+    ops::{Deref, DerefMut},
+};
 
 use crate::*;
 
@@ -50,6 +53,20 @@ impl<'a> ComputePipelineBuilder<'a> {
         self.bind_group_layouts = bind_groups;
         self
     }
+
+    /// Sets the name of the compute function to run, e.g. to pick one of several
+    /// kernels in the same module. Defaults to `main`.
+    pub const fn with_entry_point(mut self, entry: &'a str) -> Self {
+        self.entry_point = entry;
+        self
+    }
+
+    /// Sets the push constant ranges of the pipeline layout.
+    /// Requires [`wgpu::Features::PUSH_CONSTANTS`].
+    pub fn with_push_constants(mut self, ranges: &'a [wgpu::PushConstantRange]) -> Self {
+        self.push_constant_ranges = ranges;
+        self
+    }
 }
 impl ComputePipelineBuilder<'_> {
     #[must_use]
@@ -151,14 +168,23 @@ pub struct ComputePipeline<'a> {
 wgpu_inner_deref!(ComputePipeline<'_>, ComputePipeline);
 
 impl ComputePipeline<'_> {
-    pub fn dispatch_pass<'a, 'f>(&'a self, c_pass: &'f mut wgpu::ComputePass<'a>, dims: &[u32]) {
+    /// Sets the pipeline on the compute pass, along with its bind groups and
+    /// push constants, if any.
+    pub fn set_on_pass<'a>(&'a self, c_pass: &mut wgpu::ComputePass<'a>) {
         // Set pipeline
         c_pass.set_pipeline(&self.inner);
         // Set bind groups
         for (i, b) in self.bind_groups.iter().enumerate() {
             c_pass.set_bind_group(i as _, &b.inner, &[]);
         }
-        // TODO: Set push constants?
+        // Set push constants
+        if !self.push_constants.is_empty() {
+            c_pass.set_push_constants(0, self.push_constants);
+        }
+    }
+
+    pub fn dispatch_pass<'a, 'f>(&'a self, c_pass: &'f mut wgpu::ComputePass<'a>, dims: &[u32]) {
+        self.set_on_pass(c_pass);
 
         let (x, y, z) = {
             (
@@ -186,4 +212,122 @@ impl ComputePipeline<'_> {
 
         self.dispatch_encoder(&mut encoder, dims);
     }
+
+    /// Dispatches enough workgroups to cover `total` invocations.
+    /// See `workgroup_count()`.
+    pub fn dispatch_for(&self, total: [u32; 3], workgroup_size: [u32; 3]) {
+        self.dispatch(&workgroup_count(total, workgroup_size));
+    }
+}
+impl<'a> ComputePipeline<'a> {
+    /// Sets the push constant data, which is set at offset 0 on dispatch.
+    /// The pipeline must be created with `with_push_constants()`.
+    pub fn with_push_constant_data(mut self, data: &'a [u8]) -> Self {
+        self.push_constants = data;
+        self
+    }
+}
+
+pub struct ComputePassBuilder<'a> {
+    /// Encoder is used to create the compute pass on begin()
+    pub(crate) encoder: &'a mut CommandEncoder,
+    pub(crate) desc: wgpu::ComputePassDescriptor<'a>,
+    /// An optional pipeline that the compute pass will start with
+    pub(crate) init_pipeline: Option<&'a ComputePipeline<'a>>,
+}
+impl<'a> ComputePassBuilder<'a> {
+    pub fn with_pipeline(mut self, pipeline: &'a ComputePipeline<'a>) -> Self {
+        self.init_pipeline = Some(pipeline);
+        self
+    }
+
+    pub fn begin(self) -> ComputePass<'a> {
+        let gpu = self.encoder.gpu.clone();
+        if gpu.profiler.timestamp.is_some() {
+            gpu.begin_profiler_section(self.desc.label.unwrap_or("Compute pass"), self.encoder);
+        }
+
+        let mut pass = ComputePass {
+            inner: self.encoder.begin_compute_pass(&self.desc),
+        };
+        if let Some(pipeline) = self.init_pipeline {
+            pass.set_pipeline(pipeline);
+        }
+        pass
+    }
+}
+
+pub struct ComputePass<'a> {
+    pub inner: wgpu::ComputePass<'a>,
+}
+impl<'a> ComputePass<'a> {
+    /// Sets the pipeline along with its bind groups and push constants.
+    /// See `ComputePipeline::set_on_pass()`.
+    pub fn set_pipeline(&mut self, pipeline: &'a ComputePipeline<'a>) -> &mut Self {
+        pipeline.set_on_pass(&mut self.inner);
+        self
+    }
+
+    /// Shadows wgpu::ComputePass::set_bind_group and returns self for chaining
+    pub fn set_bind_group(
+        &mut self,
+        index: u32,
+        bind_group: &'a wgpu::BindGroup,
+        offsets: &[wgpu::DynamicOffset],
+    ) -> &mut Self {
+        self.inner.set_bind_group(index, bind_group, offsets);
+        self
+    }
+
+    /// Shadows wgpu::ComputePass::dispatch and returns self for chaining
+    pub fn dispatch(&mut self, x: u32, y: u32, z: u32) -> &mut Self {
+        self.inner.dispatch(x, y, z);
+        self
+    }
+
+    /// Dispatches enough workgroups to cover `total` invocations.
+    /// See `workgroup_count()`.
+    pub fn dispatch_for(&mut self, total: [u32; 3], workgroup_size: [u32; 3]) -> &mut Self {
+        let [x, y, z] = workgroup_count(total, workgroup_size);
+        self.dispatch(x, y, z)
+    }
+}
+impl<'a> Deref for ComputePass<'a> {
+    type Target = wgpu::ComputePass<'a>;
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+impl<'a> DerefMut for ComputePass<'a> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.inner
+    }
+}
+
+impl CommandEncoder {
+    /// Creates a compute pass on this encoder.
+    /// Passes recorded on the same encoder execute in the order they are
+    /// recorded, so a compute pass that writes a buffer is guaranteed to finish
+    /// before a later render pass on the encoder reads it.
+    pub fn compute_pass<'a>(&'a mut self, label: &'a str) -> ComputePassBuilder<'a> {
+        ComputePassBuilder {
+            encoder: self,
+            desc: wgpu::ComputePassDescriptor { label: Some(label) },
+            init_pipeline: None,
+        }
+    }
+}
+
+/// The number of workgroups needed to cover `total` invocations with the given
+/// workgroup size, rounded up in each dimension.
+pub const fn workgroup_count(total: [u32; 3], workgroup_size: [u32; 3]) -> [u32; 3] {
+    #[allow(clippy::manual_div_ceil)]
+    const fn ceil_div(total: u32, size: u32) -> u32 {
+        (total + size - 1) / size
+    }
+    [
+        ceil_div(total[0], workgroup_size[0]),
+        ceil_div(total[1], workgroup_size[1]),
+        ceil_div(total[2], workgroup_size[2]),
+    ]
 }
@@ -1,21 +1,31 @@
-//! This example is currently broken because I don't understand the new egui
-//! textures since 0.17
+//! Renders egui with agpu.
+//! The font atlas and user textures are uploaded from egui's `TexturesDelta` on
+//! every `run`.
 
 #![cfg(feature = "egui")]
 
+use std::collections::HashMap;
 use std::ops::{Deref, DerefMut};
 
-use crate::{BindGroup, Buffer, Gpu, RenderPass, RenderPipeline, Sampler};
+use tracing::warn;
+
+use crate::{
+    BindGroup, Buffer, ColorTargetBuilderExt, Gpu, RenderPass, RenderPipeline, Sampler, Texture, D2,
+};
 
 pub struct Egui {
     pub ctx: egui::Context,
     gpu: Gpu,
     vertex_buffers: Vec<(Buffer, Buffer)>,
     ubo_buffer: Buffer,
-    _linear_sampler: Sampler,
+    linear_sampler: Sampler,
     bind_group: BindGroup,
     pipeline: RenderPipeline,
     last_meshes: Vec<egui::epaint::ClippedMesh>,
+    /// The textures egui has set, with the bind group used to draw them
+    textures: HashMap<egui::TextureId, (Texture<D2>, BindGroup)>,
+    /// Textures that egui freed in the last run, removed once they have been drawn
+    pending_free: Vec<egui::TextureId>,
 }
 impl Deref for Egui {
     type Target = egui::Context;
@@ -53,17 +63,23 @@ impl Egui {
             .linear_filter()
             .create();
 
-        // Create the bind group and pipeline
-        let bind_group = gpu.create_bind_group(&[
-            ubo_buffer.bind_uniform(),
-            linear_sampler.bind().in_fragment(),
-        ]);
+        // Create the bind groups and pipeline
+        let bind_group = gpu.create_bind_group(&[ubo_buffer.bind_uniform()]);
+        // Every texture bind group has the same layout as this one
+        let (_, texture_bind_group) =
+            Self::new_texture(&gpu, &linear_sampler, [1, 1], &[egui::Color32::WHITE]);
+        // egui outputs premultiplied colors
+        let format = gpu
+            .preferred_format
+            .unwrap_or(wgpu::TextureFormat::Bgra8UnormSrgb);
+        let targets = [wgpu::ColorTargetState::from(format).blend_over_premult()];
         let pipeline = gpu
             .new_pipeline("EGUI pipeline")
             .with_vertex_layouts(&[Self::VERTEX_LAYOUT])
             .with_fragment(include_bytes!("egui/shader/egui.frag.spv"))
             .with_vertex(include_bytes!("egui/shader/egui.vert.spv"))
-            .with_bind_groups(&[&bind_group.layout])
+            .with_fragment_targets(&targets)
+            .with_bind_groups(&[&bind_group.layout, &texture_bind_group.layout])
             .create();
 
         Self {
@@ -71,11 +87,65 @@ impl Egui {
             gpu,
             vertex_buffers: Vec::new(),
             ubo_buffer,
-            _linear_sampler: linear_sampler,
+            linear_sampler,
             bind_group,
             pipeline,
             last_meshes: Vec::new(),
+            textures: HashMap::new(),
+            pending_free: Vec::new(),
+        }
+    }
+
+    fn new_texture(
+        gpu: &Gpu,
+        sampler: &Sampler,
+        size: [usize; 2],
+        pixels: &[egui::Color32],
+    ) -> (Texture<D2>, BindGroup) {
+        let texture = gpu
+            .new_texture("EGUI texture")
+            .with_format(wgpu::TextureFormat::Rgba8UnormSrgb)
+            .allow_binding()
+            .create((size[0] as u32, size[1] as u32), pixels);
+        let bind_group = gpu.create_bind_group_fragment(&[texture.bind_texture(), sampler.bind()]);
+        (texture, bind_group)
+    }
+
+    /// Applies the texture changes of a run.
+    /// Textures that were freed are kept until the next run, as they may still be
+    /// drawn this frame.
+    fn update_textures(&mut self, delta: egui::TexturesDelta) {
+        for id in self.pending_free.drain(..) {
+            self.textures.remove(&id);
         }
+
+        for (id, image_delta) in delta.set {
+            let pixels: Vec<egui::Color32> = match &image_delta.image {
+                egui::ImageData::Color(image) => image.pixels.clone(),
+                // The font atlas only has coverage, which becomes white with alpha
+                egui::ImageData::Alpha(image) => image.srgba_pixels(1.0).collect(),
+            };
+            let size = image_delta.image.size();
+
+            match (image_delta.pos, self.textures.get(&id)) {
+                // Update a region of an existing texture
+                (Some([x, y]), Some((texture, _))) => texture.write_block(
+                    (x as u32, y as u32),
+                    (size[0] as u32, size[1] as u32),
+                    &pixels,
+                ),
+                (Some(_), None) => {
+                    warn!("egui updated {:?}, which was never set", id);
+                }
+                // Set the whole texture, which may change its size
+                (None, _) => {
+                    let texture = Self::new_texture(&self.gpu, &self.linear_sampler, size, &pixels);
+                    self.textures.insert(id, texture);
+                }
+            }
+        }
+
+        self.pending_free = delta.free;
     }
 
     pub fn resize(&mut self, width: u32, height: u32) {
@@ -89,6 +159,7 @@ impl Egui {
         run_ui: impl FnOnce(&egui::Context),
     ) -> egui::PlatformOutput {
         let output = self.ctx.run(new_input, run_ui);
+        self.update_textures(output.textures_delta);
         let meshes = self.ctx.tessellate(output.shapes);
         self.update_buffers(&meshes);
         self.last_meshes = meshes;
@@ -143,8 +214,13 @@ impl Egui {
         for (egui::ClippedMesh(clip_rect, mesh), (vb, ib)) in
             self.last_meshes.iter().zip(&self.vertex_buffers)
         {
+            let texture_bind_group = match self.textures.get(&mesh.texture_id) {
+                Some((_, bind_group)) => bind_group,
+                None => continue,
+            };
             if let Some((x, y, width, height)) = render_region(clip_rect, width, height) {
                 render_pass
+                    .set_bind_group(1, texture_bind_group, &[])
                     .set_scissor_rect(x, y, width, height)
                     .set_vertex_buffer(0, vb.slice(..))
                     .set_index_buffer_u32(ib.slice(..))
@@ -4,11 +4,12 @@ layout (location = 0) in vec2 in_pos;
 layout (location = 1) in vec2 in_uv;
 layout (location = 2) in vec4 in_color;
 
-// layout (set = 0, binding = 1) uniform texture2D tex;
-// layout (set = 0, binding = 2) uniform sampler s;
+layout (set = 1, binding = 0) uniform texture2D tex;
+layout (set = 1, binding = 1) uniform sampler s;
 
 layout (location = 0) out vec4 out_color;
 
 void main() {
-    out_color = in_color ;
+    // The color is premultiplied, as is the texture
+    out_color = in_color * texture(sampler2D(tex, s), in_uv);
 }
@@ -18,11 +18,19 @@ out gl_PerVertex {
     vec4 gl_Position;
 };
 
+// egui vertex colors are in gamma space, but we render to an srgb target
+vec3 linear_from_srgb(vec3 srgb) {
+    bvec3 cutoff = lessThan(srgb, vec3(0.04045));
+    vec3 lower = srgb / vec3(12.92);
+    vec3 higher = pow((srgb + vec3(0.055)) / vec3(1.055), vec3(2.4));
+    return mix(higher, lower, cutoff);
+}
+
 void main() {
     vec2 xy = (in_pos / viewport.size) * 2 - 1;
     gl_Position = vec4(xy, 0, 1); 
     gl_Position.y = - gl_Position.y;
     out_pos = in_pos;
     out_uv = in_uv;
-    out_color = in_color;
+    out_color = vec4(linear_from_srgb(in_color.rgb), in_color.a);
 }
@@ -19,5 +19,16 @@ pub use profiler::*;
 pub mod pipeline;
 pub use pipeline::*;
 
+pub mod render_graph;
+pub use render_graph::*;
+
+pub mod command_list;
+pub use command_list::*;
+
+#[cfg(feature = "nalgebra")]
+pub mod camera;
+#[cfg(feature = "nalgebra")]
+pub use camera::*;
+
 pub mod texture;
 pub use texture::*;
@@ -6,13 +6,23 @@ pub use binding::*;
 
 mod view;
 use futures::executor::block_on;
+use tracing::warn;
 pub use view::*;
 
 mod vertex_layout;
 pub use vertex_layout::*;
 
-use crate::Gpu;
-use std::ops::Deref;
+mod dynamic_uniform;
+pub use dynamic_uniform::*;
+
+mod vertex_buffer;
+pub use vertex_buffer::*;
+
+mod staging_belt;
+pub use staging_belt::*;
+
+use crate::{CommandEncoder, Gpu, GpuError};
+use std::{future::Future, ops::Deref};
 
 /// * Probably best used as `RefCell<Buffer>`
 pub struct Buffer {
@@ -21,6 +31,9 @@ pub struct Buffer {
     pub(crate) inner: wgpu::Buffer,
     pub(crate) usages: wgpu::BufferUsages,
     pub size: u64,
+    /// The format of the indices, if this is an index buffer with a known format.
+    /// Used by `RenderPass::set_index_buffer_auto()`.
+    pub index_format: Option<wgpu::IndexFormat>,
 }
 /// Allows you to use this as a reference to the inner `wgpu::Buffer`
 impl Deref for Buffer {
@@ -33,16 +46,152 @@ impl Deref for Buffer {
 impl Buffer {
     /// # Errors
     /// Errors according to [`wgpu::BufferAsyncError`]
+    /// Blocks until the download is complete. See `download_async()` for a
+    /// non-blocking download.
     pub fn download_immediately(
         &self,
     ) -> Result<wgpu::util::DownloadBuffer, wgpu::BufferAsyncError> {
-        let fut = wgpu::util::DownloadBuffer::read_buffer(
+        let fut = self.download_async();
+        self.gpu.poll(wgpu::Maintain::Wait);
+        block_on(fut)
+    }
+
+    /// Starts downloading the contents of the buffer.
+    /// The returned future only makes progress when the device is polled, so
+    /// call `Gpu::poll_once()` (e.g. once per frame) to keep rendering while the
+    /// download completes, or `Gpu::poll(wgpu::Maintain::Wait)` to block.
+    pub fn download_async(
+        &self,
+    ) -> impl Future<Output = Result<wgpu::util::DownloadBuffer, wgpu::BufferAsyncError>> + Send
+    {
+        wgpu::util::DownloadBuffer::read_buffer(
             &self.gpu.device,
             &self.gpu.queue,
             &self.inner.slice(..),
+        )
+    }
+
+    /// Downloads only the given byte range of the buffer.
+    /// The buffer must have `COPY_SRC` usage, and the range must be within the
+    /// buffer and aligned to `wgpu::COPY_BUFFER_ALIGNMENT`.
+    /// # Errors
+    /// Errors if the buffer lacks `COPY_SRC`, if the range is invalid, or
+    /// according to [`wgpu::BufferAsyncError`]
+    pub fn read_range(
+        &self,
+        range: std::ops::Range<u64>,
+    ) -> Result<wgpu::util::DownloadBuffer, GpuError> {
+        if !self.usages.contains(wgpu::BufferUsages::COPY_SRC) {
+            return Err(GpuError::MissingBufferUsage(wgpu::BufferUsages::COPY_SRC));
+        }
+        let misaligned = (range.start | range.end) & (wgpu::COPY_BUFFER_ALIGNMENT - 1) != 0;
+        if range.start >= range.end || range.end > self.size || misaligned {
+            return Err(GpuError::InvalidBufferRange(range));
+        }
+
+        let fut = wgpu::util::DownloadBuffer::read_buffer(
+            &self.gpu.device,
+            &self.gpu.queue,
+            &self.inner.slice(range),
         );
         self.gpu.poll(wgpu::Maintain::Wait);
-        block_on(fut)
+        block_on(fut).map_err(|_| GpuError::BufferAsyncError)
+    }
+
+    /// Zeroes the whole buffer when the encoder is submitted, without uploading any
+    /// data from the CPU, e.g. to reset atomic counters between compute dispatches.
+    /// The buffer must have `COPY_DST` usage, see `allow_copy_to()`.
+    /// # Errors
+    /// Errors if the buffer lacks `COPY_DST`
+    pub fn clear(&self, encoder: &mut CommandEncoder) -> Result<(), GpuError> {
+        self.clear_range(encoder, 0, self.size)
+    }
+
+    /// Zeroes `size` bytes of the buffer starting at `offset` when the encoder is
+    /// submitted. See `clear()`. The range must be within the buffer and aligned to
+    /// `wgpu::COPY_BUFFER_ALIGNMENT`.
+    /// # Errors
+    /// Errors if the buffer lacks `COPY_DST`, or if the range is invalid
+    pub fn clear_range(
+        &self,
+        encoder: &mut CommandEncoder,
+        offset: u64,
+        size: u64,
+    ) -> Result<(), GpuError> {
+        if !self.usages.contains(wgpu::BufferUsages::COPY_DST) {
+            return Err(GpuError::MissingBufferUsage(wgpu::BufferUsages::COPY_DST));
+        }
+        let range = offset..offset + size;
+        let misaligned = (range.start | range.end) & (wgpu::COPY_BUFFER_ALIGNMENT - 1) != 0;
+        if range.start >= range.end || range.end > self.size || misaligned {
+            return Err(GpuError::InvalidBufferRange(range));
+        }
+
+        encoder.clear_buffer(&self.inner, offset, std::num::NonZeroU64::new(size));
+        Ok(())
+    }
+
+    /// Maps the byte range of the buffer for writing, calls `f` with the mapped bytes,
+    /// then unmaps the buffer. Unlike `write()`, this writes directly to the buffer
+    /// instead of copying through a staging buffer, which suits large uploads.
+    /// The buffer must have `MAP_WRITE` usage, which can only be combined with
+    /// `COPY_SRC`. The range must start at a multiple of `wgpu::MAP_ALIGNMENT` and
+    /// end at a multiple of `wgpu::COPY_BUFFER_ALIGNMENT`.
+    /// Blocks until the buffer is mapped.
+    /// # Errors
+    /// Errors if the buffer lacks `MAP_WRITE`, if the range is invalid, or if the
+    /// buffer could not be mapped
+    pub fn with_mapped_write<R>(
+        &self,
+        range: std::ops::Range<u64>,
+        f: impl FnOnce(&mut [u8]) -> R,
+    ) -> Result<R, GpuError> {
+        let slice = self.map_range(range, wgpu::MapMode::Write)?;
+        let result = f(&mut slice.get_mapped_range_mut());
+        self.inner.unmap();
+        Ok(result)
+    }
+
+    /// Maps the byte range of the buffer for reading, calls `f` with the mapped bytes,
+    /// then unmaps the buffer. See `with_mapped_write()`.
+    /// The buffer must have `MAP_READ` usage, which can only be combined with `COPY_DST`.
+    /// # Errors
+    /// Errors if the buffer lacks `MAP_READ`, if the range is invalid, or if the
+    /// buffer could not be mapped
+    pub fn with_mapped_read<R>(
+        &self,
+        range: std::ops::Range<u64>,
+        f: impl FnOnce(&[u8]) -> R,
+    ) -> Result<R, GpuError> {
+        let slice = self.map_range(range, wgpu::MapMode::Read)?;
+        let result = f(&slice.get_mapped_range());
+        self.inner.unmap();
+        Ok(result)
+    }
+
+    fn map_range(
+        &self,
+        range: std::ops::Range<u64>,
+        mode: wgpu::MapMode,
+    ) -> Result<wgpu::BufferSlice<'_>, GpuError> {
+        let usage = match mode {
+            wgpu::MapMode::Read => wgpu::BufferUsages::MAP_READ,
+            wgpu::MapMode::Write => wgpu::BufferUsages::MAP_WRITE,
+        };
+        if !self.usages.contains(usage) {
+            return Err(GpuError::MissingBufferUsage(usage));
+        }
+        let misaligned = range.start & (wgpu::MAP_ALIGNMENT - 1) != 0
+            || range.end & (wgpu::COPY_BUFFER_ALIGNMENT - 1) != 0;
+        if range.start >= range.end || range.end > self.size || misaligned {
+            return Err(GpuError::InvalidBufferRange(range));
+        }
+
+        let slice = self.inner.slice(range);
+        let mapping = slice.map_async(mode);
+        self.gpu.poll(wgpu::Maintain::Wait);
+        block_on(mapping).map_err(|_| GpuError::BufferAsyncError)?;
+        Ok(slice)
     }
 
     pub fn size(&self) -> usize {
@@ -57,7 +206,19 @@ impl Buffer {
     {
         let data = bytemuck::cast_slice(data);
 
-        self.resize_if_smaller(data.len() as u64, true);
+        // The old contents are overwritten, so they do not need to be copied
+        self.resize_if_smaller(data.len() as u64, false);
+        self.write_impl(0, data);
+    }
+
+    /// Writes raw bytes to the buffer, e.g. a uniform block that was packed by hand.
+    /// Use `write()` for data of a single `Pod` type.
+    /// Resizes the buffer if the data is larger than the current buffer size.
+    /// The length must be a multiple of `wgpu::COPY_BUFFER_ALIGNMENT` (4 bytes), and the
+    /// bytes must already be laid out as the shader expects, e.g. with std140 padding.
+    pub fn write_bytes(&mut self, data: &[u8]) {
+        // The old contents are overwritten, so they do not need to be copied
+        self.resize_if_smaller(data.len() as u64, false);
         self.write_impl(0, data);
     }
 
@@ -127,8 +288,19 @@ impl Buffer {
     /// Currently the encoder is immediately submitted which may cause a
     /// performance hit
     fn resize_impl(&mut self, size: u64, copy: bool) {
-        if copy {
-            self.usages |= wgpu::BufferUsages::COPY_DST
+        // The new buffer is copied or written into
+        self.usages |= wgpu::BufferUsages::COPY_DST;
+        // The old contents can only be copied out with COPY_SRC
+        let copy = copy && self.size > 0;
+        let copy = if copy && !self.usages.contains(wgpu::BufferUsages::COPY_SRC) {
+            warn!(
+                "Buffer {:?} is missing COPY_SRC usage, so its contents are discarded on resize. \
+                Create it with allow_copy_from() to keep them",
+                self.label
+            );
+            false
+        } else {
+            copy
         };
         // Create the new buffer
         let new_buffer = self.gpu.device.create_buffer(&wgpu::BufferDescriptor {
@@ -142,7 +314,8 @@ impl Buffer {
             let mut encoder = self
                 .gpu
                 .create_command_encoder("agpu::Buffer::resize_impl() command encoder");
-            self.copy_to(&mut encoder, &new_buffer);
+            // Only the part that fits is kept if the buffer shrinks
+            encoder.copy_buffer_to_buffer(&self.inner, 0, &new_buffer, 0, self.size.min(size));
             self.gpu.queue.submit([encoder.finish()]);
         };
         // Destroy the old buffer
@@ -153,10 +326,21 @@ impl Buffer {
         self.size = size;
     }
 
+    /// Resizes the buffer, keeping the contents that fit.
+    /// The contents can only be kept if the buffer has `COPY_SRC` usage
+    /// (see `BufferBuilder::allow_copy_from()`), otherwise they are discarded
+    /// with a warning. `COPY_DST` is added to the usage.
     pub fn resize(&mut self, size: u64) {
         self.resize_impl(size, true);
     }
 
+    /// Grows the buffer to at least `size` bytes, keeping its contents, e.g. before
+    /// appending to it. Does nothing if the buffer is already large enough.
+    /// Returns whether the buffer was resized. See `resize()`.
+    pub fn grow(&mut self, size: u64) -> bool {
+        self.resize_if_smaller(size, true)
+    }
+
     pub fn copy_to(&self, encoder: &mut wgpu::CommandEncoder, target: &wgpu::Buffer) {
         encoder.copy_buffer_to_buffer(&self.inner, 0, target, 0, self.size);
     }
@@ -2,7 +2,7 @@ use std::{ops::Deref, rc::Rc};
 
 use wgpu::BindGroupEntry;
 
-use crate::Gpu;
+use crate::{Gpu, GpuError};
 
 impl crate::Buffer {
     /// Create a uniform buffer binding.
@@ -79,6 +79,62 @@ impl crate::Buffer {
             resource: self.as_entire_binding(),
         }
     }
+
+    /// Create a uniform buffer binding of `size` bytes starting at `offset`.
+    /// Useful for suballocating several uniform blocks from one buffer.
+    /// # Errors
+    /// Errors if the range is out of bounds, empty, or if `offset` is not a multiple
+    /// of the device's `min_uniform_buffer_offset_alignment`
+    pub fn bind_uniform_range(&self, offset: u64, size: u64) -> Result<Binding<'_>, GpuError> {
+        let alignment = self.gpu.device.limits().min_uniform_buffer_offset_alignment;
+        Ok(Binding {
+            gpu: &self.gpu,
+            visibility: Binding::DEFAULT_VISIBILITY,
+            ty: wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Uniform,
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            },
+            resource: self.range_binding(offset, size, alignment)?,
+        })
+    }
+
+    /// Create a storage buffer binding of `size` bytes starting at `offset`.
+    /// # Errors
+    /// Errors if the range is out of bounds, empty, or if `offset` is not a multiple
+    /// of the device's `min_storage_buffer_offset_alignment`
+    pub fn bind_storage_range(&self, offset: u64, size: u64) -> Result<Binding<'_>, GpuError> {
+        let alignment = self.gpu.device.limits().min_storage_buffer_offset_alignment;
+        Ok(Binding {
+            gpu: &self.gpu,
+            visibility: Binding::DEFAULT_VISIBILITY,
+            ty: wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Storage { read_only: false },
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            },
+            resource: self.range_binding(offset, size, alignment)?,
+        })
+    }
+
+    fn range_binding(
+        &self,
+        offset: u64,
+        size: u64,
+        alignment: u32,
+    ) -> Result<wgpu::BindingResource<'_>, GpuError> {
+        let range = offset..offset.saturating_add(size);
+        let misaligned = offset & (alignment as u64 - 1) != 0;
+        let size = match std::num::NonZeroU64::new(size) {
+            Some(size) if range.end <= self.size && !misaligned => size,
+            _ => return Err(GpuError::InvalidBufferRange(range)),
+        };
+        Ok(wgpu::BindingResource::Buffer(wgpu::BufferBinding {
+            buffer: &self.inner,
+            offset,
+            size: Some(size),
+        }))
+    }
 }
 
 impl<D> crate::Texture<D>
@@ -93,12 +149,7 @@ where
             visibility: Binding::DEFAULT_VISIBILITY,
             ty: wgpu::BindingType::Texture {
                 sample_type: sample_type(*self.format),
-                // TODO: different texture view reps?
-                view_dimension: match self.size.dim() {
-                    wgpu::TextureDimension::D1 => wgpu::TextureViewDimension::D1,
-                    wgpu::TextureDimension::D2 => wgpu::TextureViewDimension::D2,
-                    wgpu::TextureDimension::D3 => wgpu::TextureViewDimension::D3,
-                },
+                view_dimension: self.size.view_dim(),
                 multisampled: false,
             },
             resource: wgpu::BindingResource::TextureView(&self.view),
@@ -111,27 +162,199 @@ where
         self.bind_texture()
     }
 
-    /// Create a storage texture binding.
-    // Can be const following RFC 2632
-    pub fn bind_storage_texture(&self) -> Binding {
-        Binding {
+    /// Create a depth texture binding, e.g. for sampling a shadow map.
+    /// This is bound in the shader as `texture_depth_2d`, and can be sampled with
+    /// `textureSampleCompare()` using a sampler created with `comparator()`, which
+    /// `Sampler::bind()` binds as `sampler_comparison`.
+    /// # Errors
+    /// Errors with `GpuError::UnsupportedFormat` if the format is not `Depth32Float`
+    /// or `Depth24Plus`. Formats with a stencil aspect can not be sampled through
+    /// the texture's view, which includes the stencil aspect.
+    pub fn bind_depth(&self) -> Result<Binding<'_>, GpuError> {
+        match *self.format {
+            wgpu::TextureFormat::Depth32Float | wgpu::TextureFormat::Depth24Plus => {}
+            format => return Err(GpuError::UnsupportedFormat(format)),
+        }
+        Ok(Binding {
+            gpu: &self.gpu,
+            visibility: Binding::DEFAULT_VISIBILITY,
+            ty: wgpu::BindingType::Texture {
+                sample_type: wgpu::TextureSampleType::Depth,
+                view_dimension: self.size.view_dim(),
+                multisampled: false,
+            },
+            resource: wgpu::BindingResource::TextureView(&self.view),
+        })
+    }
+
+    /// Create a write-only storage texture binding.
+    /// Write-only is the most portable access mode, as read access is not
+    /// supported for many formats and backends.
+    /// # Errors
+    /// Errors with `GpuError::UnsupportedFormat` if the format cannot be used
+    /// as a storage texture.
+    pub fn bind_storage_texture(&self) -> Result<Binding<'_>, GpuError> {
+        self.bind_storage_texture_impl(wgpu::StorageTextureAccess::WriteOnly)
+    }
+
+    /// Alias for `bind_storage_texture()`.
+    /// # Errors
+    /// See `bind_storage_texture()`.
+    pub fn bind_storage_texture_writeonly(&self) -> Result<Binding<'_>, GpuError> {
+        self.bind_storage_texture_impl(wgpu::StorageTextureAccess::WriteOnly)
+    }
+
+    /// Create a read-only storage texture binding.
+    /// # Errors
+    /// Errors with `GpuError::UnsupportedFormat` if the format does not support
+    /// read access as a storage texture. Read access is never supported unless the
+    /// `Gpu` is created with `TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES`.
+    pub fn bind_storage_texture_readonly(&self) -> Result<Binding<'_>, GpuError> {
+        self.bind_storage_texture_impl(wgpu::StorageTextureAccess::ReadOnly)
+    }
+
+    /// Create a read-write storage texture binding.
+    /// # Errors
+    /// Errors with `GpuError::UnsupportedFormat` if the format does not support
+    /// read access as a storage texture. Read access is never supported unless the
+    /// `Gpu` is created with `TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES`.
+    pub fn bind_storage_texture_readwrite(&self) -> Result<Binding<'_>, GpuError> {
+        self.bind_storage_texture_impl(wgpu::StorageTextureAccess::ReadWrite)
+    }
+
+    fn bind_storage_texture_impl(
+        &self,
+        access: wgpu::StorageTextureAccess,
+    ) -> Result<Binding<'_>, GpuError> {
+        let format = *self.format;
+        let features = self.gpu.format_features(format);
+        let storage = features
+            .allowed_usages
+            .contains(wgpu::TextureUsages::STORAGE_BINDING);
+        // Read access requires an additional format feature
+        let readable = access == wgpu::StorageTextureAccess::WriteOnly
+            || features
+                .flags
+                .contains(wgpu::TextureFormatFeatureFlags::STORAGE_READ_WRITE);
+        if !storage || !readable {
+            return Err(GpuError::UnsupportedFormat(format));
+        }
+
+        Ok(Binding {
             gpu: &self.gpu,
             visibility: Binding::DEFAULT_VISIBILITY,
             ty: wgpu::BindingType::StorageTexture {
-                // TODO: different texture view reps?
-                view_dimension: match self.size.dim() {
-                    wgpu::TextureDimension::D1 => wgpu::TextureViewDimension::D1,
-                    wgpu::TextureDimension::D2 => wgpu::TextureViewDimension::D2,
-                    wgpu::TextureDimension::D3 => wgpu::TextureViewDimension::D3,
-                },
-                access: wgpu::StorageTextureAccess::ReadWrite,
-                format: *self.format,
+                view_dimension: self.size.view_dim(),
+                access,
+                format,
+            },
+            resource: wgpu::BindingResource::TextureView(&self.view),
+        })
+    }
+}
+
+impl crate::Texture<crate::D2Array> {
+    /// Create a texture array binding.
+    /// This is bound in the shader as `texture_2d_array`.
+    pub fn bind_texture_array(&self) -> Binding<'_> {
+        Binding {
+            gpu: &self.gpu,
+            visibility: Binding::DEFAULT_VISIBILITY,
+            ty: wgpu::BindingType::Texture {
+                sample_type: sample_type(*self.format),
+                view_dimension: wgpu::TextureViewDimension::D2Array,
+                multisampled: false,
             },
             resource: wgpu::BindingResource::TextureView(&self.view),
         }
     }
 }
 
+/// The views of an array of textures, to be bound as a `binding_array`.
+/// See `Gpu::create_texture_array_binding()`.
+pub struct TextureBindingArray<'a> {
+    gpu: &'a Gpu,
+    views: Vec<&'a wgpu::TextureView>,
+    sample_type: wgpu::TextureSampleType,
+}
+impl TextureBindingArray<'_> {
+    /// Create the binding of the array.
+    /// This is bound in the shader as `binding_array<texture_2d<f32>, N>` in WGSL or
+    /// `texture2D textures[N]` in GLSL, where `N` is the number of textures.
+    pub fn bind(&self) -> Binding<'_> {
+        Binding {
+            gpu: self.gpu,
+            visibility: Binding::DEFAULT_VISIBILITY,
+            ty: wgpu::BindingType::Texture {
+                sample_type: self.sample_type,
+                view_dimension: wgpu::TextureViewDimension::D2,
+                multisampled: false,
+            },
+            resource: wgpu::BindingResource::TextureViewArray(&self.views),
+        }
+    }
+
+    /// The number of textures in the array
+    pub fn len(&self) -> usize {
+        self.views.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.views.is_empty()
+    }
+}
+
+impl Gpu {
+    /// Collects the textures into an array that is bound as a single binding, e.g. to
+    /// draw many materials in one draw call instead of one bind group per material.
+    /// The views are borrowed by the returned array, which creates the binding with
+    /// `bind()`.
+    ///
+    /// Requires `Features::TEXTURE_BINDING_ARRAY`. Indexing the array with a value that
+    /// differs between invocations additionally requires
+    /// `Features::SAMPLED_TEXTURE_AND_STORAGE_BUFFER_ARRAY_NON_UNIFORM_INDEXING`.
+    /// Where these are not supported, fall back to a `Texture<D2Array>` with a layer per
+    /// texture, bound with `bind_texture_array()`, which also requires the textures to
+    /// share a format and size.
+    /// # Errors
+    /// Errors with `MissingFeature` if the feature is not enabled, or with
+    /// `ValidationError` if there are no textures or they do not share a format and size
+    pub fn create_texture_array_binding<'a>(
+        &'a self,
+        textures: &[&'a crate::Texture<crate::D2>],
+    ) -> Result<TextureBindingArray<'a>, GpuError> {
+        if !self.has_feature(wgpu::Features::TEXTURE_BINDING_ARRAY) {
+            return Err(GpuError::MissingFeature(
+                wgpu::Features::TEXTURE_BINDING_ARRAY,
+            ));
+        }
+        let first = match textures.first() {
+            Some(first) => first,
+            None => {
+                return Err(GpuError::ValidationError(
+                    "texture binding array is empty".to_owned(),
+                ))
+            }
+        };
+        if let Some(texture) = textures
+            .iter()
+            .find(|t| t.format != first.format || t.size != first.size)
+        {
+            return Err(GpuError::ValidationError(format!(
+                "texture of format {:?} and size {:?} does not match format {:?} and size {:?} \
+                of the first texture in the array",
+                *texture.format, texture.size, *first.format, first.size
+            )));
+        }
+
+        Ok(TextureBindingArray {
+            gpu: self,
+            views: textures.iter().map(|t| &t.view).collect(),
+            sample_type: sample_type(*first.format),
+        })
+    }
+}
+
 macro_rules! gen_binding_vis_fn {
     ($($fn_name:ident => $stage:ident),*) => {
         $(
@@ -161,6 +384,22 @@ impl Binding<'_> {
         in_vertex_fragment => VERTEX_FRAGMENT
     );
 
+    /// The number of array elements of the binding, for array resources
+    fn count(&self) -> Option<std::num::NonZeroU32> {
+        match self.resource {
+            wgpu::BindingResource::TextureViewArray(views) => {
+                std::num::NonZeroU32::new(views.len() as u32)
+            }
+            wgpu::BindingResource::SamplerArray(samplers) => {
+                std::num::NonZeroU32::new(samplers.len() as u32)
+            }
+            wgpu::BindingResource::BufferArray(buffers) => {
+                std::num::NonZeroU32::new(buffers.len() as u32)
+            }
+            _ => None,
+        }
+    }
+
     pub const fn buffer_dynamic_offset(mut self) -> Self {
         if let wgpu::BindingType::Buffer {
             ty,
@@ -243,6 +482,8 @@ impl BindGroupLayout {
             gpu: self.gpu.clone(),
             layout: self.clone(),
             inner: bind_group,
+            binding_indices: (0..bindings.len() as u32).collect(),
+            binding_types: bindings.iter().map(|b| b.ty).collect(),
         }
     }
 }
@@ -252,38 +493,52 @@ pub struct BindGroup {
     gpu: crate::Gpu,
     pub layout: BindGroupLayout,
     pub inner: wgpu::BindGroup,
+    /// The binding index of each entry, in the order they were given
+    binding_indices: Vec<u32>,
+    /// The binding type of each entry, used to validate `rebind()`
+    binding_types: Vec<wgpu::BindingType>,
 }
 crate::wgpu_inner_deref!(BindGroup);
 
 impl BindGroup {
+    /// Creates a bind group where each binding index is its position in the slice.
+    /// See `BindGroupBuilder` for explicit binding indices.
     pub fn new(gpu: crate::Gpu, bindings: &[Binding]) -> Self {
+        Self::new_labeled(gpu, None, bindings)
+    }
+
+    /// Creates a bind group like `new()`, with a label for the group and its layout
+    /// that is shown in debuggers such as RenderDoc.
+    pub fn new_labeled(gpu: crate::Gpu, label: Option<&str>, bindings: &[Binding]) -> Self {
+        let indices = (0..bindings.len() as u32).collect::<Vec<_>>();
+        Self::new_indexed(gpu, label, &indices, bindings)
+    }
+
+    fn new_indexed(
+        gpu: crate::Gpu,
+        label: Option<&str>,
+        indices: &[u32],
+        bindings: &[Binding],
+    ) -> Self {
         let bind_group_layout = gpu.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
-            label: None,
-            entries: bindings
+            label,
+            entries: indices
                 .iter()
-                .enumerate()
-                .map(|(i, binding)| wgpu::BindGroupLayoutEntry {
-                    binding: i as _,
+                .zip(bindings)
+                .map(|(&i, binding)| wgpu::BindGroupLayoutEntry {
+                    binding: i,
                     visibility: binding.visibility,
                     ty: binding.ty,
-                    count: None,
+                    count: binding.count(),
                 })
                 .collect::<Vec<_>>()
                 .as_slice(),
         });
 
         let bind_group = gpu.device.create_bind_group(&wgpu::BindGroupDescriptor {
-            label: None,
+            label,
             layout: &bind_group_layout,
-            entries: bindings
-                .iter()
-                .enumerate()
-                .map(|(i, b)| BindGroupEntry {
-                    binding: i as _,
-                    resource: b.resource.clone(),
-                })
-                .collect::<Vec<_>>()
-                .as_slice(),
+            entries: &Self::entries(indices, bindings),
         });
         let layout = BindGroupLayout::from_wgpu(gpu.clone(), bind_group_layout);
 
@@ -291,10 +546,24 @@ impl BindGroup {
             gpu,
             layout,
             inner: bind_group,
+            binding_indices: indices.to_vec(),
+            binding_types: bindings.iter().map(|b| b.ty).collect(),
         }
     }
 
+    fn entries<'a>(indices: &[u32], bindings: &[Binding<'a>]) -> Vec<BindGroupEntry<'a>> {
+        indices
+            .iter()
+            .zip(bindings)
+            .map(|(&i, b)| BindGroupEntry {
+                binding: i,
+                resource: b.resource.clone(),
+            })
+            .collect()
+    }
+
     // Creates a new bind group with the same layout as this one, but with the given bindings.
+    // The bindings use the same binding indices as this bind group.
     pub fn instance(&self, bindings: &[Binding]) -> Self {
         let bind_group = self
             .gpu
@@ -302,49 +571,119 @@ impl BindGroup {
             .create_bind_group(&wgpu::BindGroupDescriptor {
                 label: None,
                 layout: &self.layout,
-                entries: bindings
-                    .iter()
-                    .enumerate()
-                    .map(|(i, b)| BindGroupEntry {
-                        binding: i as _,
-                        resource: b.resource.clone(),
-                    })
-                    .collect::<Vec<_>>()
-                    .as_slice(),
+                entries: &Self::entries(&self.binding_indices, bindings),
             });
 
         BindGroup {
             gpu: self.gpu.clone(),
             layout: self.layout.clone(),
             inner: bind_group,
+            binding_indices: self.binding_indices.clone(),
+            binding_types: self.binding_types.clone(),
         }
     }
 
-    // Recreates the bind group with the same layout inplace, but with the given bindings.
-    pub fn rebind(&mut self, bindings: &[Binding]) -> &Self {
+    /// Recreates the bind group with the same layout inplace, but with the given bindings.
+    /// The bindings use the same binding indices as this bind group.
+    /// # Errors
+    /// Errors with `ValidationError` if the number of bindings or the type of any
+    /// binding differs from the bindings the bind group was created with
+    pub fn rebind(&mut self, bindings: &[Binding]) -> Result<&Self, GpuError> {
+        if bindings.len() != self.binding_types.len() {
+            return Err(GpuError::ValidationError(format!(
+                "rebind expected {} bindings but got {}",
+                self.binding_types.len(),
+                bindings.len()
+            )));
+        }
+        for ((index, expected), binding) in self
+            .binding_indices
+            .iter()
+            .zip(&self.binding_types)
+            .zip(bindings)
+        {
+            if binding.ty != *expected {
+                return Err(GpuError::ValidationError(format!(
+                    "rebind binding {} has type {:?} but the layout expects {:?}",
+                    index, binding.ty, expected
+                )));
+            }
+        }
+
         self.inner = self
             .gpu
             .device
             .create_bind_group(&wgpu::BindGroupDescriptor {
                 label: None,
                 layout: &self.layout,
-                entries: bindings
-                    .iter()
-                    .enumerate()
-                    .map(|(i, b)| BindGroupEntry {
-                        binding: i as _,
-                        resource: b.resource.clone(),
-                    })
-                    .collect::<Vec<_>>()
-                    .as_slice(),
+                entries: &Self::entries(&self.binding_indices, bindings),
             });
-        self
+        Ok(self)
+    }
+
+    /// The binding index of each entry
+    pub fn binding_indices(&self) -> &[u32] {
+        &self.binding_indices
     }
 }
 impl crate::Gpu {
     pub fn create_bind_group(&self, bindings: &[Binding]) -> BindGroup {
         BindGroup::new(self.clone(), bindings)
     }
+
+    /// Creates a bind group with a label for the group and its layout, which makes
+    /// it identifiable in debuggers such as RenderDoc.
+    pub fn create_bind_group_labeled(&self, label: &str, bindings: &[Binding]) -> BindGroup {
+        BindGroup::new_labeled(self.clone(), Some(label), bindings)
+    }
+
+    /// Creates a bind group where every binding is only visible to the fragment stage,
+    /// overriding the visibility of each binding. This is shorthand for calling
+    /// `in_fragment()` on every binding, e.g. for a texture and its sampler.
+    pub fn create_bind_group_fragment(&self, bindings: &[Binding]) -> BindGroup {
+        let bindings = bindings
+            .iter()
+            .cloned()
+            .map(Binding::in_fragment)
+            .collect::<Vec<_>>();
+        self.create_bind_group(&bindings)
+    }
+
+    /// Build a bind group with explicit binding indices.
+    pub fn build_bind_group<'a>(&self) -> BindGroupBuilder<'a> {
+        BindGroupBuilder {
+            gpu: self.clone(),
+            label: None,
+            indices: Vec::new(),
+            bindings: Vec::new(),
+        }
+    }
+}
+
+/// Builds a bind group where each binding has an explicit binding index,
+/// e.g. for shaders with non-contiguous binding numbers.
+pub struct BindGroupBuilder<'a> {
+    gpu: Gpu,
+    label: Option<&'a str>,
+    indices: Vec<u32>,
+    bindings: Vec<Binding<'a>>,
+}
+impl<'a> BindGroupBuilder<'a> {
+    pub fn with_label(mut self, label: &'a str) -> Self {
+        self.label = Some(label);
+        self
+    }
+
+    /// Adds a binding at the given binding index.
+    pub fn binding(mut self, index: u32, binding: Binding<'a>) -> Self {
+        self.indices.push(index);
+        self.bindings.push(binding);
+        self
+    }
+
+    pub fn create(&self) -> BindGroup {
+        BindGroup::new_indexed(self.gpu.clone(), self.label, &self.indices, &self.bindings)
+    }
 }
 pub trait BindingsExt {
     fn create_group(&self) -> BindGroup;
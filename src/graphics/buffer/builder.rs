@@ -1,8 +1,42 @@
+use std::any::TypeId;
+
 use bytemuck::Pod;
 use wgpu::util::DeviceExt;
 
 use crate::{Buffer, Gpu};
 
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// Index types, and arrays of them, whose format `BufferBuilder::create_indices()`
+/// can infer.
+pub trait IndexElement: Pod + sealed::Sealed {
+    const FORMAT: wgpu::IndexFormat;
+}
+impl sealed::Sealed for u16 {}
+impl IndexElement for u16 {
+    const FORMAT: wgpu::IndexFormat = wgpu::IndexFormat::Uint16;
+}
+impl sealed::Sealed for u32 {}
+impl IndexElement for u32 {
+    const FORMAT: wgpu::IndexFormat = wgpu::IndexFormat::Uint32;
+}
+impl<const N: usize> sealed::Sealed for [u16; N] {}
+impl<const N: usize> IndexElement for [u16; N]
+where
+    Self: Pod,
+{
+    const FORMAT: wgpu::IndexFormat = wgpu::IndexFormat::Uint16;
+}
+impl<const N: usize> sealed::Sealed for [u32; N] {}
+impl<const N: usize> IndexElement for [u32; N]
+where
+    Self: Pod,
+{
+    const FORMAT: wgpu::IndexFormat = wgpu::IndexFormat::Uint32;
+}
+
 pub enum BufferInitContent<'a> {
     /// The buffer will be initialized with the given data
     Data(&'a [u8]),
@@ -14,6 +48,9 @@ pub struct BufferBuilder<'a> {
     pub gpu: Gpu,
     pub label: Option<&'a str>,
     pub usage: wgpu::BufferUsages,
+    /// The format of the indices if this is an index buffer.
+    /// If not set, `create()` infers it if the contents are `u16` or `u32`.
+    pub index_format: Option<wgpu::IndexFormat>,
 }
 impl<'a> BufferBuilder<'a> {
     #[must_use]
@@ -22,6 +59,7 @@ impl<'a> BufferBuilder<'a> {
             gpu,
             label: Some(label),
             usage: wgpu::BufferUsages::empty(),
+            index_format: None,
         }
     }
 
@@ -32,11 +70,26 @@ impl<'a> BufferBuilder<'a> {
     }
 
     /// Allow a buffer to be the index buffer in a draw operation.
+    /// The index format is inferred in `create()` if the contents are `u16` or `u32`,
+    /// and in `create_indices()` for arrays of them such as triangles. Otherwise it
+    /// must be set with `as_index_buffer_u16()` or `as_index_buffer_u32()`.
     pub fn as_index_buffer(mut self) -> Self {
         self.usage |= wgpu::BufferUsages::INDEX;
         self
     }
 
+    /// Allow a buffer to be the index buffer in a draw operation, with u16 indices.
+    pub fn as_index_buffer_u16(mut self) -> Self {
+        self.index_format = Some(wgpu::IndexFormat::Uint16);
+        self.as_index_buffer()
+    }
+
+    /// Allow a buffer to be the index buffer in a draw operation, with u32 indices.
+    pub fn as_index_buffer_u32(mut self) -> Self {
+        self.index_format = Some(wgpu::IndexFormat::Uint32);
+        self.as_index_buffer()
+    }
+
     /// Allow a buffer to be the vertex buffer in a draw operation.
     pub fn as_vertex_buffer(mut self) -> Self {
         self.usage |= wgpu::BufferUsages::VERTEX;
@@ -142,12 +195,60 @@ impl<'a> BufferBuilder<'a> {
         let (inner, size) =
             self.create_impl(BufferInitContent::Data(bytemuck::cast_slice(contents)));
 
+        // Infer the index format from the type of the indices. Only the index types
+        // themselves are inferred, since the size of any other type is ambiguous,
+        // e.g. `[u16; 2]` has the size of a `u32`
+        let index_format = self.index_format.or_else(|| {
+            if !self.usage.contains(wgpu::BufferUsages::INDEX) {
+                return None;
+            }
+            let ty = TypeId::of::<T>();
+            if ty == TypeId::of::<u16>() {
+                Some(wgpu::IndexFormat::Uint16)
+            } else if ty == TypeId::of::<u32>() {
+                Some(wgpu::IndexFormat::Uint32)
+            } else {
+                None
+            }
+        });
+
+        Buffer {
+            inner,
+            gpu: self.gpu.clone(),
+            size,
+            label: self.label.map(|a| a.to_string()).unwrap_or_default(),
+            usages: self.usage,
+            index_format,
+        }
+    }
+
+    /// Creates an index buffer with the format of `I`, e.g. from a slice of
+    /// `[u16; 3]` triangles. An explicit format set on the builder takes precedence.
+    #[must_use]
+    pub fn create_indices<I>(mut self, indices: &[I]) -> Buffer
+    where
+        I: IndexElement,
+    {
+        self.index_format = self.index_format.or(Some(I::FORMAT));
+        self.as_index_buffer().create(indices)
+    }
+
+    /// Creates the buffer from raw bytes, e.g. a uniform block that was packed by hand
+    /// to the std140 layout. Use `create()` for data of a single `Pod` type.
+    /// The bytes are uploaded as is, so they must already be laid out as the shader
+    /// expects. The buffer is padded up to `wgpu::COPY_BUFFER_ALIGNMENT` (4 bytes).
+    /// The index format is not inferred, see `as_index_buffer_u16()` and `as_index_buffer_u32()`.
+    #[must_use]
+    pub fn create_bytes(&self, contents: &[u8]) -> Buffer {
+        let (inner, size) = self.create_impl(BufferInitContent::Data(contents));
+
         Buffer {
             inner,
             gpu: self.gpu.clone(),
             size,
             label: self.label.map(|a| a.to_string()).unwrap_or_default(),
             usages: self.usage,
+            index_format: self.index_format,
         }
     }
 
@@ -162,6 +263,7 @@ impl<'a> BufferBuilder<'a> {
             size,
             label: self.label.map(|a| a.to_string()).unwrap_or_default(),
             usages: self.usage,
+            index_format: self.index_format,
         }
     }
 
@@ -171,4 +273,31 @@ impl<'a> BufferBuilder<'a> {
     {
         self.create_uninit((count * std::mem::size_of::<T>()) as u64)
     }
+
+    /// Builds a buffer of `count` elements of `T`, with its contents zeroed.
+    /// `COPY_DST` is added to the usage so the buffer can be written to or reset later,
+    /// e.g. for compute output or scratch buffers.
+    #[must_use]
+    pub fn create_zeroed<T>(&self, count: usize) -> Buffer
+    where
+        T: Pod,
+    {
+        let size = (count * std::mem::size_of::<T>()) as u64;
+        let usage = self.usage | wgpu::BufferUsages::COPY_DST;
+        let inner = self.gpu.device.create_buffer(&wgpu::BufferDescriptor {
+            label: self.label,
+            size,
+            usage,
+            mapped_at_creation: false,
+        });
+
+        Buffer {
+            inner,
+            gpu: self.gpu.clone(),
+            size,
+            label: self.label.map(|a| a.to_string()).unwrap_or_default(),
+            usages: usage,
+            index_format: self.index_format,
+        }
+    }
 }
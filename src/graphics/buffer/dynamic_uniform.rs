@@ -0,0 +1,133 @@
+use std::cell::Cell;
+use std::marker::PhantomData;
+
+use crate::{Binding, Buffer, Gpu};
+
+/// Rounds `size` up to the next multiple of `alignment`.
+/// `alignment` must be a power of two.
+pub const fn align_to(size: u64, alignment: u64) -> u64 {
+    (size + alignment - 1) & !(alignment - 1)
+}
+
+/// A uniform buffer holding many values of `T`, each bound with a dynamic offset.
+/// This lets many objects share a single buffer and bind group.
+///
+/// Values are pushed one after another until the capacity is reached. Call
+/// `reset()` at the start of each frame to start from the beginning again.
+/// Pushing does not wrap around, since the writes only happen on the next submit,
+/// and would overwrite values that earlier draws in the same frame still use.
+pub struct DynamicUniform<T> {
+    pub buffer: Buffer,
+    /// The distance in bytes between two values, aligned to the device's
+    /// `min_uniform_buffer_offset_alignment`
+    pub stride: u64,
+    pub capacity: u32,
+    /// Index of the next value to push
+    cursor: Cell<u32>,
+    _marker: PhantomData<T>,
+}
+impl<T> DynamicUniform<T>
+where
+    T: bytemuck::Pod,
+{
+    /// Writes the value into the next slot, and returns the dynamic offset to
+    /// pass to `set_bind_group()`.
+    /// Returns `None` without writing if every slot has been pushed since the last
+    /// `reset()`.
+    pub fn push(&self, value: T) -> Option<u32> {
+        let index = self.cursor.get();
+        if index >= self.capacity {
+            return None;
+        }
+        self.cursor.set(index + 1);
+
+        let offset = self.offset_of(index);
+        self.buffer
+            .gpu
+            .queue
+            .write_buffer(&self.buffer, offset as u64, bytemuck::bytes_of(&value));
+        Some(offset)
+    }
+
+    /// The dynamic offset of the value at the given index.
+    pub const fn offset_of(&self, index: u32) -> u32 {
+        (index as u64 * self.stride) as u32
+    }
+
+    /// Starts pushing from the beginning of the buffer again.
+    pub fn reset(&self) {
+        self.cursor.set(0);
+    }
+
+    /// Create a uniform binding with a dynamic offset, sized to a single `T`.
+    #[must_use]
+    pub fn bind(&self) -> Binding<'_> {
+        let size = std::num::NonZeroU64::new(std::mem::size_of::<T>() as u64);
+        Binding {
+            gpu: &self.buffer.gpu,
+            visibility: Binding::DEFAULT_VISIBILITY,
+            ty: wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Uniform,
+                has_dynamic_offset: true,
+                min_binding_size: size,
+            },
+            resource: wgpu::BindingResource::Buffer(wgpu::BufferBinding {
+                buffer: &self.buffer,
+                offset: 0,
+                size,
+            }),
+        }
+    }
+}
+
+pub struct DynamicUniformBuilder<'a, T> {
+    gpu: Gpu,
+    label: &'a str,
+    capacity: u32,
+    _marker: PhantomData<T>,
+}
+impl<'a, T> DynamicUniformBuilder<'a, T>
+where
+    T: bytemuck::Pod,
+{
+    /// Sets the number of values the buffer can hold. Defaults to 1.
+    /// A capacity of 0 is treated as 1, since the buffer must hold a value to bind.
+    pub const fn capacity(mut self, capacity: u32) -> Self {
+        self.capacity = if capacity == 0 { 1 } else { capacity };
+        self
+    }
+
+    pub fn create(&self) -> DynamicUniform<T> {
+        let alignment = self.gpu.device.limits().min_uniform_buffer_offset_alignment as u64;
+        let stride = align_to(std::mem::size_of::<T>() as u64, alignment);
+
+        let buffer = self
+            .gpu
+            .new_buffer(self.label)
+            .as_uniform_buffer()
+            .allow_copy_to()
+            .create_uninit(stride * self.capacity as u64);
+
+        DynamicUniform {
+            buffer,
+            stride,
+            capacity: self.capacity,
+            cursor: Cell::new(0),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl Gpu {
+    pub fn new_dynamic_uniform<'a, T>(&self, label: &'a str) -> DynamicUniformBuilder<'a, T>
+    where
+        T: bytemuck::Pod,
+    {
+        DynamicUniformBuilder {
+            gpu: self.clone(),
+            label,
+            capacity: 1,
+            _marker: PhantomData,
+        }
+    }
+}
@@ -0,0 +1,85 @@
+use std::num::NonZeroU64;
+
+use futures::executor::LocalPool;
+use futures::task::LocalSpawnExt;
+
+use crate::{Buffer, CommandEncoder};
+
+/// Batches many small buffer writes into copies from a few reusable staging
+/// buffers. See [`wgpu::util::StagingBelt`].
+///
+/// The `Gpu` owns a belt which is accessible with `Gpu::staging_belt()`.
+/// Using it goes as follows:
+/// - Write to buffers with `write_buffer()`.
+/// - Call `finish()`.
+/// - Submit the encoders that were written to.
+/// - Call `recall()`. This **must** happen after the submit, otherwise the
+///   staging buffers are still in use by the pending copies.
+pub struct StagingBelt {
+    inner: wgpu::util::StagingBelt,
+    /// Drives the recall futures, which complete once the gpu is done with the
+    /// staging buffers
+    pool: LocalPool,
+}
+impl StagingBelt {
+    /// The default size of a single staging buffer.
+    pub const DEFAULT_CHUNK_SIZE: u64 = 1 << 20;
+
+    /// Creates a belt which allocates staging buffers of `chunk_size` bytes.
+    /// Writes larger than this get their own staging buffer.
+    pub fn new(chunk_size: u64) -> Self {
+        Self {
+            inner: wgpu::util::StagingBelt::new(chunk_size),
+            pool: LocalPool::new(),
+        }
+    }
+
+    /// Records a copy of the data into the target buffer at the given byte offset.
+    /// The offset and size of the data must be multiples of
+    /// `wgpu::COPY_BUFFER_ALIGNMENT`, and the target must have `COPY_DST` usage.
+    pub fn write_buffer<T>(
+        &mut self,
+        encoder: &mut CommandEncoder,
+        target: &Buffer,
+        offset: u64,
+        data: &[T],
+    ) where
+        T: bytemuck::Pod,
+    {
+        let data = bytemuck::cast_slice(data);
+        let size = match NonZeroU64::new(data.len() as u64) {
+            Some(size) => size,
+            None => return,
+        };
+        self.inner
+            .write_buffer(encoder, target, offset, size, &target.gpu.device)
+            .copy_from_slice(data);
+    }
+
+    /// Closes the staging buffers that have been written to.
+    /// Must be called before submitting the encoders.
+    pub fn finish(&mut self) {
+        self.inner.finish();
+    }
+
+    /// Reclaims the staging buffers that the gpu is done with.
+    /// Must be called after submitting the encoders.
+    pub fn recall(&mut self) {
+        self.pool
+            .spawner()
+            .spawn_local(self.inner.recall())
+            .expect("Spawn staging belt recall");
+        self.pool.run_until_stalled();
+    }
+}
+impl Default for StagingBelt {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_CHUNK_SIZE)
+    }
+}
+
+impl std::fmt::Debug for StagingBelt {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "agpu StagingBelt")
+    }
+}
@@ -0,0 +1,94 @@
+use std::marker::PhantomData;
+use std::ops::Deref;
+
+use crate::{Buffer, Gpu, VertexLayout};
+
+/// A vertex buffer of `V`, which carries the vertex layout of `V`.
+/// The pipeline's layout can be taken from the buffer with `layout()`, and the buffer
+/// bound with `RenderPass::set_typed_vertex_buffer()`, so that the two always agree.
+pub struct VertexBuffer<V> {
+    pub buffer: Buffer,
+    /// The number of vertices last written, which may be less than the buffer holds
+    len: u32,
+    _marker: PhantomData<V>,
+}
+impl<V> VertexBuffer<V>
+where
+    V: VertexLayout + bytemuck::Pod,
+{
+    /// The vertex buffer layout of `V`, starting at shader location `L`.
+    pub fn layout<const L: u32>(&self) -> wgpu::VertexBufferLayout<'static> {
+        V::vertex_buffer_layout::<L>()
+    }
+
+    /// The number of vertices in the buffer, as of the last `create()` or `write()`.
+    pub fn len(&self) -> u32 {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Writes the vertices to the buffer, resizing it if they do not fit.
+    /// The buffer is not shrunk if there are fewer vertices, but `len()` is updated.
+    /// Requires the buffer to be created with `allow_copy_to()`.
+    pub fn write(&mut self, vertices: &[V]) {
+        self.buffer.write(vertices);
+        self.len = vertices.len() as u32;
+    }
+}
+/// Allows the typed buffer to be used as a `Buffer`, e.g. for `slice()`
+impl<V> Deref for VertexBuffer<V> {
+    type Target = Buffer;
+
+    fn deref(&self) -> &Self::Target {
+        &self.buffer
+    }
+}
+
+pub struct VertexBufferBuilder<'a, V> {
+    gpu: Gpu,
+    label: &'a str,
+    usage: wgpu::BufferUsages,
+    _marker: PhantomData<V>,
+}
+impl<'a, V> VertexBufferBuilder<'a, V>
+where
+    V: VertexLayout + bytemuck::Pod,
+{
+    /// Allow the vertices to be written after creation.
+    pub fn allow_copy_to(mut self) -> Self {
+        self.usage |= wgpu::BufferUsages::COPY_DST;
+        self
+    }
+
+    #[must_use]
+    pub fn create(&self, vertices: &[V]) -> VertexBuffer<V> {
+        let buffer = self
+            .gpu
+            .new_buffer(self.label)
+            .with_usage(self.usage)
+            .create(vertices);
+
+        VertexBuffer {
+            buffer,
+            len: vertices.len() as u32,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl Gpu {
+    pub fn new_vertex_buffer<'a, V>(&self, label: &'a str) -> VertexBufferBuilder<'a, V>
+    where
+        V: VertexLayout + bytemuck::Pod,
+    {
+        VertexBufferBuilder {
+            gpu: self.clone(),
+            label,
+            usage: wgpu::BufferUsages::VERTEX,
+            _marker: PhantomData,
+        }
+    }
+}
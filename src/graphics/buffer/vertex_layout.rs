@@ -6,6 +6,89 @@ pub trait VertexLayout {
     fn vertex_buffer_layout<const L: u32>() -> wgpu::VertexBufferLayout<'static>;
 }
 
+/// Collects the layouts of several vertex buffers, so that their attributes use
+/// consecutive shader locations without computing the start of each buffer by hand.
+/// The layouts are generated at shader location 0 and offset by the number of
+/// attributes of the previous buffers, since `vertex_buffer_layout()` only takes the
+/// start location as a const generic.
+/// ```ignore
+/// // Vertex fields at locations 0.., instance fields right after them
+/// let layouts = VertexLayouts::new().add::<Vertex>().add_instance::<Instance>();
+/// let layouts = layouts.layouts();
+/// let pipeline = gpu.new_pipeline("").with_vertex_layouts(&layouts).create();
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct VertexLayouts {
+    buffers: Vec<VertexBufferAttributes>,
+    next_location: u32,
+}
+
+#[derive(Debug, Clone)]
+struct VertexBufferAttributes {
+    array_stride: wgpu::BufferAddress,
+    step_mode: wgpu::VertexStepMode,
+    attributes: Vec<wgpu::VertexAttribute>,
+}
+
+impl VertexLayouts {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the layout of the next vertex buffer, with the step mode of `V`.
+    #[must_use]
+    pub fn add<V: VertexLayout>(self) -> Self {
+        let layout = V::vertex_buffer_layout::<0>();
+        self.push(layout.step_mode, layout)
+    }
+
+    /// Adds the layout of the next vertex buffer, stepped per instance regardless of
+    /// the step mode of `V`.
+    #[must_use]
+    pub fn add_instance<V: VertexLayout>(self) -> Self {
+        self.push(
+            wgpu::VertexStepMode::Instance,
+            V::vertex_buffer_layout::<0>(),
+        )
+    }
+
+    fn push(mut self, step_mode: wgpu::VertexStepMode, layout: wgpu::VertexBufferLayout) -> Self {
+        let attributes = layout
+            .attributes
+            .iter()
+            .map(|attribute| wgpu::VertexAttribute {
+                shader_location: attribute.shader_location + self.next_location,
+                ..*attribute
+            })
+            .collect::<Vec<_>>();
+        self.next_location += attributes.len() as u32;
+        self.buffers.push(VertexBufferAttributes {
+            array_stride: layout.array_stride,
+            step_mode,
+            attributes,
+        });
+        self
+    }
+
+    /// The shader location that the attributes of the next buffer will start at.
+    pub fn next_location(&self) -> u32 {
+        self.next_location
+    }
+
+    /// The layouts for `PipelineBuilder::with_vertex_layouts()`, in the order they
+    /// were added, which is also their vertex buffer slot.
+    pub fn layouts(&self) -> Vec<wgpu::VertexBufferLayout<'_>> {
+        self.buffers
+            .iter()
+            .map(|buffer| wgpu::VertexBufferLayout {
+                array_stride: buffer.array_stride,
+                step_mode: buffer.step_mode,
+                attributes: &buffer.attributes,
+            })
+            .collect()
+    }
+}
+
 pub trait VertexLayoutImpl {
     fn vertex_buffer_layout<const L: u32>() -> wgpu::VertexBufferLayout<'static>;
 }
@@ -0,0 +1,122 @@
+use nalgebra::{Matrix4, Orthographic3, Perspective3, Point3, Vector3};
+
+/// Converts nalgebra's OpenGL clip space, where depth is in `-1..1`,
+/// to wgpu's clip space, where depth is in `0..1`.
+#[rustfmt::skip]
+const OPENGL_TO_WGPU: Matrix4<f32> = Matrix4::new(
+    1.0, 0.0, 0.0, 0.0,
+    0.0, 1.0, 0.0, 0.0,
+    0.0, 0.0, 0.5, 0.5,
+    0.0, 0.0, 0.0, 1.0,
+);
+
+#[derive(Debug, Clone, Copy)]
+enum Projection {
+    Perspective(Perspective3<f32>),
+    Orthographic(Orthographic3<f32>),
+}
+
+/// A right-handed camera producing a combined view-projection matrix.
+///
+/// The matrix can be uploaded directly to a uniform buffer with `as_uniform_bytes()`:
+/// ```ignore
+/// let mut camera = Camera::perspective(45.0_f32.to_radians(), aspect, 1.0, 10.0);
+/// camera.look_at([1.5, -5.0, 3.0].into(), Point3::origin(), Vector3::z());
+/// let uniform = gpu.new_buffer("camera").as_uniform_buffer().allow_copy_to()
+///     .create(camera.as_uniform_bytes());
+///
+/// program.on_resize(move |_, width, height| {
+///     camera.update_aspect(width as f32 / height as f32);
+///     uniform.write_unchecked(camera.as_uniform_bytes());
+/// });
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Camera {
+    projection: Projection,
+    view: Matrix4<f32>,
+    view_projection: Matrix4<f32>,
+}
+
+impl Camera {
+    /// Creates a camera with a perspective projection.
+    /// `fov` is the vertical field of view in radians.
+    pub fn perspective(fov: f32, aspect: f32, near: f32, far: f32) -> Self {
+        Self::new(Projection::Perspective(Perspective3::new(
+            aspect, fov, near, far,
+        )))
+    }
+
+    /// Creates a camera with an orthographic projection `height` units tall.
+    /// The width is `height * aspect`, so that the height is kept on resize.
+    pub fn orthographic(height: f32, aspect: f32, near: f32, far: f32) -> Self {
+        let (half_width, half_height) = (height * aspect * 0.5, height * 0.5);
+        Self::new(Projection::Orthographic(Orthographic3::new(
+            -half_width,
+            half_width,
+            -half_height,
+            half_height,
+            near,
+            far,
+        )))
+    }
+
+    fn new(projection: Projection) -> Self {
+        let mut camera = Self {
+            projection,
+            view: Matrix4::identity(),
+            view_projection: Matrix4::identity(),
+        };
+        camera.update();
+        camera
+    }
+
+    /// Points the camera at `target` from `eye`.
+    pub fn look_at(
+        &mut self,
+        eye: Point3<f32>,
+        target: Point3<f32>,
+        up: Vector3<f32>,
+    ) -> &mut Self {
+        self.view = Matrix4::look_at_rh(&eye, &target, &up);
+        self.update();
+        self
+    }
+
+    /// Sets the aspect ratio (width / height) of the projection.
+    /// Call this when the viewport is resized.
+    pub fn update_aspect(&mut self, aspect: f32) -> &mut Self {
+        match &mut self.projection {
+            Projection::Perspective(p) => p.set_aspect(aspect),
+            Projection::Orthographic(o) => {
+                let half_width = (o.top() - o.bottom()) * aspect * 0.5;
+                o.set_left_and_right(-half_width, half_width);
+            }
+        }
+        self.update();
+        self
+    }
+
+    /// The view matrix
+    pub fn view(&self) -> &Matrix4<f32> {
+        &self.view
+    }
+
+    /// The combined view-projection matrix
+    pub fn matrix(&self) -> &Matrix4<f32> {
+        &self.view_projection
+    }
+
+    /// The view-projection matrix as column-major bytes, as expected by a
+    /// `mat4x4<f32>` uniform.
+    pub fn as_uniform_bytes(&self) -> &[u8] {
+        bytemuck::cast_slice(self.view_projection.as_slice())
+    }
+
+    fn update(&mut self) {
+        let projection = match &self.projection {
+            Projection::Perspective(p) => p.to_homogeneous(),
+            Projection::Orthographic(o) => o.to_homogeneous(),
+        };
+        self.view_projection = OPENGL_TO_WGPU * projection * self.view;
+    }
+}
@@ -0,0 +1,53 @@
+use crate::{CommandEncoder, Gpu};
+
+/// Commands that are recorded again each time the list is submitted.
+///
+/// wgpu command buffers are consumed on submit and cannot be reused, so there is
+/// nothing to cache between submits; the list only stores the code that records
+/// the commands, and replays it into a fresh encoder. For draws that stay the same
+/// every frame, record a render bundle once with `Gpu::new_render_bundle()` and
+/// execute it in the recording with `RenderPass::execute_bundle()`, which skips
+/// validating the draws again.
+/// ```ignore
+/// let mut bundle = gpu.new_render_bundle("scene", &[format]).begin();
+/// bundle.set_pipeline(&pipeline).draw_triangle();
+/// let bundle = bundle.finish();
+/// let scene = gpu.new_command_list("scene", move |encoder| {
+///     encoder.render_pass("scene pass", &targets).begin().execute_bundle(&bundle);
+/// });
+/// // Each frame
+/// scene.submit();
+/// ```
+pub struct CommandList<'a> {
+    gpu: Gpu,
+    label: String,
+    record: Box<dyn Fn(&mut CommandEncoder) + 'a>,
+}
+
+impl<'a> CommandList<'a> {
+    /// Records the commands into `encoder`, alongside any other commands in it.
+    pub fn record(&self, encoder: &mut CommandEncoder) {
+        (self.record)(encoder);
+    }
+
+    /// Records the commands into a new encoder and submits it.
+    pub fn submit(&self) {
+        let mut encoder = self.gpu.create_command_encoder(&self.label);
+        self.record(&mut encoder);
+    }
+}
+
+impl Gpu {
+    /// Creates a command list that calls `record` each time it is submitted.
+    pub fn new_command_list<'a>(
+        &self,
+        label: &str,
+        record: impl Fn(&mut CommandEncoder) + 'a,
+    ) -> CommandList<'a> {
+        CommandList {
+            gpu: self.clone(),
+            label: label.to_owned(),
+            record: Box::new(record),
+        }
+    }
+}
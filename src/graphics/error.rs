@@ -8,13 +8,84 @@ pub enum GpuError {
     SurfaceError(wgpu::SurfaceError),
     BufferAsyncError,
     QueryNone,
+    /// The buffer was created without a usage that the operation requires
+    MissingBufferUsage(wgpu::BufferUsages),
+    /// The byte range is out of bounds or not aligned as the operation requires
+    InvalidBufferRange(std::ops::Range<u64>),
+    /// wgpu validation failed when creating a resource
+    ValidationError(String),
+    /// Reading the texture back from the gpu failed
+    TextureReadError,
+    /// The texture format is not supported by the operation or the adapter
+    UnsupportedFormat(wgpu::TextureFormat),
+    /// The operation requires features that are not enabled on the device
+    MissingFeature(wgpu::Features),
+    /// A binding could not be reflected from the shader
+    ReflectionError(String),
+    /// The render graph has a missing, duplicate or cyclic dependency
+    InvalidRenderGraph(String),
+    /// An image could not be loaded or decoded
+    #[cfg(feature = "image")]
+    ImageError(image::ImageError),
 }
 impl std::fmt::Display for GpuError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::result::Result<(), std::fmt::Error> {
-        write!(f, "{:?}", self)
+        match self {
+            Self::AdapterNone => write!(f, "no suitable graphics adapter was found"),
+            Self::ShaderParseError => write!(f, "failed to load or parse shader"),
+            Self::RequestDeviceError(e) => write!(f, "failed to request device: {}", e),
+            Self::DisplayNone => write!(f, "no display is available"),
+            Self::SurfaceError(e) => write!(f, "failed to get surface texture: {}", e),
+            Self::BufferAsyncError => write!(f, "failed to map buffer"),
+            Self::QueryNone => write!(f, "query set was not enabled"),
+            Self::MissingBufferUsage(usage) => {
+                write!(f, "buffer is missing required usage {:?}", usage)
+            }
+            Self::InvalidBufferRange(range) => {
+                write!(f, "buffer range {:?} is out of bounds or misaligned", range)
+            }
+            Self::ValidationError(e) => write!(f, "validation error: {}", e),
+            Self::TextureReadError => write!(f, "failed to read texture from the gpu"),
+            Self::UnsupportedFormat(format) => {
+                write!(f, "texture format {:?} is not supported", format)
+            }
+            Self::MissingFeature(features) => {
+                write!(f, "device is missing required features {:?}", features)
+            }
+            Self::ReflectionError(e) => write!(f, "shader reflection failed: {}", e),
+            Self::InvalidRenderGraph(e) => write!(f, "invalid render graph: {}", e),
+            #[cfg(feature = "image")]
+            Self::ImageError(e) => write!(f, "failed to load image: {}", e),
+        }
+    }
+}
+impl std::error::Error for GpuError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::RequestDeviceError(e) => Some(e),
+            Self::SurfaceError(e) => Some(e),
+            #[cfg(feature = "image")]
+            Self::ImageError(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+impl From<wgpu::SurfaceError> for GpuError {
+    fn from(e: wgpu::SurfaceError) -> Self {
+        Self::SurfaceError(e)
+    }
+}
+impl From<wgpu::RequestDeviceError> for GpuError {
+    fn from(e: wgpu::RequestDeviceError) -> Self {
+        Self::RequestDeviceError(e)
+    }
+}
+#[cfg(feature = "image")]
+impl From<image::ImageError> for GpuError {
+    fn from(e: image::ImageError) -> Self {
+        Self::ImageError(e)
     }
 }
-impl std::error::Error for GpuError {}
 
 /// Generic error type for any error.
 /// Recommended to use with terminal errors only, which are expected to be displayed and not handled.
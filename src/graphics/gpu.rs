@@ -4,10 +4,14 @@ pub use builder::GpuBuilder;
 pub use wgpu::Backends;
 pub use wgpu::Features;
 
-use crate::{BufferBuilder, GpuError, Profiler, ViewportBuilder};
+use crate::{
+    BlitCache, BufferBuilder, DebugGroupGuard, GpuError, PipelineBuilder, PipelineCache,
+    ProfileScope, Profiler, RenderPipeline, StagingBelt, TexturePool, ViewportBuilder,
+};
 use core::mem::ManuallyDrop;
 use raw_window_handle::HasRawWindowHandle;
 use std::{
+    cell::{RefCell, RefMut},
     ops::{Deref, DerefMut},
     rc::Rc,
 };
@@ -29,6 +33,14 @@ pub struct GpuCtx {
     pub queue: wgpu::Queue,
     pub profiler: Profiler,
     pub preferred_format: Option<wgpu::TextureFormat>,
+    /// Render pipelines created with `Gpu::new_pipeline_cached()`
+    pub pipeline_cache: PipelineCache,
+    /// Shared staging belt, see `Gpu::staging_belt()`
+    pub staging_belt: RefCell<StagingBelt>,
+    /// Pipelines created with `Gpu::blit()`
+    pub blit_cache: BlitCache,
+    /// Textures released by `Gpu::acquire_texture()`
+    pub texture_pool: TexturePool,
 }
 impl GpuCtx {
     #[allow(clippy::new_ret_no_self)]
@@ -92,6 +104,107 @@ impl Gpu {
         crate::pipeline::PipelineBuilder::new(self.clone(), label)
     }
 
+    /// Creates a pipeline that runs the fragment shader over the whole target, for
+    /// post-processing effects. It uses the default screen vertex shader, see
+    /// `SCREEN_VERTEX_SHADER`, and targets the preferred surface format.
+    /// The fragment entry point must be `main`.
+    /// The bind group layouts are reflected from the shader, see
+    /// `PipelineBuilder::with_auto_layout()`. Draw it with `RenderPass::draw_fullscreen()`.
+    /// Use `new_pipeline()` for other targets or explicit bind group layouts, which
+    /// also defaults to the screen vertex shader.
+    #[must_use]
+    pub fn fullscreen_pipeline(&self, fragment: &'static [u8]) -> RenderPipeline {
+        let builder = self
+            .new_pipeline("Fullscreen pipeline")
+            .with_fragment(fragment)
+            .with_auto_layout();
+        match self.preferred_format {
+            Some(format) => builder.with_fragment_targets(&[format.into()]).create(),
+            None => builder.create(),
+        }
+    }
+
+    /// Creates the pipeline from the builder, or returns the existing pipeline
+    /// if an identical one was already created and is still alive.
+    #[must_use]
+    pub fn new_pipeline_cached(&self, builder: &PipelineBuilder) -> Rc<RenderPipeline> {
+        self.pipeline_cache.get_or_create(builder)
+    }
+
+    /// Borrows the shared staging belt for batched buffer writes.
+    /// See `StagingBelt` for how it must be used around submits.
+    /// # Panics
+    /// Panics if the belt is already borrowed.
+    pub fn staging_belt(&self) -> RefMut<'_, StagingBelt> {
+        self.staging_belt.borrow_mut()
+    }
+
+    /// Polls the device, which runs buffer mapping callbacks and cleans up
+    /// resources. `Maintain::Wait` blocks until all submitted work is done,
+    /// while `Maintain::Poll` returns immediately.
+    pub fn poll(&self, maintain: wgpu::Maintain) {
+        self.device.poll(maintain);
+    }
+
+    /// Polls the device without blocking. See `poll()`.
+    pub fn poll_once(&self) {
+        self.poll(wgpu::Maintain::Poll);
+    }
+
+    /// The features enabled on the device. These are the features that were granted,
+    /// which include the required features and the optional features supported by the
+    /// adapter, see `GpuBuilder::with_optional_features()`.
+    pub fn features(&self) -> wgpu::Features {
+        self.device.features()
+    }
+
+    /// Whether all of the given features are enabled on the device.
+    /// See `features()`.
+    pub fn has_feature(&self, features: wgpu::Features) -> bool {
+        self.features().contains(features)
+    }
+
+    /// The limits granted to the device, which are the requested limits rather than
+    /// the best limits of the adapter. See `GpuBuilder::with_limits()`.
+    pub fn limits(&self) -> wgpu::Limits {
+        self.device.limits()
+    }
+
+    /// Returns the features of a texture format that can be used on the device.
+    /// Adapter specific features are only used if the device has
+    /// `TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES` enabled, otherwise these are the
+    /// features guaranteed by the WebGPU spec.
+    pub fn format_features(&self, format: wgpu::TextureFormat) -> wgpu::TextureFormatFeatures {
+        if self.has_feature(wgpu::Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES) {
+            self.adapter.get_texture_format_features(format)
+        } else {
+            format.describe().guaranteed_format_features
+        }
+    }
+
+    /// Whether textures of the format can be bound as storage textures.
+    pub fn supports_storage(&self, format: wgpu::TextureFormat) -> bool {
+        self.format_features(format)
+            .allowed_usages
+            .contains(wgpu::TextureUsages::STORAGE_BINDING)
+    }
+
+    /// Whether textures of the format can be sampled with a filtering sampler.
+    pub fn supports_filtering(&self, format: wgpu::TextureFormat) -> bool {
+        self.format_features(format).filterable
+    }
+
+    /// Whether the format can be used as a render target with the given sample count.
+    /// wgpu does not report per-format sample counts, so only the counts guaranteed
+    /// by the WebGPU spec (1 and 4) are considered supported.
+    pub fn supports_msaa(&self, format: wgpu::TextureFormat, samples: u32) -> bool {
+        let renderable = self
+            .format_features(format)
+            .allowed_usages
+            .contains(wgpu::TextureUsages::RENDER_ATTACHMENT);
+        renderable && (samples == 1 || samples == 4)
+    }
+
     pub fn create_command_encoder(&self, label: &str) -> CommandEncoder {
         let inner = self
             .device
@@ -103,6 +216,16 @@ impl Gpu {
         }
     }
 
+    /// Finishes the encoders and submits them with a single `queue.submit()`, instead
+    /// of one submit per encoder when they are dropped.
+    /// The command buffers execute in the order of `encoders`, so work recorded in an
+    /// earlier encoder is visible to later ones, and the whole batch executes after
+    /// everything submitted before it.
+    pub fn submit_batch(&self, encoders: Vec<CommandEncoder>) {
+        self.queue
+            .submit(encoders.into_iter().map(CommandEncoder::finish));
+    }
+
     pub(crate) fn begin_profiler_section<'a>(
         &self,
         label: &str,
@@ -112,25 +235,34 @@ impl Gpu {
         self.profiler.timestamp(label, encoder);
     }
 
-    pub(crate) fn begin_pipeline_statistics_query(&self, render_pass: &mut wgpu::RenderPass) {
-        self.profiler.begin_stats(render_pass);
+    /// Begins a timed scope on the encoder, which ends when the returned
+    /// `ProfileScope` is dropped. The scope's duration is included in
+    /// `timestamp_report()`. Does nothing if timestamps are not supported.
+    pub fn profile_scope<'a>(
+        &self,
+        label: &str,
+        encoder: &'a mut CommandEncoder,
+    ) -> ProfileScope<'a> {
+        ProfileScope::new(label, encoder)
     }
 
-    pub fn total_statistics(&self) -> Result<[u64; 5], GpuError> {
-        // Get the QuerySet from the profiler
-        let stats = self.profiler.stats.as_ref().ok_or(GpuError::QueryNone)?;
-
-        let mut ret = [0; 5];
+    pub(crate) fn begin_pipeline_statistics_query(
+        &self,
+        render_pass: &mut wgpu::RenderPass,
+    ) -> bool {
+        self.profiler.begin_stats(render_pass)
+    }
 
-        for (i, stat) in stats
-            .get(&self.device, self.profiler.query_count())?
-            .iter()
-            .enumerate()
-        {
-            ret[i % 5] += stat;
-        }
+    /// See `Profiler::statistics()`.
+    /// # Errors
+    /// Errors if pipeline statistics are not supported or could not be read
+    pub fn total_statistics(&self) -> Result<[u64; 5], GpuError> {
+        self.profiler.statistics(&self.device)
+    }
 
-        Ok(ret)
+    /// See `Profiler::statistics_report()`.
+    pub fn statistics_report(&self) -> Vec<(&'static str, u64)> {
+        self.profiler.statistics_report(&self.device)
     }
 
     pub fn timestamp_report(&self) -> Vec<(String, f32)> {
@@ -156,16 +288,37 @@ impl Deref for Gpu {
     }
 }
 
+/// A command encoder that is submitted to the queue when dropped.
+/// Call `finish()` to take the command buffer and submit it yourself instead,
+/// e.g. with `Gpu::submit_batch()`, or `discard()` to drop the recorded commands
+/// without running them. Only an encoder that is dropped without either is submitted.
 pub struct CommandEncoder {
     inner: ManuallyDrop<wgpu::CommandEncoder>,
     finished: bool,
     pub(crate) gpu: Gpu,
 }
 impl CommandEncoder {
+    /// Finishes recording, returning the command buffer without submitting it.
+    /// The encoder is no longer submitted on drop.
     pub fn finish(mut self) -> wgpu::CommandBuffer {
         self.finished = true;
         unsafe { ManuallyDrop::take(&mut self.inner).finish() }
     }
+
+    /// Drops the encoder without submitting it, e.g. when the frame was cancelled
+    /// after commands were recorded. The recorded commands never run.
+    pub fn discard(mut self) {
+        self.finished = true;
+        // Safe because `finished` stops `drop()` from taking the encoder again
+        unsafe { ManuallyDrop::drop(&mut self.inner) };
+    }
+
+    /// Groups the commands recorded through the returned guard under `label` in
+    /// graphics debuggers, until the guard is dropped.
+    pub fn debug_group(&mut self, label: &str) -> DebugGroupGuard<'_, Self> {
+        self.inner.push_debug_group(label);
+        DebugGroupGuard::new(self, |encoder| encoder.inner.pop_debug_group())
+    }
 }
 impl Deref for CommandEncoder {
     type Target = wgpu::CommandEncoder;
@@ -1,10 +1,14 @@
 use futures::executor::block_on;
 use raw_window_handle::{HasRawWindowHandle, RawWindowHandle};
+use tracing::{error, warn};
 
 use crate::{
     graphics::{Gpu, GpuCtx, GpuError},
-    Profiler,
+    BlitCache, PipelineCache, Profiler, StagingBelt, TexturePool,
 };
+use std::{cell::RefCell, sync::Arc};
+
+type ErrorHandler = Arc<dyn Fn(wgpu::Error) + Send + Sync>;
 
 #[derive(Clone)]
 /// Builder for `GpuContext`.
@@ -17,8 +21,13 @@ pub struct GpuBuilder<'a> {
     /// The power preference for the adapter.
     /// This defaults to `HighPerformance` but can be set to use `LowPower`.
     power_preference: wgpu::PowerPreference,
+    /// A substring of the name of the adapter to use.
+    /// If no adapter matches, the adapter is chosen by the power preference.
+    adapter_name: Option<&'a str>,
     /// The device limits.
     limits: wgpu::Limits,
+    /// Whether the limits are clamped to those supported by the adapter.
+    clamp_limits: bool,
     /// The features that the device must support.
     features: wgpu::Features,
     /// The features that the device can optionally support.
@@ -27,6 +36,8 @@ pub struct GpuBuilder<'a> {
     trace_path: Option<&'a std::path::Path>,
     /// The label for this context.
     label: Option<&'a str>,
+    /// The handler for errors that are not caught by an error scope.
+    error_handler: Option<ErrorHandler>,
 }
 impl Default for GpuBuilder<'_> {
     fn default() -> Self {
@@ -39,11 +50,14 @@ impl GpuBuilder<'_> {
         Self {
             backends: wgpu::Backends::PRIMARY,
             power_preference: wgpu::PowerPreference::HighPerformance,
+            adapter_name: None,
             limits: wgpu::Limits::default(),
+            clamp_limits: false,
             label: None,
             features: wgpu::Features::default(),
             optional_features: wgpu::Features::empty(),
             trace_path: None,
+            error_handler: None,
         }
     }
 }
@@ -60,10 +74,45 @@ impl<'a> GpuBuilder<'a> {
         self
     }
 
+    /// Selects the first adapter whose name contains `name`, e.g. to force a
+    /// specific GPU on multi-GPU systems, or a software adapter such as `llvmpipe`
+    /// for reproducible CI runs. See `list_adapters()` for the available names.
+    /// If no adapter matches, this falls back to the power preference.
+    pub fn with_adapter_name(mut self, name: &'a str) -> Self {
+        self.adapter_name = Some(name);
+        self
+    }
+
+    /// Lists the adapters available for the given backends, e.g. to present a picker
+    /// for `with_adapter_name()`.
+    pub fn list_adapters(backends: wgpu::Backends) -> Vec<wgpu::AdapterInfo> {
+        wgpu::Instance::new(backends)
+            .enumerate_adapters(backends)
+            .map(|adapter| adapter.get_info())
+            .collect()
+    }
+
     /// Sets the limits for the device.
     /// There is certainly a better way to do this.
     pub fn with_limits(mut self, limits: wgpu::Limits) -> Self {
         self.limits = limits;
+        self.clamp_limits = false;
+        self
+    }
+
+    /// Uses the downlevel limits, which are supported by most Vulkan, DX11 and Metal
+    /// devices, clamped to the limits of the adapter.
+    pub fn with_downlevel_limits(mut self) -> Self {
+        self.limits = wgpu::Limits::downlevel_defaults();
+        self.clamp_limits = true;
+        self
+    }
+
+    /// Uses the WebGL2 limits, which are required to run on the GL backend,
+    /// clamped to the limits of the adapter.
+    pub fn with_webgl_limits(mut self) -> Self {
+        self.limits = wgpu::Limits::downlevel_webgl2_defaults();
+        self.clamp_limits = true;
         self
     }
 
@@ -94,6 +143,15 @@ impl<'a> GpuBuilder<'a> {
         self
     }
 
+    /// Sets the handler for device errors that are not caught by an error scope,
+    /// such as validation errors and running out of memory.
+    /// By default these are logged with `tracing::error!`.
+    /// The handler may be invoked from any thread.
+    pub fn with_error_handler(mut self, handler: Box<dyn Fn(wgpu::Error) + Send + Sync>) -> Self {
+        self.error_handler = Some(handler.into());
+        self
+    }
+
     /// Sets the output trace path for wgpu
     pub fn with_trace_path(mut self, path: &'a std::path::Path) -> Self {
         self.trace_path = Some(path);
@@ -132,20 +190,23 @@ impl<'a> GpuBuilder<'a> {
         let compatible_surface = window.map(|w| unsafe { instance.create_surface(w) });
 
         // Initialize the adapter (physical device).
-        let adapter = instance
-            .request_adapter(&wgpu::RequestAdapterOptions {
-                power_preference: self.power_preference,
-                compatible_surface: compatible_surface.as_ref(),
-                force_fallback_adapter: false,
-            })
-            .await
-            .ok_or(GpuError::AdapterNone)?;
+        let adapter = match self.find_adapter(&instance, compatible_surface.as_ref()) {
+            Some(adapter) => adapter,
+            None => instance
+                .request_adapter(&wgpu::RequestAdapterOptions {
+                    power_preference: self.power_preference,
+                    compatible_surface: compatible_surface.as_ref(),
+                    force_fallback_adapter: false,
+                })
+                .await
+                .ok_or(GpuError::AdapterNone)?,
+        };
 
         // Create the `device` (and get the handle for the command queue `queue`)
         let (device, queue) = adapter
             .request_device(
                 &wgpu::DeviceDescriptor {
-                    limits: self.limits.clone(),
+                    limits: self.limits(&adapter),
                     label: self.label,
                     features: self.features(&adapter),
                 },
@@ -154,6 +215,11 @@ impl<'a> GpuBuilder<'a> {
             .await
             .map_err(GpuError::RequestDeviceError)?;
 
+        match self.error_handler {
+            Some(handler) => device.on_uncaptured_error(move |e| handler(e)),
+            None => device.on_uncaptured_error(|e| error!("Uncaptured wgpu error: {}", e)),
+        }
+
         let preferred_format = compatible_surface.and_then(|s| s.get_preferred_format(&adapter));
 
         let profiler = Profiler::new(&device, &queue);
@@ -165,16 +231,93 @@ impl<'a> GpuBuilder<'a> {
             queue,
             profiler,
             preferred_format,
+            pipeline_cache: PipelineCache::default(),
+            staging_belt: RefCell::new(StagingBelt::default()),
+            blit_cache: BlitCache::default(),
+            texture_pool: TexturePool::default(),
         };
 
         Ok(gpu.into_handle())
     }
 
+    /// Finds the first adapter matching `adapter_name` that supports the surface.
+    fn find_adapter(
+        &self,
+        instance: &wgpu::Instance,
+        surface: Option<&wgpu::Surface>,
+    ) -> Option<wgpu::Adapter> {
+        let name = self.adapter_name?;
+        let adapter = instance
+            .enumerate_adapters(self.backends)
+            .filter(|adapter| match surface {
+                Some(surface) => adapter.is_surface_supported(surface),
+                None => true,
+            })
+            .find(|adapter| adapter.get_info().name.contains(name));
+        if adapter.is_none() {
+            warn!(
+                "No adapter named {:?}, falling back to the power preference",
+                name
+            );
+        }
+        adapter
+    }
+
+    fn limits(&self, adapter: &wgpu::Adapter) -> wgpu::Limits {
+        if self.clamp_limits {
+            clamp_limits(&self.limits, &adapter.limits())
+        } else {
+            self.limits.clone()
+        }
+    }
+
     fn features(&self, adapter: &wgpu::Adapter) -> wgpu::Features {
         self.features | (self.optional_features & adapter.features())
     }
 }
 
+/// Lowers each maximum in `limits` and raises each minimum alignment so that none
+/// of them exceed what `supported` allows.
+fn clamp_limits(limits: &wgpu::Limits, supported: &wgpu::Limits) -> wgpu::Limits {
+    macro_rules! clamp {
+        ($($max:ident),* ; $($min:ident),*) => {
+            wgpu::Limits {
+                $($max: limits.$max.min(supported.$max),)*
+                $($min: limits.$min.max(supported.$min),)*
+            }
+        };
+    }
+    clamp!(
+        max_texture_dimension_1d,
+        max_texture_dimension_2d,
+        max_texture_dimension_3d,
+        max_texture_array_layers,
+        max_bind_groups,
+        max_dynamic_uniform_buffers_per_pipeline_layout,
+        max_dynamic_storage_buffers_per_pipeline_layout,
+        max_sampled_textures_per_shader_stage,
+        max_samplers_per_shader_stage,
+        max_storage_buffers_per_shader_stage,
+        max_storage_textures_per_shader_stage,
+        max_uniform_buffers_per_shader_stage,
+        max_uniform_buffer_binding_size,
+        max_storage_buffer_binding_size,
+        max_vertex_buffers,
+        max_vertex_attributes,
+        max_vertex_buffer_array_stride,
+        max_push_constant_size,
+        max_inter_stage_shader_components,
+        max_compute_workgroup_storage_size,
+        max_compute_invocations_per_workgroup,
+        max_compute_workgroup_size_x,
+        max_compute_workgroup_size_y,
+        max_compute_workgroup_size_z,
+        max_compute_workgroups_per_dimension;
+        min_uniform_buffer_offset_alignment,
+        min_storage_buffer_offset_alignment
+    )
+}
+
 struct NoWindow;
 unsafe impl HasRawWindowHandle for NoWindow {
     fn raw_window_handle(&self) -> RawWindowHandle {
@@ -1,12 +1,33 @@
-use crate::Gpu;
+use futures::executor::block_on;
+
+use crate::{Gpu, GpuError};
 
 mod builder;
 pub use builder::*;
 
+mod cache;
+pub use cache::*;
+
+mod watch;
+pub use watch::*;
+
+mod preprocess;
+pub use preprocess::*;
+
+mod reflect;
+pub use reflect::*;
+
+#[derive(Debug)]
 pub struct RenderPipeline {
     pub gpu: Gpu,
     pub inner: wgpu::RenderPipeline,
     pub depth_stencil: Option<wgpu::DepthStencilState>,
+    /// The bind group layouts reflected from the shaders, if the pipeline was
+    /// created with `PipelineBuilder::with_auto_layout()`. Empty otherwise.
+    pub bind_group_layouts: Vec<wgpu::BindGroupLayout>,
+    /// The descriptor used to recreate the pipeline, if the pipeline was created with
+    /// `PipelineBuilder::retain_descriptor()`.
+    pub(crate) retained: Option<Box<RetainedDescriptor>>,
 }
 impl RenderPipeline {
     pub fn new(
@@ -18,8 +39,110 @@ impl RenderPipeline {
             gpu,
             inner,
             depth_stencil,
+            bind_group_layouts: Vec::new(),
+            retained: None,
         }
     }
+
+    /// Rebuilds the pipeline with every fragment target set to `format`, e.g. when
+    /// the surface format changes after moving the window to an HDR monitor.
+    /// The pipeline must be created with `PipelineBuilder::retain_descriptor()`.
+    /// # Errors
+    /// Errors with `ValidationError` if the descriptor was not retained or the new
+    /// pipeline fails validation. The old pipeline is kept in that case.
+    pub fn recreate(&mut self, format: wgpu::TextureFormat) -> Result<(), GpuError> {
+        let retained = self.retained.as_mut().ok_or_else(|| {
+            GpuError::ValidationError(
+                "pipeline was not created with retain_descriptor()".to_owned(),
+            )
+        })?;
+        let mut targets = retained.fragment_targets.clone();
+        for target in &mut targets {
+            target.format = format;
+        }
+        self.inner = retained.create(&self.gpu, &targets)?;
+        retained.fragment_targets = targets;
+        Ok(())
+    }
+}
+
+/// Owned copy of everything needed to create a render pipeline.
+/// The shader modules and pipeline layout are kept alive with the pipeline.
+#[derive(Debug)]
+pub(crate) struct RetainedDescriptor {
+    pub label: Option<String>,
+    pub layout: wgpu::PipelineLayout,
+    pub vertex_module: wgpu::ShaderModule,
+    pub vertex_entry: String,
+    pub vertex_layouts: Vec<(
+        wgpu::BufferAddress,
+        wgpu::VertexStepMode,
+        Vec<wgpu::VertexAttribute>,
+    )>,
+    pub fragment_module: Option<wgpu::ShaderModule>,
+    pub fragment_entry: String,
+    pub fragment_targets: Vec<wgpu::ColorTargetState>,
+    pub primitive: wgpu::PrimitiveState,
+    pub depth_stencil: Option<wgpu::DepthStencilState>,
+    pub multisample: wgpu::MultisampleState,
+    pub multiview: Option<std::num::NonZeroU32>,
+}
+impl RetainedDescriptor {
+    fn create(
+        &self,
+        gpu: &Gpu,
+        targets: &[wgpu::ColorTargetState],
+    ) -> Result<wgpu::RenderPipeline, GpuError> {
+        let buffers = self
+            .vertex_layouts
+            .iter()
+            .map(
+                |(array_stride, step_mode, attributes)| wgpu::VertexBufferLayout {
+                    array_stride: *array_stride,
+                    step_mode: *step_mode,
+                    attributes,
+                },
+            )
+            .collect::<Vec<_>>();
+
+        create_render_pipeline(
+            gpu,
+            &wgpu::RenderPipelineDescriptor {
+                label: self.label.as_deref(),
+                layout: Some(&self.layout),
+                vertex: wgpu::VertexState {
+                    module: &self.vertex_module,
+                    entry_point: &self.vertex_entry,
+                    buffers: &buffers,
+                },
+                primitive: self.primitive,
+                depth_stencil: self.depth_stencil.clone(),
+                multisample: self.multisample,
+                fragment: self
+                    .fragment_module
+                    .as_ref()
+                    .map(|module| wgpu::FragmentState {
+                        module,
+                        entry_point: &self.fragment_entry,
+                        targets,
+                    }),
+                multiview: self.multiview,
+            },
+        )
+    }
+}
+
+/// Creates the pipeline, returning validation errors instead of letting wgpu panic.
+pub(crate) fn create_render_pipeline(
+    gpu: &Gpu,
+    desc: &wgpu::RenderPipelineDescriptor,
+) -> Result<wgpu::RenderPipeline, GpuError> {
+    gpu.device.push_error_scope(wgpu::ErrorFilter::Validation);
+    let pipeline = gpu.device.create_render_pipeline(desc);
+    match block_on(gpu.device.pop_error_scope()) {
+        Some(error) => Err(GpuError::ValidationError(error.to_string())),
+        None => Ok(pipeline),
+    }
 }
 crate::wgpu_inner_deref!(RenderPipeline);
 
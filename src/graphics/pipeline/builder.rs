@@ -1,7 +1,10 @@
+use futures::executor::block_on;
 use tracing::warn;
 pub use wgpu::CompareFunction;
 
 use std::borrow::Cow;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
 
 use wgpu::ShaderModuleDescriptor;
 use wgpu::ShaderSource;
@@ -9,7 +12,7 @@ use wgpu::ShaderSource;
 use crate::Gpu;
 use crate::GpuError;
 
-use crate::RenderPipeline;
+use crate::{create_render_pipeline, reflect_layout_entries, RenderPipeline, RetainedDescriptor};
 
 pub trait ColorTargetBuilderExt {
     fn blend_over(self) -> Self;
@@ -58,6 +61,15 @@ impl ColorTargetBuilderExt for wgpu::ColorTargetState {
     }
 }
 
+/// Vertex shader that draws one triangle covering the whole target, with 3 vertices and
+/// no vertex buffers. It outputs the uv at `location(0)`, where the top left is (0, 0).
+/// This is the default vertex shader of `PipelineBuilder`.
+pub const SCREEN_VERTEX_SHADER: &[u8] = include_bytes!("../../shader/screen.vert.spv");
+
+/// WGSL version of `SCREEN_VERTEX_SHADER`, which is used by default on the GL backend
+/// since it does not accept SPIR-V.
+pub const SCREEN_VERTEX_SHADER_WGSL: &[u8] = include_bytes!("../../shader/screen.wgsl");
+
 pub struct PipelineBuilder<'a> {
     /// Handle to the Gpu
     gpu: Gpu,
@@ -66,14 +78,34 @@ pub struct PipelineBuilder<'a> {
     /// This is a seperate struct to take advantage of Default trait derivation
     desc: PipelineDescriptor<'a>,
 
-    /// SPIR-V bytes for the vertex shader
-    vertex: ShaderModuleDescriptor<'a>,
-    /// SPIR-V bytes for the fragment shader.
+    /// The vertex shader, parsed on create()
+    vertex: ShaderInput<'a>,
+    /// The fragment shader, parsed on create().
     /// This is optional
-    fragment: Option<ShaderModuleDescriptor<'a>>,
+    fragment: Option<ShaderInput<'a>>,
     vertex_entry: &'a str,
     fragment_entry: &'a str,
     fragment_targets: &'a [wgpu::ColorTargetState],
+    /// Directory that WGSL `#include`s are resolved relative to
+    include_dir: &'a str,
+    /// Constants substituted into WGSL source
+    defines: &'a [(&'a str, &'a str)],
+    /// Reflect the bind group layouts from the shaders on create()
+    auto_layout: bool,
+    /// Keep the descriptor in the created pipeline so that it can be recreated
+    retain: bool,
+}
+
+/// Unparsed shader input. Parsing is deferred to `try_create()` so that errors
+/// can be returned instead of panicking.
+#[derive(Clone, Copy)]
+enum ShaderInput<'a> {
+    /// SPIR-V or WGSL bytes
+    Bytes(&'a [u8]),
+    /// Path to a SPIR-V or WGSL file, loaded at runtime
+    Path(&'a str),
+    /// WGSL source that is run through the preprocessor
+    Wgsl(&'a str),
 }
 
 #[derive(Default)]
@@ -94,6 +126,9 @@ struct PipelineDescriptor<'a> {
     depth_stencil: Option<wgpu::DepthStencilState>,
     multisample: wgpu::MultisampleState,
     vertex_layouts: &'a [wgpu::VertexBufferLayout<'a>],
+    /// The number of array layers the render attachments have, if multiview is used.
+    /// Requires [`Features::MULTIVIEW`].
+    multiview: Option<std::num::NonZeroU32>,
 }
 impl PipelineBuilder<'_> {
     pub fn make_spirv(bytes: &[u8]) -> Result<ShaderSource, GpuError> {
@@ -151,12 +186,12 @@ impl PipelineBuilder<'_> {
     /// 'a: lifetime of the shader source
     /// 'b: lifetime of the input path
     pub fn shader_auto_load<'a, 'b>(path: &'b str) -> Result<ShaderSource<'a>, GpuError> {
-        if let Ok(spirv) = Self::make_spirv_owned(std::fs::read(path).unwrap()) {
-            Ok(spirv)
-        } else if let Ok(wgsl) = Self::make_wgsl_owned(std::fs::read_to_string(path).unwrap()) {
-            Ok(wgsl)
+        let bytes = std::fs::read(path).map_err(|_| GpuError::ShaderParseError)?;
+        if let Ok(ShaderSource::SpirV(spirv)) = Self::make_spirv(&bytes) {
+            Ok(ShaderSource::SpirV(Cow::Owned(spirv.into_owned())))
         } else {
-            Err(GpuError::ShaderParseError)
+            let wgsl = String::from_utf8(bytes).map_err(|_| GpuError::ShaderParseError)?;
+            Self::make_wgsl_owned(wgsl)
         }
     }
 
@@ -174,17 +209,19 @@ impl<'a> PipelineBuilder<'a> {
     pub fn new(gpu: Gpu, label: &'a str) -> Self {
         const DEFAULT_FRAGMENT_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Bgra8UnormSrgb;
 
-        let vertex = wgpu::util::make_spirv(include_bytes!("../../shader/screen.vert.spv"));
-        let fragment = wgpu::util::make_spirv(include_bytes!("../../shader/uv.frag.spv"));
-
-        let vertex = ShaderModuleDescriptor {
-            label: Some("Default vertex shader"),
-            source: vertex,
+        // The GL backend only accepts WGSL, so the defaults are embedded in both
+        let (vertex, fragment): (&[u8], &[u8]) = match gpu.adapter.get_info().backend {
+            wgpu::Backend::Gl => (
+                SCREEN_VERTEX_SHADER_WGSL,
+                include_bytes!("../../shader/uv.wgsl"),
+            ),
+            _ => (
+                SCREEN_VERTEX_SHADER,
+                include_bytes!("../../shader/uv.frag.spv"),
+            ),
         };
-        let fragment = Some(ShaderModuleDescriptor {
-            label: Some("Default fragment shader"),
-            source: fragment,
-        });
+        let vertex = ShaderInput::Bytes(vertex);
+        let fragment = Some(ShaderInput::Bytes(fragment));
 
         Self {
             gpu,
@@ -200,6 +237,10 @@ impl<'a> PipelineBuilder<'a> {
                 blend: Some(wgpu::BlendState::ALPHA_BLENDING),
                 write_mask: wgpu::ColorWrites::ALL,
             }],
+            include_dir: ".",
+            defines: &[],
+            auto_layout: false,
+            retain: false,
         }
     }
 
@@ -272,18 +313,81 @@ impl<'a> PipelineBuilder<'a> {
         self
     }
 
+    /// Use reversed-Z depth, where near is 1.0 and far is 0.0, which spreads the
+    /// precision of floating point depth more evenly over the view distance.
+    /// This sets the depth comparison to `Greater`, so the depth attachment must be
+    /// cleared to 0.0 with `clear_depth_val(0.0)` instead of `clear_depth()`, and the
+    /// projection must map near to 1.0.
+    pub fn reverse_z(self) -> Self {
+        self.depth_compare(CompareFunction::Greater)
+    }
+
+    /// Declare a depth-stencil state for the pipeline with a `Depth24PlusStencil8`
+    /// format. The stencil test always passes and keeps the stencil values until it
+    /// is configured with `stencil_front()` and `stencil_back()`.
+    /// The read and write masks default to `0xff`.
     pub fn with_depth_stencil(mut self) -> Self {
         self.desc.depth_stencil = Some(wgpu::DepthStencilState {
             depth_write_enabled: true,
             depth_compare: wgpu::CompareFunction::Less,
-            // TODO: Actually need a stencil state to use stencil lol
-            stencil: wgpu::StencilState::default(),
+            stencil: wgpu::StencilState {
+                read_mask: 0xff,
+                write_mask: 0xff,
+                ..Default::default()
+            },
             format: wgpu::TextureFormat::Depth24PlusStencil8,
             bias: wgpu::DepthBiasState::default(),
         });
         self
     }
 
+    /// Set the stencil test and operations for front-facing primitives.
+    /// The reference value is set with `RenderPass::set_stencil_reference()`.
+    pub fn stencil_front(mut self, state: wgpu::StencilFaceState) -> Self {
+        self.do_depth(|desc| {
+            desc.stencil.front = state;
+        });
+        self
+    }
+
+    /// Set the stencil test and operations for back-facing primitives.
+    pub fn stencil_back(mut self, state: wgpu::StencilFaceState) -> Self {
+        self.do_depth(|desc| {
+            desc.stencil.back = state;
+        });
+        self
+    }
+
+    /// Set the stencil test and operations for both faces.
+    pub fn stencil(self, state: wgpu::StencilFaceState) -> Self {
+        self.stencil_front(state).stencil_back(state)
+    }
+
+    /// Set the mask of the stencil bits that are compared in the stencil test
+    pub fn stencil_read_mask(mut self, mask: u32) -> Self {
+        self.do_depth(|desc| {
+            desc.stencil.read_mask = mask;
+        });
+        self
+    }
+
+    /// Set the mask of the stencil bits that are written by stencil operations
+    pub fn stencil_write_mask(mut self, mask: u32) -> Self {
+        self.do_depth(|desc| {
+            desc.stencil.write_mask = mask;
+        });
+        self
+    }
+
+    /// Enable or disable depth writes. Disable them for stencil-only passes and for
+    /// transparent geometry.
+    pub fn depth_write(mut self, enabled: bool) -> Self {
+        self.do_depth(|desc| {
+            desc.depth_write_enabled = enabled;
+        });
+        self
+    }
+
     fn str_from_bytes(bytes: &[u8]) -> Result<&str, GpuError> {
         std::str::from_utf8(bytes).map_err(|_| GpuError::ShaderParseError)
     }
@@ -291,14 +395,14 @@ impl<'a> PipelineBuilder<'a> {
     /// Load the vertex shader from file path.
     /// See `with_vertex()` for loading static bytes.
     pub fn load_vertex(mut self, path: &'a str) -> Self {
-        self.vertex.source = Self::shader_auto_load(path).expect("Load vertex shader");
+        self.vertex = ShaderInput::Path(path);
         self
     }
     /// Load the vertex shader from bytes.
     /// This is convenient for static bytes. If you want to load from a file, at
     /// runtime, see load_vertex()
     pub fn with_vertex(mut self, bytes: &'a [u8]) -> Self {
-        self.vertex.source = Self::shader_auto(bytes).expect("Parse vertex shader");
+        self.vertex = ShaderInput::Bytes(bytes);
         self
     }
 
@@ -306,10 +410,7 @@ impl<'a> PipelineBuilder<'a> {
     /// This is convenient for static bytes. If you want to load from a file, at
     /// runtime, see load_fragment()
     pub fn with_fragment(mut self, bytes: &'static [u8]) -> Self {
-        self.fragment = Some(ShaderModuleDescriptor {
-            label: Some("Default fragment shader"),
-            source: Self::shader_auto(bytes).expect("Parse fragment shader"),
-        });
+        self.fragment = Some(ShaderInput::Bytes(bytes));
         self
     }
 
@@ -331,6 +432,36 @@ impl<'a> PipelineBuilder<'a> {
         self.with_vertex(bytes).with_fragment(bytes)
     }
 
+    /// Sets WGSL source for both the vertex and fragment stages, which is run
+    /// through the preprocessor on create(). This supports `#include "path"`
+    /// and `#define NAME value`. See `preprocess_wgsl()`.
+    /// This also sets the entry points to vs_main and fs_main respectively.
+    pub fn with_wgsl_source(mut self, source: &'a str) -> Self {
+        self.vertex = ShaderInput::Wgsl(source);
+        self.fragment = Some(ShaderInput::Wgsl(source));
+        self.vertex_entry = "vs_main";
+        self.fragment_entry = "fs_main";
+        self
+    }
+
+    /// Sets the directory that WGSL `#include`s are resolved relative to.
+    /// Defaults to the current working directory.
+    pub fn with_include_dir(mut self, dir: &'a str) -> Self {
+        self.include_dir = dir;
+        self
+    }
+
+    /// Sets constants that are substituted into WGSL source.
+    /// See `with_wgsl_source()`.
+    ///
+    /// WGSL `override` constants can not be set at pipeline creation yet, since the
+    /// `constants` map of `wgpu::PipelineCompilationOptions` was only added in wgpu 0.20.
+    /// Until then, use a define to specialize a value such as a quality level.
+    pub fn with_defines(mut self, defines: &'a [(&'a str, &'a str)]) -> Self {
+        self.defines = defines;
+        self
+    }
+
     /// Optional version of with_fragment_bytes(), for use in macros
     /// This has no effect if None is provided. To remove the fragment shader,
     /// use no_fragment() instead.
@@ -342,13 +473,36 @@ impl<'a> PipelineBuilder<'a> {
         }
     }
 
+    /// Removes the fragment stage from the pipeline.
+    /// This is required for depth-only pipelines such as shadow passes.
+    /// `fragment_targets` are ignored when there is no fragment stage.
+    pub fn no_fragment(mut self) -> Self {
+        self.fragment = None;
+        self
+    }
+
     /// Load the fragment shader from file path at runtime.
     /// See `with_fragment()` for loading static bytes.
     pub fn load_fragment(mut self, fragment: &'a str) -> Self {
-        self.fragment = Some(ShaderModuleDescriptor {
-            label: Some("Default fragment shader"),
-            source: Self::shader_auto_load(fragment).expect("Load fragment shader"),
-        });
+        self.fragment = Some(ShaderInput::Path(fragment));
+        self
+    }
+
+    /// Reflects the bind group layouts from the shaders when the pipeline is
+    /// created, instead of using the layouts from `with_bind_groups()`.
+    /// The layouts are available as `RenderPipeline::bind_group_layouts`, and
+    /// bind groups for the pipeline must be created with them.
+    pub const fn with_auto_layout(mut self) -> Self {
+        self.auto_layout = true;
+        self
+    }
+
+    /// Keeps an owned copy of the descriptor in the created pipeline, so that it can
+    /// be rebuilt for a new surface format with `RenderPipeline::recreate()`.
+    /// This keeps the shader modules and pipeline layout alive for as long as the
+    /// pipeline, and copies the fragment targets and vertex layouts.
+    pub const fn retain_descriptor(mut self) -> Self {
+        self.retain = true;
         self
     }
 
@@ -357,6 +511,24 @@ impl<'a> PipelineBuilder<'a> {
         self
     }
 
+    /// Sets the number of samples per pixel for MSAA.
+    /// This must match the sample count of the render targets, e.g. the
+    /// viewport's `with_msaa()`.
+    pub const fn with_multisample(mut self, samples: u32) -> Self {
+        self.desc.multisample.count = samples;
+        self
+    }
+
+    /// Renders to `layers` array layers of the render targets at once, e.g. for stereo
+    /// rendering. The render targets must be array textures with this many layers,
+    /// and the vertex shader can use `[[builtin(view_index)]]` to get the layer.
+    /// The device must have `Features::MULTIVIEW` enabled, otherwise `try_create()`
+    /// returns an error.
+    pub const fn with_multiview(mut self, layers: u32) -> Self {
+        self.desc.multiview = std::num::NonZeroU32::new(layers);
+        self
+    }
+
     /// Cull front faces.
     /// Front is CCW.
     pub const fn cull_front(mut self) -> Self {
@@ -400,17 +572,87 @@ impl<'a> PipelineBuilder<'a> {
         self
     }
 
+    /// Creates the pipeline, panicking if a shader fails to parse.
+    /// See `try_create()` for a non-panicking version.
     #[must_use]
     pub fn create(&self) -> RenderPipeline {
+        self.try_create().expect("Create render pipeline")
+    }
+
+    /// Creates the pipeline.
+    /// # Errors
+    /// Errors with `ShaderParseError` if a shader fails to load, parse or
+    /// validate, with `ReflectionError` if `with_auto_layout()` is used and a
+    /// binding can not be reflected, with `MissingFeature` if `with_multiview()` is
+    /// used without `Features::MULTIVIEW`, or with `ValidationError` if the pipeline
+    /// fails validation.
+    pub fn try_create(&self) -> Result<RenderPipeline, GpuError> {
+        if self.desc.multiview.is_some() && !self.gpu.has_feature(wgpu::Features::MULTIVIEW) {
+            return Err(GpuError::MissingFeature(wgpu::Features::MULTIVIEW));
+        }
+
+        let vertex_label = self.label_suffix("vertex shader");
+        let fragment_label = self.label_suffix("fragment shader");
+        let vertex = ShaderModuleDescriptor {
+            label: vertex_label.as_deref(),
+            source: self.shader_source(self.vertex)?,
+        };
+        let fragment = match self.fragment {
+            Some(fragment) => Some(ShaderModuleDescriptor {
+                label: fragment_label.as_deref(),
+                source: self.shader_source(fragment)?,
+            }),
+            None => None,
+        };
+
+        // Reflect the bind group layouts if requested
+        let bind_group_layouts = if self.auto_layout {
+            let mut stages = vec![(
+                &vertex.source,
+                self.vertex_entry,
+                wgpu::ShaderStages::VERTEX,
+            )];
+            if let Some(fragment) = &fragment {
+                stages.push((
+                    &fragment.source,
+                    self.fragment_entry,
+                    wgpu::ShaderStages::FRAGMENT,
+                ));
+            }
+            let label = self.label_suffix("bind group layout");
+            reflect_layout_entries(&stages)?
+                .iter()
+                .map(|entries| {
+                    self.gpu
+                        .device
+                        .create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                            label: label.as_deref(),
+                            entries,
+                        })
+                })
+                .collect()
+        } else {
+            Vec::new()
+        };
+        let auto_layouts = bind_group_layouts.iter().collect::<Vec<_>>();
+
+        // Catch shader errors instead of letting wgpu panic
+        self.gpu
+            .device
+            .push_error_scope(wgpu::ErrorFilter::Validation);
+
         // Create vertex module
-        let vertex_module = self.gpu.device.create_shader_module(&self.vertex);
+        let vertex_module = self.gpu.device.create_shader_module(&vertex);
 
         // Create shader module
-        let fragment_module = self
-            .fragment
+        let fragment_module = fragment
             .as_ref()
             .map(|fragment| self.gpu.device.create_shader_module(fragment));
 
+        if block_on(self.gpu.device.pop_error_scope()).is_some() {
+            return Err(GpuError::ShaderParseError);
+        }
+
         // Map fragment state if Some() otherwise it is None
         let fragment = fragment_module
             .as_ref()
@@ -426,7 +668,11 @@ impl<'a> PipelineBuilder<'a> {
             .device
             .create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
                 label: self.label_suffix("pipeline layout").as_deref(),
-                bind_group_layouts: self.desc.bind_group_layouts,
+                bind_group_layouts: if self.auto_layout {
+                    &auto_layouts
+                } else {
+                    self.desc.bind_group_layouts
+                },
                 push_constant_ranges: self.desc.push_constant_ranges,
             });
 
@@ -442,16 +688,72 @@ impl<'a> PipelineBuilder<'a> {
             depth_stencil: self.desc.depth_stencil.clone(),
             multisample: self.desc.multisample,
             fragment,
-            // TODO: Implement multiview interface
-            multiview: None,
+            multiview: self.desc.multiview,
         };
 
         // Create the pipeline
-        let pipeline = self.gpu.device.create_render_pipeline(&pipeline_desc);
-        RenderPipeline {
+        let pipeline = create_render_pipeline(&self.gpu, &pipeline_desc)?;
+
+        let retained = self.retain.then(|| {
+            Box::new(RetainedDescriptor {
+                label: self.label.map(str::to_owned),
+                layout,
+                vertex_module,
+                vertex_entry: self.vertex_entry.to_owned(),
+                vertex_layouts: self
+                    .desc
+                    .vertex_layouts
+                    .iter()
+                    .map(|layout| {
+                        (
+                            layout.array_stride,
+                            layout.step_mode,
+                            layout.attributes.to_vec(),
+                        )
+                    })
+                    .collect(),
+                fragment_module,
+                fragment_entry: self.fragment_entry.to_owned(),
+                fragment_targets: self.fragment_targets.to_vec(),
+                primitive: self.desc.primitive,
+                depth_stencil: self.desc.depth_stencil.clone(),
+                multisample: self.desc.multisample,
+                multiview: self.desc.multiview,
+            })
+        });
+
+        Ok(RenderPipeline {
             depth_stencil: self.desc.depth_stencil.clone(),
             gpu: self.gpu.clone(),
             inner: pipeline,
+            bind_group_layouts,
+            retained,
+        })
+    }
+
+    /// The file paths of shaders that were set with `load_vertex()` or
+    /// `load_fragment()`.
+    pub(crate) fn shader_paths(&self) -> Vec<&'a str> {
+        std::iter::once(Some(self.vertex))
+            .chain(std::iter::once(self.fragment))
+            .flatten()
+            .filter_map(|shader| match shader {
+                ShaderInput::Path(path) => Some(path),
+                ShaderInput::Bytes(_) | ShaderInput::Wgsl(_) => None,
+            })
+            .collect()
+    }
+
+    /// Loads and parses the shader input
+    fn shader_source(&self, input: ShaderInput<'a>) -> Result<ShaderSource<'a>, GpuError> {
+        match input {
+            ShaderInput::Bytes(bytes) => Self::shader_auto(bytes),
+            ShaderInput::Path(path) => Self::shader_auto_load(path),
+            ShaderInput::Wgsl(wgsl) => Self::make_wgsl_owned(crate::preprocess_wgsl(
+                wgsl,
+                std::path::Path::new(self.include_dir),
+                self.defines,
+            )?),
         }
     }
 
@@ -460,3 +762,77 @@ impl<'a> PipelineBuilder<'a> {
         self.label.map(|label| format!("{} {}", label, suffix))
     }
 }
+
+impl PipelineBuilder<'_> {
+    /// Deterministic hash of everything that affects the created pipeline.
+    /// The label is not included. Returns `None` if the shader source can't
+    /// be hashed.
+    pub(crate) fn cache_key(&self) -> Option<u64> {
+        // DefaultHasher::new() uses fixed keys, so this is stable across runs
+        let mut hasher = DefaultHasher::new();
+
+        hash_shader(&self.shader_source(self.vertex).ok()?, &mut hasher)?;
+        self.vertex_entry.hash(&mut hasher);
+        match self.fragment {
+            Some(fragment) => {
+                true.hash(&mut hasher);
+                hash_shader(&self.shader_source(fragment).ok()?, &mut hasher)?;
+                self.fragment_entry.hash(&mut hasher);
+                self.fragment_targets.hash(&mut hasher);
+            }
+            None => false.hash(&mut hasher),
+        }
+
+        // wgpu doesn't expose layout ids, but the debug output contains the
+        // (index, epoch, backend) of the id, which is unique per layout
+        self.auto_layout.hash(&mut hasher);
+        self.retain.hash(&mut hasher);
+        for layout in self.desc.bind_group_layouts {
+            format!("{:?}", layout).hash(&mut hasher);
+        }
+        self.desc.push_constant_ranges.hash(&mut hasher);
+
+        self.desc.primitive.hash(&mut hasher);
+        self.desc.multisample.hash(&mut hasher);
+        self.desc.multiview.hash(&mut hasher);
+        for layout in self.desc.vertex_layouts {
+            layout.array_stride.hash(&mut hasher);
+            layout.step_mode.hash(&mut hasher);
+            layout.attributes.hash(&mut hasher);
+        }
+
+        // DepthStencilState isn't Hash because of the float bias
+        match &self.desc.depth_stencil {
+            Some(depth) => {
+                true.hash(&mut hasher);
+                depth.format.hash(&mut hasher);
+                depth.depth_write_enabled.hash(&mut hasher);
+                depth.depth_compare.hash(&mut hasher);
+                depth.stencil.hash(&mut hasher);
+                depth.bias.constant.hash(&mut hasher);
+                depth.bias.slope_scale.to_bits().hash(&mut hasher);
+                depth.bias.clamp.to_bits().hash(&mut hasher);
+            }
+            None => false.hash(&mut hasher),
+        }
+
+        Some(hasher.finish())
+    }
+}
+
+fn hash_shader(source: &ShaderSource, hasher: &mut DefaultHasher) -> Option<()> {
+    match source {
+        ShaderSource::SpirV(words) => {
+            0u8.hash(hasher);
+            words.hash(hasher);
+        }
+        ShaderSource::Wgsl(wgsl) => {
+            1u8.hash(hasher);
+            wgsl.hash(hasher);
+        }
+        // Other sources are only available with extra wgpu features
+        #[allow(unreachable_patterns)]
+        _ => return None,
+    }
+    Some(())
+}
@@ -0,0 +1,55 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::{Rc, Weak};
+
+use crate::{PipelineBuilder, RenderPipeline};
+
+/// Cache of created render pipelines, keyed by a hash of the pipeline builder.
+///
+/// The cache only holds weak references. Pipelines own a `Gpu` handle, so
+/// strong references would keep the `Gpu` alive forever. A cached pipeline is
+/// shared for as long as something else holds onto it.
+#[derive(Debug, Default)]
+pub struct PipelineCache {
+    pipelines: RefCell<HashMap<u64, Weak<RenderPipeline>>>,
+}
+impl PipelineCache {
+    /// Returns the cached pipeline for the builder, or creates and caches it.
+    pub fn get_or_create(&self, builder: &PipelineBuilder) -> Rc<RenderPipeline> {
+        let key = match builder.cache_key() {
+            Some(key) => key,
+            // Not hashable, so it can't be cached
+            None => return Rc::new(builder.create()),
+        };
+
+        if let Some(pipeline) = self.pipelines.borrow().get(&key).and_then(Weak::upgrade) {
+            return pipeline;
+        }
+
+        let pipeline = Rc::new(builder.create());
+        let mut pipelines = self.pipelines.borrow_mut();
+        // Prune dead entries while we're here
+        pipelines.retain(|_, pipeline| pipeline.strong_count() > 0);
+        pipelines.insert(key, Rc::downgrade(&pipeline));
+        pipeline
+    }
+
+    /// The number of live pipelines in the cache.
+    pub fn len(&self) -> usize {
+        self.pipelines
+            .borrow()
+            .values()
+            .filter(|pipeline| pipeline.strong_count() > 0)
+            .count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Removes all pipelines from the cache.
+    /// Pipelines that are still in use are not destroyed.
+    pub fn clear(&self) {
+        self.pipelines.borrow_mut().clear();
+    }
+}
@@ -0,0 +1,109 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::GpuError;
+
+/// Runs a lightweight preprocessor over WGSL source.
+///
+/// * `#include "path"` lines are replaced by the contents of the file, resolved
+///   relative to `include_dir`. Included files are preprocessed as well.
+/// * `#define NAME value` lines define a constant. Every whole-word occurrence
+///   of `NAME` is replaced by `value`.
+/// * `defines` are applied the same way, and take precedence over `#define`s
+///   in the source.
+///
+/// # Errors
+/// Errors with `ShaderParseError` if an include can't be read, if includes are
+/// cyclic, or if a directive is malformed.
+pub fn preprocess_wgsl(
+    source: &str,
+    include_dir: &Path,
+    defines: &[(&str, &str)],
+) -> Result<String, GpuError> {
+    let mut state = Preprocessor {
+        include_dir,
+        defines: defines
+            .iter()
+            .map(|&(name, value)| (name.to_owned(), value.to_owned()))
+            .collect(),
+        include_stack: Vec::new(),
+    };
+    let expanded = state.expand(source)?;
+    Ok(state.substitute(&expanded))
+}
+
+struct Preprocessor<'a> {
+    include_dir: &'a Path,
+    defines: HashMap<String, String>,
+    /// Files currently being included, used to detect cycles
+    include_stack: Vec<PathBuf>,
+}
+impl Preprocessor<'_> {
+    /// Resolves includes and collects defines
+    fn expand(&mut self, source: &str) -> Result<String, GpuError> {
+        let mut out = String::with_capacity(source.len());
+        for line in source.lines() {
+            let trimmed = line.trim_start();
+            if let Some(rest) = trimmed.strip_prefix("#include") {
+                let path = rest
+                    .trim()
+                    .strip_prefix('"')
+                    .and_then(|rest| rest.strip_suffix('"'))
+                    .ok_or(GpuError::ShaderParseError)?;
+                out.push_str(&self.include(path)?);
+            } else if let Some(rest) = trimmed.strip_prefix("#define") {
+                let rest = rest.trim();
+                let (name, value) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+                if name.is_empty() {
+                    return Err(GpuError::ShaderParseError);
+                }
+                // Defines passed in take precedence
+                self.defines
+                    .entry(name.to_owned())
+                    .or_insert_with(|| value.trim().to_owned());
+            } else {
+                out.push_str(line);
+            }
+            out.push('\n');
+        }
+        Ok(out)
+    }
+
+    fn include(&mut self, path: &str) -> Result<String, GpuError> {
+        let path = self
+            .include_dir
+            .join(path)
+            .canonicalize()
+            .map_err(|_| GpuError::ShaderParseError)?;
+        if self.include_stack.contains(&path) {
+            return Err(GpuError::ShaderParseError);
+        }
+        let source = std::fs::read_to_string(&path).map_err(|_| GpuError::ShaderParseError)?;
+
+        self.include_stack.push(path);
+        let expanded = self.expand(&source);
+        self.include_stack.pop();
+        expanded
+    }
+
+    /// Replaces whole-word occurrences of defined names with their values
+    fn substitute(&self, source: &str) -> String {
+        if self.defines.is_empty() {
+            return source.to_owned();
+        }
+
+        let is_ident = |c: char| c.is_alphanumeric() || c == '_';
+        let mut out = String::with_capacity(source.len());
+        let mut rest = source;
+        while let Some(start) = rest.find(is_ident) {
+            out.push_str(&rest[..start]);
+            rest = &rest[start..];
+            let end = rest.find(|c| !is_ident(c)).unwrap_or(rest.len());
+            let word = &rest[..end];
+            out.push_str(self.defines.get(word).map_or(word, String::as_str));
+            rest = &rest[end..];
+        }
+        out.push_str(rest);
+        out
+    }
+}
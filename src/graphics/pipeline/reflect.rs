@@ -0,0 +1,224 @@
+use std::collections::BTreeMap;
+
+use crate::GpuError;
+
+/// A shader stage to reflect: the shader source, the entry point, and the stage
+/// the entry point runs in.
+pub type ReflectStage<'a> = (&'a wgpu::ShaderSource<'a>, &'a str, wgpu::ShaderStages);
+
+/// Reflects the bind group layout entries used by the given shader stages.
+/// The returned `Vec` is indexed by bind group, and each group's entries are
+/// sorted by binding. Only bindings used by the entry points are included, and
+/// their visibility is the set of stages that use them.
+/// # Errors
+/// Errors with `ShaderParseError` if a shader fails to parse or validate, or
+/// with `ReflectionError` if the type of a binding can not be determined.
+pub fn reflect_layout_entries(
+    stages: &[ReflectStage],
+) -> Result<Vec<Vec<wgpu::BindGroupLayoutEntry>>, GpuError> {
+    let mut entries = BTreeMap::<(u32, u32), wgpu::BindGroupLayoutEntry>::new();
+
+    for &(source, entry_point, stage) in stages {
+        let module = parse_module(source)?;
+        let info = naga::valid::Validator::new(
+            naga::valid::ValidationFlags::all(),
+            naga::valid::Capabilities::all(),
+        )
+        .validate(&module)
+        .map_err(|_| GpuError::ShaderParseError)?;
+
+        let entry_index = module
+            .entry_points
+            .iter()
+            .position(|entry| entry.name == entry_point && shader_stage(entry.stage) == stage)
+            .ok_or_else(|| {
+                GpuError::ReflectionError(format!("entry point `{}` not found", entry_point))
+            })?;
+        let entry_info = info.get_entry_point(entry_index);
+
+        for (handle, global) in module.global_variables.iter() {
+            let binding = match &global.binding {
+                Some(binding) if !entry_info[handle].is_empty() => binding,
+                _ => continue,
+            };
+            let ty = binding_type(&module, global)?;
+            entries
+                .entry((binding.group, binding.binding))
+                .or_insert(wgpu::BindGroupLayoutEntry {
+                    binding: binding.binding,
+                    visibility: wgpu::ShaderStages::NONE,
+                    ty,
+                    count: None,
+                })
+                .visibility |= stage;
+        }
+    }
+
+    let group_count = entries.keys().last().map_or(0, |&(group, _)| group + 1);
+    let mut groups = vec![Vec::new(); group_count as usize];
+    for ((group, _), entry) in entries {
+        groups[group as usize].push(entry);
+    }
+    Ok(groups)
+}
+
+fn parse_module(source: &wgpu::ShaderSource) -> Result<naga::Module, GpuError> {
+    match source {
+        wgpu::ShaderSource::Wgsl(wgsl) => {
+            naga::front::wgsl::parse_str(wgsl).map_err(|_| GpuError::ShaderParseError)
+        }
+        wgpu::ShaderSource::SpirV(words) => naga::front::spv::parse_u8_slice(
+            bytemuck::cast_slice(words),
+            &naga::front::spv::Options::default(),
+        )
+        .map_err(|_| GpuError::ShaderParseError),
+        // Other sources are only available with additional wgpu features
+        #[allow(unreachable_patterns)]
+        _ => Err(GpuError::ReflectionError(
+            "unsupported shader source".to_string(),
+        )),
+    }
+}
+
+const fn shader_stage(stage: naga::ShaderStage) -> wgpu::ShaderStages {
+    match stage {
+        naga::ShaderStage::Vertex => wgpu::ShaderStages::VERTEX,
+        naga::ShaderStage::Fragment => wgpu::ShaderStages::FRAGMENT,
+        naga::ShaderStage::Compute => wgpu::ShaderStages::COMPUTE,
+    }
+}
+
+/// Determines the binding type of a global resource variable
+fn binding_type(
+    module: &naga::Module,
+    global: &naga::GlobalVariable,
+) -> Result<wgpu::BindingType, GpuError> {
+    let unknown = || {
+        GpuError::ReflectionError(format!(
+            "can not determine binding type of `{}`",
+            global.name.as_deref().unwrap_or("<unnamed>")
+        ))
+    };
+
+    let ty = match global.class {
+        naga::StorageClass::Uniform => wgpu::BindingType::Buffer {
+            ty: wgpu::BufferBindingType::Uniform,
+            has_dynamic_offset: false,
+            min_binding_size: None,
+        },
+        naga::StorageClass::Storage { access } => wgpu::BindingType::Buffer {
+            ty: wgpu::BufferBindingType::Storage {
+                read_only: !access.contains(naga::StorageAccess::STORE),
+            },
+            has_dynamic_offset: false,
+            min_binding_size: None,
+        },
+        naga::StorageClass::Handle => match module.types[global.ty].inner {
+            naga::TypeInner::Sampler { comparison } => wgpu::BindingType::Sampler(if comparison {
+                wgpu::SamplerBindingType::Comparison
+            } else {
+                wgpu::SamplerBindingType::Filtering
+            }),
+            naga::TypeInner::Image {
+                dim,
+                arrayed,
+                class,
+            } => {
+                let view_dimension = view_dimension(dim, arrayed).ok_or_else(unknown)?;
+                match class {
+                    naga::ImageClass::Sampled { kind, multi } => wgpu::BindingType::Texture {
+                        sample_type: match kind {
+                            naga::ScalarKind::Float => {
+                                wgpu::TextureSampleType::Float { filterable: true }
+                            }
+                            naga::ScalarKind::Sint => wgpu::TextureSampleType::Sint,
+                            naga::ScalarKind::Uint => wgpu::TextureSampleType::Uint,
+                            naga::ScalarKind::Bool => return Err(unknown()),
+                        },
+                        view_dimension,
+                        multisampled: multi,
+                    },
+                    naga::ImageClass::Depth { multi } => wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Depth,
+                        view_dimension,
+                        multisampled: multi,
+                    },
+                    naga::ImageClass::Storage { format, access } => {
+                        wgpu::BindingType::StorageTexture {
+                            access: storage_access(access),
+                            format: storage_format(format),
+                            view_dimension,
+                        }
+                    }
+                }
+            }
+            _ => return Err(unknown()),
+        },
+        _ => return Err(unknown()),
+    };
+    Ok(ty)
+}
+
+const fn view_dimension(
+    dim: naga::ImageDimension,
+    arrayed: bool,
+) -> Option<wgpu::TextureViewDimension> {
+    Some(match (dim, arrayed) {
+        (naga::ImageDimension::D1, false) => wgpu::TextureViewDimension::D1,
+        (naga::ImageDimension::D2, false) => wgpu::TextureViewDimension::D2,
+        (naga::ImageDimension::D2, true) => wgpu::TextureViewDimension::D2Array,
+        (naga::ImageDimension::D3, false) => wgpu::TextureViewDimension::D3,
+        (naga::ImageDimension::Cube, false) => wgpu::TextureViewDimension::Cube,
+        (naga::ImageDimension::Cube, true) => wgpu::TextureViewDimension::CubeArray,
+        _ => return None,
+    })
+}
+
+fn storage_access(access: naga::StorageAccess) -> wgpu::StorageTextureAccess {
+    if access == naga::StorageAccess::LOAD {
+        wgpu::StorageTextureAccess::ReadOnly
+    } else if access == naga::StorageAccess::STORE {
+        wgpu::StorageTextureAccess::WriteOnly
+    } else {
+        wgpu::StorageTextureAccess::ReadWrite
+    }
+}
+
+const fn storage_format(format: naga::StorageFormat) -> wgpu::TextureFormat {
+    use naga::StorageFormat as Sf;
+    use wgpu::TextureFormat as Tf;
+    match format {
+        Sf::R8Unorm => Tf::R8Unorm,
+        Sf::R8Snorm => Tf::R8Snorm,
+        Sf::R8Uint => Tf::R8Uint,
+        Sf::R8Sint => Tf::R8Sint,
+        Sf::R16Uint => Tf::R16Uint,
+        Sf::R16Sint => Tf::R16Sint,
+        Sf::R16Float => Tf::R16Float,
+        Sf::Rg8Unorm => Tf::Rg8Unorm,
+        Sf::Rg8Snorm => Tf::Rg8Snorm,
+        Sf::Rg8Uint => Tf::Rg8Uint,
+        Sf::Rg8Sint => Tf::Rg8Sint,
+        Sf::R32Uint => Tf::R32Uint,
+        Sf::R32Sint => Tf::R32Sint,
+        Sf::R32Float => Tf::R32Float,
+        Sf::Rg16Uint => Tf::Rg16Uint,
+        Sf::Rg16Sint => Tf::Rg16Sint,
+        Sf::Rg16Float => Tf::Rg16Float,
+        Sf::Rgba8Unorm => Tf::Rgba8Unorm,
+        Sf::Rgba8Snorm => Tf::Rgba8Snorm,
+        Sf::Rgba8Uint => Tf::Rgba8Uint,
+        Sf::Rgba8Sint => Tf::Rgba8Sint,
+        Sf::Rgb10a2Unorm => Tf::Rgb10a2Unorm,
+        Sf::Rg11b10Float => Tf::Rg11b10Float,
+        Sf::Rg32Uint => Tf::Rg32Uint,
+        Sf::Rg32Sint => Tf::Rg32Sint,
+        Sf::Rg32Float => Tf::Rg32Float,
+        Sf::Rgba16Uint => Tf::Rgba16Uint,
+        Sf::Rgba16Sint => Tf::Rgba16Sint,
+        Sf::Rgba16Float => Tf::Rgba16Float,
+        Sf::Rgba32Uint => Tf::Rgba32Uint,
+        Sf::Rgba32Sint => Tf::Rgba32Sint,
+        Sf::Rgba32Float => Tf::Rgba32Float,
+    }
+}
@@ -0,0 +1,81 @@
+use std::ops::Deref;
+use std::time::SystemTime;
+
+use tracing::warn;
+
+use crate::{PipelineBuilder, RenderPipeline};
+
+/// A render pipeline that is rebuilt when its shader files change on disk.
+/// Only shaders loaded with `load_vertex()` or `load_fragment()` are watched.
+/// Create this with `PipelineBuilder::watch()`.
+pub struct WatchedPipeline<'a> {
+    builder: PipelineBuilder<'a>,
+    pipeline: RenderPipeline,
+    /// Watched paths and their last known modification time
+    files: Vec<(&'a str, Option<SystemTime>)>,
+}
+impl<'a> WatchedPipeline<'a> {
+    /// Checks the shader files for changes and rebuilds the pipeline if any
+    /// changed. If the rebuild fails, the error is logged and the old pipeline
+    /// is kept.
+    /// Returns `true` if the pipeline was rebuilt.
+    pub fn poll_reload(&mut self) -> bool {
+        let mut changed = false;
+        for (path, modified) in &mut self.files {
+            let current = Self::modified(path);
+            if current != *modified {
+                *modified = current;
+                changed = true;
+            }
+        }
+        if !changed {
+            return false;
+        }
+
+        match self.builder.try_create() {
+            Ok(pipeline) => {
+                self.pipeline = pipeline;
+                true
+            }
+            Err(e) => {
+                warn!("Failed to reload pipeline, keeping the old one: {}", e);
+                false
+            }
+        }
+    }
+
+    /// The paths of the watched shader files.
+    pub fn paths(&self) -> impl Iterator<Item = &str> {
+        self.files.iter().map(|(path, _)| *path)
+    }
+
+    fn modified(path: &str) -> Option<SystemTime> {
+        std::fs::metadata(path).and_then(|m| m.modified()).ok()
+    }
+}
+impl Deref for WatchedPipeline<'_> {
+    type Target = RenderPipeline;
+    fn deref(&self) -> &Self::Target {
+        &self.pipeline
+    }
+}
+
+impl<'a> PipelineBuilder<'a> {
+    /// Creates the pipeline and watches its shader files for changes.
+    /// Call `WatchedPipeline::poll_reload()` to rebuild on change, e.g. once
+    /// per frame.
+    #[must_use]
+    pub fn watch(self) -> WatchedPipeline<'a> {
+        let pipeline = self.create();
+        let files = self
+            .shader_paths()
+            .into_iter()
+            .map(|path| (path, WatchedPipeline::modified(path)))
+            .collect();
+        WatchedPipeline {
+            builder: self,
+            pipeline,
+            files,
+        }
+    }
+}
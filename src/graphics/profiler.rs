@@ -4,13 +4,22 @@ use std::{
     mem::size_of,
 };
 
+use tracing::warn;
+
+use crate::GpuError;
+
 mod marker;
+use marker::Marker;
 
 mod section;
+pub use section::ProfileScope;
 
 mod queryset;
 pub use queryset::QuerySet;
 
+mod debug_group;
+pub use debug_group::DebugGroupGuard;
+
 pub const MAX_QUERIES: u32 = wgpu::QUERY_SET_MAX_QUERIES;
 pub const MAX_BUFFER_SIZE: u64 = MAX_QUERIES as u64 * size_of::<u64>() as u64;
 
@@ -29,8 +38,13 @@ pub struct Profiler {
     pub(crate) stats: Option<QuerySet>,
     /// The amount of nanoseconds each tick of a timestamp query represents
     pub timestamp_period: f32,
-    markers: RefCell<Vec<String>>,
+    markers: RefCell<Vec<Marker>>,
     resolved: Cell<bool>,
+    /// Whether a timestamp has been skipped for exceeding `MAX_QUERIES`
+    exhausted: Cell<bool>,
+    /// The number of pipeline statistics queries written this frame.
+    /// These are counted separately from markers, since only render passes write them.
+    stats_count: Cell<u32>,
 }
 
 impl Profiler {
@@ -44,6 +58,11 @@ impl Profiler {
         self.query_count() - 1
     }
 
+    /// The number of queries that have been written, which is at most `MAX_QUERIES`.
+    pub fn written_query_count(&self) -> u32 {
+        self.query_count().min(MAX_QUERIES)
+    }
+
     #[must_use]
     pub fn new(device: &wgpu::Device, queue: &wgpu::Queue) -> Self {
         // Timestamp period is multiplied by the time span to get the duration in nanoseconds
@@ -67,22 +86,75 @@ impl Profiler {
             timestamp_period,
             markers: RefCell::new(Vec::new()),
             resolved: Cell::new(false),
+            exhausted: Cell::new(false),
+            stats_count: Cell::new(0),
         }
     }
 
     pub(crate) fn begin_section(&self, label: &str) {
-        self.markers.borrow_mut().push(label.to_string());
+        self.markers
+            .borrow_mut()
+            .push(Marker::Section(label.to_string()));
+    }
+
+    /// Writes the start timestamp of a scope, returning its query index.
+    /// Returns `None` if timestamps are not supported.
+    pub(crate) fn begin_scope(
+        &self,
+        label: &str,
+        encoder: &mut wgpu::CommandEncoder,
+    ) -> Option<u32> {
+        // Scopes are only recorded when there are timestamps to pair
+        self.timestamp.as_ref()?;
+        self.markers
+            .borrow_mut()
+            .push(Marker::ScopeBegin(label.to_string(), None));
+        self.timestamp(label, encoder);
+        Some(self.query_index())
+    }
+
+    /// Writes the end timestamp of the scope which began at query index `begin`.
+    pub(crate) fn end_scope(&self, begin: u32, encoder: &mut wgpu::CommandEncoder) {
+        self.markers.borrow_mut().push(Marker::ScopeEnd);
+        let end = self.query_index();
+        if let Some(Marker::ScopeBegin(_, scope_end)) =
+            self.markers.borrow_mut().get_mut(begin as usize)
+        {
+            *scope_end = Some(end);
+        }
+        self.timestamp("", encoder);
     }
 
+    /// Writes a timestamp for the last marker.
+    /// Timestamps past `MAX_QUERIES` in a frame are skipped, and are missing from the report.
     pub fn timestamp(&self, _label: &str, encoder: &mut wgpu::CommandEncoder) {
         if let Some(ts_qs) = &self.timestamp {
-            encoder.write_timestamp(ts_qs, self.query_index());
+            let index = self.query_index();
+            if index >= MAX_QUERIES {
+                if !self.exhausted.replace(true) {
+                    warn!(
+                        "Profiler exceeded {} queries in one frame, skipping timestamps",
+                        MAX_QUERIES
+                    );
+                }
+                return;
+            }
+            encoder.write_timestamp(ts_qs, index);
         }
     }
 
-    pub fn begin_stats(&self, render_pass: &mut wgpu::RenderPass) {
-        if let Some(stats_qs) = &self.stats {
-            render_pass.begin_pipeline_statistics_query(stats_qs, self.query_index());
+    /// Begins a pipeline statistics query for the render pass.
+    /// Returns whether the query began, in which case `end_stats()` must be called
+    /// before the pass ends.
+    pub fn begin_stats(&self, render_pass: &mut wgpu::RenderPass) -> bool {
+        match &self.stats {
+            Some(stats_qs) if self.stats_count.get() < MAX_QUERIES => {
+                let index = self.stats_count.get();
+                render_pass.begin_pipeline_statistics_query(stats_qs, index);
+                self.stats_count.set(index + 1);
+                true
+            }
+            _ => false,
         }
     }
 
@@ -92,28 +164,49 @@ impl Profiler {
         }
     }
 
-    /// Must be called before get()
+    /// Resolves the queries written this frame so that they can be read.
+    /// Must be called before get(). `Frame` calls this before it is submitted.
     pub fn resolve(&self, encoder: &mut wgpu::CommandEncoder) {
-        self.stats
-            .as_ref()
-            .unwrap()
-            .resolve(self.query_count(), encoder);
+        // Only queries that were written can be resolved, otherwise the gpu may wait
+        // for them forever
+        if let Some(timestamp) = &self.timestamp {
+            timestamp.resolve(self.written_query_count(), encoder);
+        }
+        if let Some(stats) = &self.stats {
+            stats.resolve(self.stats_count.get(), encoder);
+        }
         // if !self.resolved.replace(true) {
         //     // If replace() returns false then the query set still needs to be resolved
         //     self.foreach_query_set(|query_set| query_set.resolve(self.query_count(), encoder));
         // }
     }
 
+    /// Reports the duration of each section and scope.
+    /// Sections are timed from the previous section, while scopes are timed from
+    /// their start to their end, so nested scopes each report their own duration.
+    /// Scopes are reported in the order they began.
     pub fn timestamp_report(&self, device: &wgpu::Device) -> Vec<(String, f32)> {
         let mut ret = vec![];
         if let Some(timestamp) = &self.timestamp {
-            if let Ok(val) = timestamp.get(device, self.query_count()) {
-                for (i, marker) in self.markers.borrow()[1..].iter().enumerate() {
-                    let start = val[i];
-                    let end = val[i + 1];
-                    let duration = self.ts_to_millis(end - start);
-                    // println!("{} took {} ms", marker, duration / 1_000_000.0);
-                    ret.push((marker.clone(), duration / 1_000_000.0));
+            if let Ok(val) = timestamp.get(device, self.written_query_count()) {
+                let mut last_section = None;
+                for (i, marker) in self.markers.borrow().iter().enumerate() {
+                    let (label, start, end) = match *marker {
+                        Marker::Section(ref label) => match last_section.replace(i) {
+                            Some(start) => (label, start, i),
+                            None => continue,
+                        },
+                        Marker::ScopeBegin(ref label, Some(end)) => (label, i, end as usize),
+                        _ => continue,
+                    };
+                    // Skip markers whose timestamps were not written
+                    let (start, end) = match (val.get(start), val.get(end)) {
+                        (Some(start), Some(end)) => (start, end),
+                        _ => continue,
+                    };
+                    let duration = self.ts_to_millis(end.saturating_sub(*start));
+                    // println!("{} took {} ms", label, duration / 1_000_000.0);
+                    ret.push((label.clone(), duration / 1_000_000.0));
                 }
             }
         };
@@ -127,6 +220,35 @@ impl Profiler {
         ret
     }
 
+    /// The pipeline statistics summed over every render pass, in the order of
+    /// `PIPELINE_STATISTICS_LABELS`.
+    /// # Errors
+    /// Errors with `QueryNone` if pipeline statistics are not supported, or with
+    /// `BufferAsyncError` if the results could not be read
+    pub fn statistics(&self, device: &wgpu::Device) -> Result<[u64; 5], GpuError> {
+        let stats = self.stats.as_ref().ok_or(GpuError::QueryNone)?;
+
+        let mut ret = [0; 5];
+        for (i, stat) in stats
+            .get(device, self.stats_count.get())?
+            .iter()
+            .enumerate()
+        {
+            ret[i % 5] += stat;
+        }
+        Ok(ret)
+    }
+
+    /// Reports the pipeline statistics summed over every render pass, labeled with
+    /// `PIPELINE_STATISTICS_LABELS`. Must be called after `resolve()`.
+    /// Returns an empty report if pipeline statistics are not supported.
+    pub fn statistics_report(&self, device: &wgpu::Device) -> Vec<(&'static str, u64)> {
+        match self.statistics(device) {
+            Ok(stats) => PIPELINE_STATISTICS_LABELS.into_iter().zip(stats).collect(),
+            Err(_) => Vec::new(),
+        }
+    }
+
     #[deprecated]
     #[allow(dead_code)]
     fn foreach_query_set<F, T>(&self, mut f: F) -> Vec<T>
@@ -140,6 +262,7 @@ impl Profiler {
 
     pub fn clear(&self) {
         self.markers.borrow_mut().clear();
+        self.stats_count.set(0);
         self.resolved.set(false);
     }
 
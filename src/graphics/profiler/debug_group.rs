@@ -0,0 +1,37 @@
+use std::ops::{Deref, DerefMut};
+
+/// A debug group, created with `CommandEncoder::debug_group()` or
+/// `RenderPass::debug_group()`.
+/// Commands recorded through the group are nested under its label in graphics
+/// debuggers such as RenderDoc. The group is popped on drop.
+/// Groups can be nested by creating a group from the group itself, as it derefs to
+/// the encoder or pass it was created with.
+pub struct DebugGroupGuard<'a, T> {
+    target: &'a mut T,
+    pop: fn(&mut T),
+}
+impl<'a, T> DebugGroupGuard<'a, T> {
+    /// `pop` is called with `target` on drop, and should pop the group that was
+    /// pushed on it.
+    pub(crate) fn new(target: &'a mut T, pop: fn(&mut T)) -> Self {
+        Self { target, pop }
+    }
+}
+
+impl<T> Deref for DebugGroupGuard<'_, T> {
+    type Target = T;
+    fn deref(&self) -> &Self::Target {
+        self.target
+    }
+}
+impl<T> DerefMut for DebugGroupGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.target
+    }
+}
+
+impl<T> Drop for DebugGroupGuard<'_, T> {
+    fn drop(&mut self) {
+        (self.pop)(self.target);
+    }
+}
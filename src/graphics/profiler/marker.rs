@@ -3,3 +3,15 @@ pub struct TimestampMarker<'a> {
     pub label: &'a str,
     pub duration: f32,
 }
+
+/// A timestamp query written by the profiler.
+/// The query index of a marker is its index in the profiler's marker list.
+#[derive(Debug)]
+pub(crate) enum Marker {
+    /// A sequential section, which lasts until the next section begins
+    Section(String),
+    /// The start of a scope, with the query index of its end once it is dropped
+    ScopeBegin(String, Option<u32>),
+    /// The end of a scope
+    ScopeEnd,
+}
@@ -6,6 +6,9 @@ use futures::executor::block_on;
 use crate::QUERYSET_BUFFER_USAGE;
 use crate::{GpuError, ScopedBufferView};
 
+/// A set of GPU queries and the buffer they are resolved into.
+/// Only timestamp and pipeline statistics queries are supported. Occlusion queries
+/// need a query set on the render pass descriptor, which wgpu 0.12 does not have.
 pub struct QuerySet {
     pub(crate) inner: wgpu::QuerySet,
     pub(crate) buffer: wgpu::Buffer,
@@ -74,8 +77,12 @@ impl QuerySet {
         query_ty_size(self.ty)
     }
 
+    /// Resolves the first `count` queries into the buffer.
+    /// Each query resolves to `query_size()` u64s.
     pub fn resolve(&self, count: u32, encoder: &mut wgpu::CommandEncoder) {
-        encoder.resolve_query_set(self, 0..count * self.query_size(), &self.buffer, 0)
+        if count > 0 {
+            encoder.resolve_query_set(self, 0..count, &self.buffer, 0)
+        }
     }
 
     /// Must first call resolve()
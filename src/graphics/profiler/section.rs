@@ -1,21 +1,41 @@
-#![allow(dead_code)]
-///! Dead code is allowed because this is currently unused
-use crate::Profiler;
+use std::ops::{Deref, DerefMut};
 
-/// A convenience struct for creating a profiler section.
-pub struct Section<'a> {
-    pub(crate) profiler: &'a Profiler,
-    pub(crate) label: String,
+use crate::CommandEncoder;
+
+/// A timed profiler scope, created with `Gpu::profile_scope()`.
+/// Writes a start timestamp on creation and an end timestamp on drop.
+/// Scopes can be nested by passing the scope itself as the encoder of the
+/// inner scope, as it derefs to the encoder it was created with.
+pub struct ProfileScope<'a> {
+    pub(crate) encoder: &'a mut CommandEncoder,
+    /// Query index of the start timestamp, if timestamps are supported
+    pub(crate) begin: Option<u32>,
+}
+impl<'a> ProfileScope<'a> {
+    pub fn new(label: &str, encoder: &'a mut CommandEncoder) -> ProfileScope<'a> {
+        let gpu = encoder.gpu.clone();
+        let begin = gpu.profiler.begin_scope(label, encoder);
+        ProfileScope { encoder, begin }
+    }
+}
+
+impl Deref for ProfileScope<'_> {
+    type Target = CommandEncoder;
+    fn deref(&self) -> &Self::Target {
+        self.encoder
+    }
 }
-impl<'a> Section<'a> {
-    pub fn new(profiler: &'a Profiler, label: &'a str) -> Section<'a> {
-        let label = label.to_string();
-        Section { profiler, label }
+impl DerefMut for ProfileScope<'_> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.encoder
     }
 }
 
-impl Drop for Section<'_> {
+impl Drop for ProfileScope<'_> {
     fn drop(&mut self) {
-        // Unused
+        if let Some(begin) = self.begin {
+            let gpu = self.encoder.gpu.clone();
+            gpu.profiler.end_scope(begin, self.encoder);
+        }
     }
 }
@@ -0,0 +1,153 @@
+use std::collections::{BTreeSet, HashMap};
+
+use crate::{CommandEncoder, GpuError};
+
+type RecordFn<'a> = Box<dyn FnOnce(&mut CommandEncoder) + 'a>;
+
+struct GraphPass<'a> {
+    name: String,
+    reads: Vec<String>,
+    writes: Vec<String>,
+    record: RecordFn<'a>,
+}
+
+/// A set of passes that are recorded in an order satisfying their dependencies.
+///
+/// Resources are identified by name. Each resource is either written by exactly one
+/// pass or imported from outside the graph with `import()`. A pass that reads a
+/// resource is recorded after the pass that writes it, regardless of the order the
+/// passes were added in. To render into the same texture more than once, give each
+/// write its own resource name (e.g. `mip1` and `mip1 blurred`).
+///
+/// Passes that do not depend on each other keep the order they were added in.
+#[derive(Default)]
+pub struct RenderGraph<'a> {
+    imports: Vec<String>,
+    passes: Vec<GraphPass<'a>>,
+}
+
+impl<'a> RenderGraph<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Declares a resource that is written outside of the graph, such as the frame's
+    /// surface texture, so that passes may read it.
+    pub fn import(&mut self, resource: &str) -> &mut Self {
+        self.imports.push(resource.to_owned());
+        self
+    }
+
+    /// Adds a pass that reads and writes the given resources.
+    /// `record` is called with the encoder when the graph is executed.
+    pub fn add_pass(
+        &mut self,
+        name: &str,
+        reads: &[&str],
+        writes: &[&str],
+        record: impl FnOnce(&mut CommandEncoder) + 'a,
+    ) -> &mut Self {
+        self.passes.push(GraphPass {
+            name: name.to_owned(),
+            reads: reads.iter().map(|&r| r.to_owned()).collect(),
+            writes: writes.iter().map(|&w| w.to_owned()).collect(),
+            record: Box::new(record),
+        });
+        self
+    }
+
+    /// Returns the names of the passes in the order they will be recorded.
+    /// # Errors
+    /// Errors if a resource is written more than once, if a pass reads a resource that
+    /// is never written, or if the passes depend on each other in a cycle
+    pub fn order(&self) -> Result<Vec<&str>, GpuError> {
+        Ok(self
+            .sorted()?
+            .into_iter()
+            .map(|i| self.passes[i].name.as_str())
+            .collect())
+    }
+
+    /// Records every pass into the encoder in dependency order.
+    /// # Errors
+    /// Errors if the graph is invalid, see `order()`. No passes are recorded in that case
+    pub fn execute(self, encoder: &mut CommandEncoder) -> Result<(), GpuError> {
+        let order = self.sorted()?;
+        let mut passes: Vec<_> = self.passes.into_iter().map(Some).collect();
+        for i in order {
+            if let Some(pass) = passes[i].take() {
+                (pass.record)(encoder);
+            }
+        }
+        Ok(())
+    }
+
+    fn sorted(&self) -> Result<Vec<usize>, GpuError> {
+        // Map each resource to the pass that writes it, or None if it is imported
+        let mut writers = HashMap::new();
+        for import in &self.imports {
+            writers.insert(import.as_str(), None);
+        }
+        for (i, pass) in self.passes.iter().enumerate() {
+            for write in &pass.writes {
+                if writers.insert(write.as_str(), Some(i)).is_some() {
+                    return Err(GpuError::InvalidRenderGraph(format!(
+                        "resource `{}` written by pass `{}` is already written",
+                        write, pass.name
+                    )));
+                }
+            }
+        }
+
+        // Count the unrecorded dependencies of each pass
+        let mut pending = vec![0_usize; self.passes.len()];
+        let mut dependents = vec![Vec::new(); self.passes.len()];
+        for (i, pass) in self.passes.iter().enumerate() {
+            for read in &pass.reads {
+                match writers.get(read.as_str()) {
+                    Some(Some(writer)) => {
+                        pending[i] += 1;
+                        dependents[*writer].push(i);
+                    }
+                    Some(None) => {}
+                    None => {
+                        return Err(GpuError::InvalidRenderGraph(format!(
+                            "pass `{}` reads `{}`, which is not written by any pass",
+                            pass.name, read
+                        )))
+                    }
+                }
+            }
+        }
+
+        // Kahn's algorithm, taking the earliest added pass that is ready
+        let mut ready: BTreeSet<usize> = (0..self.passes.len())
+            .filter(|&i| pending[i] == 0)
+            .collect();
+        let mut order = Vec::with_capacity(self.passes.len());
+        while let Some(i) = ready.pop_first() {
+            order.push(i);
+            for &dependent in &dependents[i] {
+                pending[dependent] -= 1;
+                if pending[dependent] == 0 {
+                    ready.insert(dependent);
+                }
+            }
+        }
+
+        if order.len() != self.passes.len() {
+            let stuck = self
+                .passes
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| pending[*i] > 0)
+                .map(|(_, pass)| pass.name.as_str())
+                .collect::<Vec<_>>();
+            return Err(GpuError::InvalidRenderGraph(format!(
+                "passes {:?} depend on each other in a cycle",
+                stuck
+            )));
+        }
+        Ok(order)
+    }
+}
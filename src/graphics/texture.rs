@@ -6,8 +6,18 @@ mod builder;
 pub use builder::*;
 mod format;
 pub use format::*;
+mod gbuffer;
+pub use gbuffer::*;
+mod blit;
+pub use blit::BlitCache;
+mod pool;
+pub use pool::{PooledTexture, TexturePool};
+#[cfg(feature = "image")]
+mod from_image;
 
-use crate::Gpu;
+use tracing::warn;
+
+use crate::{Gpu, GpuError};
 
 pub struct Texture<D>
 where
@@ -19,6 +29,10 @@ where
     pub format: TexFormat,
     pub size: D,
     pub usage: wgpu::TextureUsages,
+    /// The number of mip levels
+    pub mip_level_count: u32,
+    /// The number of samples per texel. Greater than 1 if multisampled
+    pub sample_count: u32,
 }
 impl<D> std::ops::Deref for Texture<D>
 where
@@ -43,43 +57,63 @@ impl<D> Texture<D>
 where
     D: crate::TextureDimensions,
 {
-    // FIXME
-    // pub fn new(gpu: GpuHandle, desc: &wgpu::TextureDescriptor) -> Self {
-    //     let inner = gpu.create_texture(desc);
-    //     let view = inner.create_view(&Default::default());
-    //     Self {
-    //         gpu,
-    //         inner,
-    //         view,
-    //         format: desc.format,
-    //         size: desc.size,
-    //         usage: desc.usage,
-    //     }
-    // }
+    /// Wraps a texture created outside of agpu, e.g. by another wgpu library,
+    /// creating a view of the whole texture.
+    /// `format`, `size` and `usage` must match the descriptor the texture was created
+    /// with, as wgpu does not expose it. The texture is assumed to have a single mip
+    /// level and sample; set `mip_level_count` and `sample_count` if it does not.
+    pub fn from_raw(
+        gpu: Gpu,
+        inner: wgpu::Texture,
+        format: wgpu::TextureFormat,
+        size: D,
+        usage: wgpu::TextureUsages,
+    ) -> Self {
+        let view = inner.create_view(&wgpu::TextureViewDescriptor {
+            dimension: Some(size.view_dim()),
+            ..Default::default()
+        });
+        Self {
+            gpu,
+            inner,
+            view,
+            format: TexFormat(format),
+            size,
+            usage,
+            mip_level_count: 1,
+            sample_count: 1,
+        }
+    }
 
     /// Resize the texture. Old contents are discarded (see resize_and_copy)
+    /// The mip level and sample count are kept.
     pub fn resize(&mut self, size: D) {
         let new_texture = self.gpu.create_texture(&wgpu::TextureDescriptor {
             // TODO: update label for texture on resize
             label: None,
             size: size.as_extent(),
-            // TODO: mip level count on resize??
-            mip_level_count: 1,
-            sample_count: 1,
+            mip_level_count: self.mip_level_count,
+            sample_count: self.sample_count,
             dimension: size.dim(),
             format: *self.format,
             usage: self.usage,
         });
 
         // Create a new view
-        let view = new_texture.create_view(&Default::default());
+        let view = new_texture.create_view(&wgpu::TextureViewDescriptor {
+            dimension: Some(size.view_dim()),
+            ..Default::default()
+        });
 
         self.inner = new_texture;
         self.size = size;
         self.view = view;
     }
 
-    /// Resize the texture and copy the contents
+    /// Resize the texture and copy the contents.
+    /// The mip level and sample count are kept, but only the first mip level is
+    /// copied. Multisampled textures can't be copied, so their contents are
+    /// discarded.
     pub fn resize_with_copy(&mut self, size: D) {
         let new_usage = self.usage | wgpu::TextureUsages::COPY_DST;
 
@@ -87,9 +121,8 @@ where
             // TODO: update label for texture on resize
             label: None,
             size: size.as_extent(),
-            // TODO: mip level count on resize??
-            mip_level_count: 1,
-            sample_count: 1,
+            mip_level_count: self.mip_level_count,
+            sample_count: self.sample_count,
             dimension: size.dim(),
             format: *self.format,
             usage: new_usage,
@@ -100,19 +133,35 @@ where
             wgpu::TextureFormat::Depth32Float
             | wgpu::TextureFormat::Depth24Plus
             | wgpu::TextureFormat::Depth24PlusStencil8 => {}
+            // Multisampled textures can't be copied
+            _ if self.sample_count > 1 => {
+                warn!("Resized a multisampled texture, contents were not preserved");
+            }
             _ => {
+                // Only copy the region that exists in both textures
+                let old = self.size.as_extent();
+                let new = size.as_extent();
+                let extent = wgpu::Extent3d {
+                    width: old.width.min(new.width),
+                    height: old.height.min(new.height),
+                    depth_or_array_layers: old.depth_or_array_layers.min(new.depth_or_array_layers),
+                };
+
                 let mut enc = self.gpu.create_command_encoder("Texture resize encoder");
                 enc.copy_texture_to_texture(
                     self.inner.as_image_copy(),
                     new_texture.as_image_copy(),
-                    self.size.as_extent(),
+                    extent,
                 );
                 self.gpu.queue.submit([enc.finish()]);
             }
         }
 
         // Create a new view
-        let view = new_texture.create_view(&Default::default());
+        let view = new_texture.create_view(&wgpu::TextureViewDescriptor {
+            dimension: Some(size.view_dim()),
+            ..Default::default()
+        });
 
         self.inner = new_texture;
         self.size = size;
@@ -127,7 +176,57 @@ where
         self.write_block(D::ZEROED, size, data)
     }
 
+    /// Writes a block of `size` texels starting at `texel`.
+    /// The data is expected to be tightly packed to the block, i.e. each row is
+    /// `size.width()` texels and each layer is `size.height()` rows, regardless of
+    /// the size of the texture.
     pub fn write_block<T>(&self, texel: D, size: D, data: &[T])
+    where
+        T: bytemuck::Pod,
+    {
+        self.write_impl(0, texel, size, size.width(), data);
+    }
+
+    /// Writes to the given mip level, e.g. for uploading precomputed mips.
+    /// The data rows are expected to be the width of the mip level
+    /// (`width >> mip`).
+    /// # Panics
+    /// Panics if `mip` is not less than `mip_level_count`
+    pub fn write_mip<T>(&self, mip: u32, size: D, data: &[T])
+    where
+        T: bytemuck::Pod,
+    {
+        assert!(
+            mip < self.mip_level_count,
+            "Mip level {} is out of range for a texture with {} mips",
+            mip,
+            self.mip_level_count
+        );
+        let mip_width = (self.size.width() >> mip).max(1);
+        self.write_impl(mip, D::ZEROED, size, mip_width, data);
+    }
+
+    /// Creates a view of a single mip level of the texture,
+    /// e.g. for rendering into or sampling from one level while generating mips.
+    /// # Panics
+    /// Panics if `mip` is not less than `mip_level_count`
+    pub fn create_view_mip(&self, mip: u32) -> wgpu::TextureView {
+        assert!(
+            mip < self.mip_level_count,
+            "Mip level {} is out of range for a texture with {} mips",
+            mip,
+            self.mip_level_count
+        );
+        self.inner.create_view(&wgpu::TextureViewDescriptor {
+            label: Some("mip view"),
+            dimension: Some(self.size.view_dim()),
+            base_mip_level: mip,
+            mip_level_count: std::num::NonZeroU32::new(1),
+            ..Default::default()
+        })
+    }
+
+    fn write_impl<T>(&self, mip: u32, texel: D, size: D, row_width: u32, data: &[T])
     where
         T: bytemuck::Pod,
     {
@@ -136,7 +235,7 @@ where
         self.gpu.queue.write_texture(
             wgpu::ImageCopyTextureBase {
                 texture: &self.inner,
-                mip_level: 0,
+                mip_level: mip,
                 origin: texel.as_origin(),
                 aspect: wgpu::TextureAspect::All,
             },
@@ -145,9 +244,10 @@ where
                 // This is 0 because our source should not be offset
                 offset: 0,
                 bytes_per_row: std::num::NonZeroU32::new(
-                    size.width() * self.format.describe().block_size as u32,
+                    row_width * self.format.describe().block_size as u32,
                 ),
-                rows_per_image: None,
+                // Layers of the data are as tall as the written block
+                rows_per_image: std::num::NonZeroU32::new(size.height()),
             },
             size.as_extent(),
         )
@@ -193,15 +293,249 @@ where
 
         staging_buf.download_immediately()
     }
+
+    /// Reads the bytes of the texel at (`x`, `y`) of the first mip and layer,
+    /// e.g. to pick the object under the cursor from an ID texture.
+    /// Only the one texel is copied, but rows copied to a buffer are padded to
+    /// `wgpu::COPY_BYTES_PER_ROW_ALIGNMENT`, so this still uses a 256 byte staging buffer.
+    /// The texture must be created with `allow_copy_from()`.
+    /// Blocks until the texel is read.
+    /// # Errors
+    /// Errors with `UnsupportedFormat` for compressed formats, with `ValidationError` if
+    /// the texel is out of bounds or the texture lacks `COPY_SRC`, or with
+    /// `BufferAsyncError` if the staging buffer could not be mapped
+    pub fn read_pixel(&self, x: u32, y: u32) -> Result<Vec<u8>, GpuError> {
+        let format = self.format.describe();
+        if format.block_dimensions != (1, 1) {
+            return Err(GpuError::UnsupportedFormat(*self.format));
+        }
+        if x >= self.size.width() || y >= self.size.height() {
+            return Err(GpuError::ValidationError(format!(
+                "pixel ({}, {}) is out of bounds for texture of size {:?}",
+                x,
+                y,
+                self.size.as_extent()
+            )));
+        }
+        if !self.usage.contains(wgpu::TextureUsages::COPY_SRC) {
+            return Err(GpuError::ValidationError(
+                "texture is missing COPY_SRC usage".to_owned(),
+            ));
+        }
+
+        let row_size = wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+        let staging_buf = self
+            .gpu
+            .new_buffer("texture read pixel staging buffer")
+            .allow_copy_to()
+            .allow_map_read()
+            .create_uninit(u64::from(row_size));
+
+        let mut enc = self.gpu.create_command_encoder("texture read pixel enc");
+        enc.copy_texture_to_buffer(
+            wgpu::ImageCopyTexture {
+                texture: &self.inner,
+                mip_level: 0,
+                origin: wgpu::Origin3d { x, y, z: 0 },
+                aspect: wgpu::TextureAspect::All,
+            },
+            wgpu::ImageCopyBuffer {
+                buffer: &staging_buf,
+                layout: wgpu::ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: std::num::NonZeroU32::new(row_size),
+                    rows_per_image: None,
+                },
+            },
+            wgpu::Extent3d {
+                width: 1,
+                height: 1,
+                depth_or_array_layers: 1,
+            },
+        );
+        self.gpu.queue.submit([enc.finish()]);
+
+        staging_buf.with_mapped_read(0..u64::from(row_size), |bytes| {
+            bytes[..format.block_size as usize].to_vec()
+        })
+    }
+
+    /// Reads the depth values of the first mip and layer of a `Depth32Float` texture,
+    /// e.g. to inspect depth issues while debugging.
+    /// The values are the stored depth, row by row without padding, so the depth of
+    /// texel (`x`, `y`) is at `y * width + x`.
+    ///
+    /// Rows copied to a buffer must be a multiple of `wgpu::COPY_BYTES_PER_ROW_ALIGNMENT`
+    /// bytes, so each row of `width` f32s is padded up to the alignment in the staging
+    /// buffer, and the padding is stripped when the rows are read back.
+    ///
+    /// The texture must be created with `allow_copy_from()`. Blocks until the depth is read.
+    /// # Errors
+    /// Errors with `UnsupportedFormat` for depth formats that cannot be copied, such as
+    /// `Depth24Plus`, with `ValidationError` if the texture is multisampled or lacks
+    /// `COPY_SRC`, or with `BufferAsyncError` if the staging buffer could not be mapped
+    pub fn read_depth(&self) -> Result<Vec<f32>, GpuError> {
+        // Only depth formats with a defined layout can be copied
+        if *self.format != wgpu::TextureFormat::Depth32Float {
+            return Err(GpuError::UnsupportedFormat(*self.format));
+        }
+        if self.sample_count > 1 {
+            return Err(GpuError::ValidationError(
+                "cannot copy multisampled depth texture".to_owned(),
+            ));
+        }
+        if !self.usage.contains(wgpu::TextureUsages::COPY_SRC) {
+            return Err(GpuError::ValidationError(
+                "texture is missing COPY_SRC usage".to_owned(),
+            ));
+        }
+
+        let bytes = self.read_rows(wgpu::TextureAspect::DepthOnly, 4)?;
+        Ok(bytes
+            .chunks_exact(4)
+            .map(|b| f32::from_ne_bytes([b[0], b[1], b[2], b[3]]))
+            .collect())
+    }
+
+    /// Reads the first mip and layer of an 8 bit RGBA or BGRA texture as RGBA bytes,
+    /// row by row without padding, e.g. to save a screenshot of the surface as a PNG.
+    /// `Bgra8Unorm` and `Bgra8UnormSrgb` are swizzled to RGBA, and `Rgba8Unorm` and
+    /// `Rgba8UnormSrgb` are returned as is. See `read_immediately()` for the raw bytes.
+    ///
+    /// The texture must be created with `allow_copy_from()`. Blocks until the texture is read.
+    /// # Errors
+    /// Errors with `UnsupportedFormat` for other formats, including compressed formats
+    /// whose channels cannot be swizzled, with `ValidationError` if the texture is
+    /// multisampled or lacks `COPY_SRC`, or with `BufferAsyncError` if the staging
+    /// buffer could not be mapped
+    pub fn read_immediately_rgba(&self) -> Result<Vec<u8>, GpuError> {
+        let bgra = match *self.format {
+            wgpu::TextureFormat::Rgba8Unorm | wgpu::TextureFormat::Rgba8UnormSrgb => false,
+            wgpu::TextureFormat::Bgra8Unorm | wgpu::TextureFormat::Bgra8UnormSrgb => true,
+            _ => return Err(GpuError::UnsupportedFormat(*self.format)),
+        };
+        if self.sample_count > 1 {
+            return Err(GpuError::ValidationError(
+                "cannot copy multisampled texture".to_owned(),
+            ));
+        }
+        if !self.usage.contains(wgpu::TextureUsages::COPY_SRC) {
+            return Err(GpuError::ValidationError(
+                "texture is missing COPY_SRC usage".to_owned(),
+            ));
+        }
+
+        let mut bytes = self.read_rows(wgpu::TextureAspect::All, 4)?;
+        if bgra {
+            for texel in bytes.chunks_exact_mut(4) {
+                texel.swap(0, 2);
+            }
+        }
+        Ok(bytes)
+    }
+
+    /// Copies the first mip and layer into a staging buffer and reads it back with
+    /// the row padding stripped, see `read_depth()`.
+    fn read_rows(&self, aspect: wgpu::TextureAspect, texel_size: u32) -> Result<Vec<u8>, GpuError> {
+        let (width, height) = (self.size.width(), self.size.height());
+        let row_bytes = width * texel_size;
+        let align_mask = wgpu::COPY_BYTES_PER_ROW_ALIGNMENT - 1;
+        let padded_row_bytes = (row_bytes + align_mask) & !align_mask;
+        let read_size = u64::from(padded_row_bytes) * u64::from(height);
+        let staging_buf = self
+            .gpu
+            .new_buffer("texture read rows staging buffer")
+            .allow_copy_to()
+            .allow_map_read()
+            .create_uninit(read_size);
+
+        let mut enc = self.gpu.create_command_encoder("texture read rows enc");
+        enc.copy_texture_to_buffer(
+            wgpu::ImageCopyTexture {
+                texture: &self.inner,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+                aspect,
+            },
+            wgpu::ImageCopyBuffer {
+                buffer: &staging_buf,
+                layout: wgpu::ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: std::num::NonZeroU32::new(padded_row_bytes),
+                    rows_per_image: None,
+                },
+            },
+            wgpu::Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+        );
+        self.gpu.queue.submit([enc.finish()]);
+
+        staging_buf.with_mapped_read(0..read_size, |bytes| {
+            bytes
+                .chunks_exact(padded_row_bytes as usize)
+                .flat_map(|row| &row[..row_bytes as usize])
+                .copied()
+                .collect()
+        })
+    }
+}
+
+impl Texture<D2Array> {
+    /// Creates a 2D view of a single layer of the texture array.
+    /// # Panics
+    /// Panics if `layer` is not less than the number of layers
+    pub fn create_view_layer(&self, layer: u32) -> wgpu::TextureView {
+        assert!(
+            layer < self.size.depth(),
+            "Layer {} is out of range for a texture array with {} layers",
+            layer,
+            self.size.depth()
+        );
+        self.inner.create_view(&wgpu::TextureViewDescriptor {
+            label: Some("layer view"),
+            dimension: Some(wgpu::TextureViewDimension::D2),
+            base_array_layer: layer,
+            array_layer_count: std::num::NonZeroU32::new(1),
+            ..Default::default()
+        })
+    }
 }
 
 pub type D1 = (u32,);
 pub type D2 = (u32, u32);
 pub type D3 = (u32, u32, u32);
 
+/// 2D texture array dimensions: (width, height, layers).
+/// This is distinct from `D3` because array layers are not a third dimension;
+/// the texture is created as `TextureDimension::D2` with multiple layers.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct D2Array(pub u32, pub u32, pub u32);
+
+/// The number of mip levels in a full mip chain down to 1x1 for a texture of the
+/// given size, `max(1, floor(log2(max(width, height))) + 1)` as wgpu expects.
+pub const fn mip_level_count(width: u32, height: u32) -> u32 {
+    let size = if width > height { width } else { height };
+    if size == 0 {
+        1
+    } else {
+        u32::BITS - size.leading_zeros()
+    }
+}
+
 pub trait TextureDimensions: Copy {
     const ZEROED: Self;
     fn dim(&self) -> wgpu::TextureDimension;
+    /// The dimension of a view over the whole texture
+    fn view_dim(&self) -> wgpu::TextureViewDimension {
+        match self.dim() {
+            wgpu::TextureDimension::D1 => wgpu::TextureViewDimension::D1,
+            wgpu::TextureDimension::D2 => wgpu::TextureViewDimension::D2,
+            wgpu::TextureDimension::D3 => wgpu::TextureViewDimension::D3,
+        }
+    }
     fn as_extent(&self) -> wgpu::Extent3d;
     fn as_origin(&self) -> wgpu::Origin3d;
     fn width(&self) -> u32;
@@ -239,6 +573,40 @@ impl TextureDimensions for (u32, u32, u32) {
     }
 }
 
+impl TextureDimensions for D2Array {
+    const ZEROED: Self = D2Array(0, 0, 0);
+    fn dim(&self) -> wgpu::TextureDimension {
+        wgpu::TextureDimension::D2
+    }
+    fn view_dim(&self) -> wgpu::TextureViewDimension {
+        wgpu::TextureViewDimension::D2Array
+    }
+    fn as_extent(&self) -> wgpu::Extent3d {
+        wgpu::Extent3d {
+            width: self.0,
+            height: self.1,
+            depth_or_array_layers: self.2,
+        }
+    }
+    fn as_origin(&self) -> wgpu::Origin3d {
+        wgpu::Origin3d {
+            x: self.0,
+            y: self.1,
+            z: self.2,
+        }
+    }
+    fn width(&self) -> u32 {
+        self.0
+    }
+    fn height(&self) -> u32 {
+        self.1
+    }
+    /// The number of array layers
+    fn depth(&self) -> u32 {
+        self.2
+    }
+}
+
 impl TextureDimensions for (u32, u32) {
     const ZEROED: Self = (0, 0);
     fn dim(&self) -> wgpu::TextureDimension {
@@ -0,0 +1,226 @@
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
+
+use tracing::warn;
+
+use crate::{CommandEncoder, Gpu, GpuError, Texture, TextureDimensions, D2};
+
+/// The pipelines used by `Gpu::blit()`, created on first use for each destination
+/// format and whether the source can be filtered.
+#[derive(Debug, Default)]
+pub struct BlitCache {
+    pipelines: RefCell<HashMap<(wgpu::TextureFormat, bool), Rc<BlitPipeline>>>,
+}
+impl BlitCache {
+    /// The number of cached pipelines
+    pub fn len(&self) -> usize {
+        self.pipelines.borrow().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+// Only the wgpu objects are kept, since holding a `Gpu` in the cache would keep it
+// alive forever
+#[derive(Debug)]
+struct BlitPipeline {
+    pipeline: wgpu::RenderPipeline,
+    layout: wgpu::BindGroupLayout,
+}
+
+impl Gpu {
+    /// Copies the contents of `src` into `dst` and submits the copy.
+    /// The textures must have the same format and size, and `src` must be created
+    /// with `allow_copy_from()`. Use `blit_texture()` to copy between formats.
+    /// # Errors
+    /// Errors with `UnsupportedFormat` if the formats differ, or `ValidationError` if
+    /// the sizes differ or `src` lacks `COPY_SRC`
+    pub fn copy_texture<D>(&self, src: &Texture<D>, dst: &Texture<D>) -> Result<(), GpuError>
+    where
+        D: TextureDimensions,
+    {
+        if src.format != dst.format {
+            warn!(
+                "Cannot copy {:?} texture to {:?} texture, use blit_texture() to convert",
+                *src.format, *dst.format
+            );
+            return Err(GpuError::UnsupportedFormat(*dst.format));
+        }
+        if src.size.as_extent() != dst.size.as_extent() {
+            return Err(GpuError::ValidationError(format!(
+                "cannot copy texture of size {:?} to texture of size {:?}",
+                src.size.as_extent(),
+                dst.size.as_extent()
+            )));
+        }
+        if !src.usage.contains(wgpu::TextureUsages::COPY_SRC) {
+            return Err(GpuError::ValidationError(
+                "source texture is missing COPY_SRC usage".to_owned(),
+            ));
+        }
+
+        let mut encoder = self.create_command_encoder("copy texture");
+        encoder.copy_texture_to_texture(
+            src.as_image_copy(),
+            dst.as_image_copy(),
+            src.size.as_extent(),
+        );
+        self.queue.submit([encoder.finish()]);
+        Ok(())
+    }
+
+    /// Draws `src` over the whole of `dst` and submits the draw, converting between
+    /// formats and scaling if the sizes differ.
+    /// `src` must be created with `allow_binding()` and `dst` with `as_render_target()`.
+    /// See `blit()` to record the draw into an existing encoder.
+    /// # Errors
+    /// Errors with `ValidationError` if the textures lack the required usages
+    pub fn blit_texture(&self, src: &Texture<D2>, dst: &Texture<D2>) -> Result<(), GpuError> {
+        if !dst.usage.contains(wgpu::TextureUsages::RENDER_ATTACHMENT) {
+            return Err(GpuError::ValidationError(
+                "destination texture is missing RENDER_ATTACHMENT usage".to_owned(),
+            ));
+        }
+
+        let mut encoder = self.create_command_encoder("blit texture");
+        self.blit(&mut encoder, src, &dst.view, *dst.format)?;
+        self.queue.submit([encoder.finish()]);
+        Ok(())
+    }
+
+    /// Records a draw of `src` over the whole of `dst_view`, which has the format
+    /// `dst_format`. The sampled value is written in the destination format, so this
+    /// converts between formats, including sRGB and linear, and scales if the sizes
+    /// differ. For example, this copies an HDR render target into the surface texture.
+    ///
+    /// The pipeline is created on the first blit to each destination format and
+    /// cached in `GpuCtx::blit_cache`.
+    /// # Errors
+    /// Errors with `ValidationError` if `src` lacks `TEXTURE_BINDING` usage or is
+    /// multisampled, or `UnsupportedFormat` if `src` cannot be sampled as a float
+    /// texture, e.g. integer and depth formats
+    pub fn blit(
+        &self,
+        encoder: &mut CommandEncoder,
+        src: &Texture<D2>,
+        dst_view: &wgpu::TextureView,
+        dst_format: wgpu::TextureFormat,
+    ) -> Result<(), GpuError> {
+        if !src.usage.contains(wgpu::TextureUsages::TEXTURE_BINDING) {
+            return Err(GpuError::ValidationError(
+                "source texture is missing TEXTURE_BINDING usage".to_owned(),
+            ));
+        }
+        if src.sample_count > 1 {
+            return Err(GpuError::ValidationError(
+                "cannot blit from a multisampled texture".to_owned(),
+            ));
+        }
+        // Formats that cannot be filtered must be sampled with a nearest sampler
+        let filterable = match crate::sample_type(*src.format) {
+            wgpu::TextureSampleType::Float { filterable } => {
+                filterable && self.supports_filtering(*src.format)
+            }
+            _ => return Err(GpuError::UnsupportedFormat(*src.format)),
+        };
+
+        let blit = self.blit_pipeline(dst_format, filterable)?;
+        let sampler = if filterable {
+            self.new_sampler("blit sampler").clamp().linear_filter()
+        } else {
+            self.new_sampler("blit sampler").clamp()
+        }
+        .create();
+        let bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("blit bind group"),
+            layout: &blit.layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(&src.view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Sampler(&sampler),
+                },
+            ],
+        });
+
+        let targets = [wgpu::RenderPassColorAttachment {
+            view: dst_view,
+            resolve_target: None,
+            ops: wgpu::Operations {
+                load: wgpu::LoadOp::Load,
+                store: true,
+            },
+        }];
+        encoder
+            .render_pass("blit pass", &targets)
+            .begin()
+            .set_pipeline(&blit.pipeline)
+            .set_bind_group(0, &bind_group, &[])
+            .draw_fullscreen();
+        Ok(())
+    }
+
+    fn blit_pipeline(
+        &self,
+        format: wgpu::TextureFormat,
+        filterable: bool,
+    ) -> Result<Rc<BlitPipeline>, GpuError> {
+        if let Some(blit) = self
+            .blit_cache
+            .pipelines
+            .borrow()
+            .get(&(format, filterable))
+        {
+            return Ok(blit.clone());
+        }
+
+        let sampler = if filterable {
+            wgpu::SamplerBindingType::Filtering
+        } else {
+            wgpu::SamplerBindingType::NonFiltering
+        };
+        let layout = self
+            .device
+            .create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                label: Some("blit bind group layout"),
+                entries: &[
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 0,
+                        visibility: wgpu::ShaderStages::FRAGMENT,
+                        ty: wgpu::BindingType::Texture {
+                            sample_type: wgpu::TextureSampleType::Float { filterable },
+                            view_dimension: wgpu::TextureViewDimension::D2,
+                            multisampled: false,
+                        },
+                        count: None,
+                    },
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 1,
+                        visibility: wgpu::ShaderStages::FRAGMENT,
+                        ty: wgpu::BindingType::Sampler(sampler),
+                        count: None,
+                    },
+                ],
+            });
+
+        let targets = [format.into()];
+        let pipeline = self
+            .new_pipeline("blit pipeline")
+            .with_fragment(include_bytes!("../../shader/blit.wgsl"))
+            .with_fragment_targets(&targets)
+            .with_bind_groups(&[&layout])
+            .try_create()?
+            .inner;
+
+        let blit = Rc::new(BlitPipeline { pipeline, layout });
+        self.blit_cache
+            .pipelines
+            .borrow_mut()
+            .insert((format, filterable), blit.clone());
+        Ok(blit)
+    }
+}
@@ -4,6 +4,7 @@ pub struct TextureBuilder<'a> {
     gpu: crate::Gpu,
     texture: wgpu::TextureDescriptor<'a>,
     view: wgpu::TextureViewDescriptor<'a>,
+    full_mips: bool,
 }
 
 impl TextureBuilder<'_> {
@@ -22,6 +23,7 @@ impl TextureBuilder<'_> {
                 usage: wgpu::TextureUsages::empty(),
             },
             view: Default::default(),
+            full_mips: false,
         }
     }
 
@@ -34,8 +36,7 @@ impl TextureBuilder<'_> {
         D: crate::TextureDimensions,
     {
         self.texture.usage |= wgpu::TextureUsages::COPY_DST;
-        self.texture.size = size.as_extent();
-        self.texture.dimension = size.dim();
+        self.set_size(&size);
 
         let texture = self.gpu.device.create_texture(&self.texture);
         let view = texture.create_view(&self.view);
@@ -60,6 +61,8 @@ impl TextureBuilder<'_> {
             format: crate::TexFormat(self.texture.format),
             size,
             usage: self.texture.usage,
+            mip_level_count: self.texture.mip_level_count,
+            sample_count: self.texture.sample_count,
         }
     }
 
@@ -69,8 +72,7 @@ impl TextureBuilder<'_> {
         D: crate::TextureDimensions,
     {
         self.texture.usage |= wgpu::TextureUsages::COPY_DST;
-        self.texture.size = size.as_extent();
-        self.texture.dimension = size.dim();
+        self.set_size(&size);
 
         let texture = self.gpu.device.create_texture(&self.texture);
         let view = texture.create_view(&self.view);
@@ -82,11 +84,45 @@ impl TextureBuilder<'_> {
             format: crate::TexFormat(self.texture.format),
             size,
             usage: self.texture.usage,
+            mip_level_count: self.texture.mip_level_count,
+            sample_count: self.texture.sample_count,
         }
     }
 
+    fn set_size<D>(&mut self, size: &D)
+    where
+        D: crate::TextureDimensions,
+    {
+        self.texture.size = size.as_extent();
+        self.texture.dimension = size.dim();
+        self.view.dimension = Some(size.view_dim());
+        if self.full_mips {
+            // Array layers are not mipped, only the depth of a 3D texture is
+            let height = match size.dim() {
+                wgpu::TextureDimension::D3 => size.height().max(size.depth()),
+                _ => size.height(),
+            };
+            self.texture.mip_level_count = crate::mip_level_count(size.width(), height);
+        }
+    }
+
+    /// Sets the number of mip levels, see `with_full_mips()` to create every level.
     pub const fn mips(mut self, mips: u32) -> Self {
         self.texture.mip_level_count = mips;
+        self.full_mips = false;
+        self
+    }
+
+    /// Creates the full mip chain down to 1x1 for the size the texture is created
+    /// with, see `mip_level_count()`. The last of `mips()` and `with_full_mips()`
+    /// called takes effect.
+    ///
+    /// `create()` only uploads the data to the first mip. The other levels can be
+    /// uploaded with `Texture::write_mip()`, or rendered from the level above, which
+    /// requires `allow_binding()` and `as_render_target()`. No usages are added here
+    /// since some formats, e.g. compressed ones, cannot be render targets.
+    pub const fn with_full_mips(mut self) -> Self {
+        self.full_mips = true;
         self
     }
 
@@ -95,6 +131,9 @@ impl TextureBuilder<'_> {
         self
     }
 
+    /// Sets the format of the texture and its views.
+    /// Views cannot reinterpret the format (e.g. an sRGB view of a UNORM texture),
+    /// since wgpu 0.12 has no `view_formats`. Use `Gpu::blit()` to convert instead.
     pub const fn with_format(mut self, format: wgpu::TextureFormat) -> Self {
         self.texture.format = format;
         self
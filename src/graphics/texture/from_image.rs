@@ -0,0 +1,28 @@
+use crate::{Gpu, GpuError, Texture, TextureBuilder, D2};
+
+impl TextureBuilder<'_> {
+    /// Creates a texture with the contents and size of the image.
+    /// The format is set to `Rgba8UnormSrgb`, which matches the layout of `RgbaImage`.
+    pub fn create_from_image(self, img: &image::RgbaImage) -> Texture<D2> {
+        self.with_format(wgpu::TextureFormat::Rgba8UnormSrgb)
+            .create(img.dimensions(), img.as_raw())
+    }
+}
+
+impl Texture<D2> {
+    /// Decodes the image file at the path and uploads it to a new texture that can
+    /// be bound, see `TextureBuilder::create_from_image()`.
+    /// # Errors
+    /// Errors if the file can not be read or decoded
+    pub fn load_from_path(
+        gpu: &Gpu,
+        path: impl AsRef<std::path::Path>,
+    ) -> Result<Texture<D2>, GpuError> {
+        let path = path.as_ref();
+        let img = image::open(path)?.into_rgba8();
+        Ok(gpu
+            .new_texture(&path.to_string_lossy())
+            .allow_binding()
+            .create_from_image(&img))
+    }
+}
@@ -0,0 +1,90 @@
+use crate::{Binding, Gpu, RenderAttachment, Texture, D2};
+
+/// A set of render targets for multiple render target (MRT) passes, such as
+/// the geometry pass of a deferred renderer.
+///
+/// The targets are kept in the order they were added. `attachments()` and
+/// `color_targets()` use the same order, so passing them to the render pass and
+/// `PipelineBuilder::with_fragment_targets()` keeps the shader's
+/// `[[location(n)]]` outputs in sync with the textures.
+pub struct GBuffer {
+    pub targets: Vec<Texture<D2>>,
+}
+impl GBuffer {
+    /// The color attachments for a render pass, in target order.
+    pub fn attachments(&self) -> Vec<RenderAttachment<'_>> {
+        self.targets.iter().map(Texture::attach_render).collect()
+    }
+
+    /// The color target states for a pipeline, in target order.
+    /// Blending is disabled, as it is usually meaningless for G-buffer data.
+    pub fn color_targets(&self) -> Vec<wgpu::ColorTargetState> {
+        self.targets
+            .iter()
+            .map(|target| wgpu::ColorTargetState {
+                format: *target.format,
+                blend: None,
+                write_mask: wgpu::ColorWrites::ALL,
+            })
+            .collect()
+    }
+
+    /// Texture bindings for all targets, in target order.
+    /// Useful for reading the G-buffer in a lighting pass.
+    pub fn bindings(&self) -> Vec<Binding<'_>> {
+        self.targets.iter().map(Texture::bind_texture).collect()
+    }
+
+    /// Resizes all targets. Old contents are discarded.
+    pub fn resize(&mut self, size: D2) {
+        for target in &mut self.targets {
+            target.resize(size);
+        }
+    }
+}
+impl std::ops::Index<usize> for GBuffer {
+    type Output = Texture<D2>;
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.targets[index]
+    }
+}
+
+pub struct GBufferBuilder<'a> {
+    gpu: Gpu,
+    size: D2,
+    targets: Vec<(&'a str, wgpu::TextureFormat)>,
+}
+impl<'a> GBufferBuilder<'a> {
+    /// Adds a target with the given format. Targets are bound to the fragment
+    /// outputs in the order they are added.
+    pub fn with_target(mut self, label: &'a str, format: wgpu::TextureFormat) -> Self {
+        self.targets.push((label, format));
+        self
+    }
+
+    pub fn create(&self) -> GBuffer {
+        let targets = self
+            .targets
+            .iter()
+            .map(|&(label, format)| {
+                self.gpu
+                    .new_texture(label)
+                    .as_render_target()
+                    .allow_binding()
+                    .with_format(format)
+                    .create_empty(self.size)
+            })
+            .collect();
+        GBuffer { targets }
+    }
+}
+
+impl Gpu {
+    pub fn new_gbuffer<'a>(&self, size: D2) -> GBufferBuilder<'a> {
+        GBufferBuilder {
+            gpu: self.clone(),
+            size,
+            targets: Vec::new(),
+        }
+    }
+}
@@ -0,0 +1,135 @@
+use std::{
+    cell::{Cell, RefCell},
+    collections::VecDeque,
+    ops::{Deref, DerefMut},
+};
+
+use crate::{Gpu, Texture, TextureDimensions, D2};
+
+type PoolKey = (D2, wgpu::TextureFormat, wgpu::TextureUsages);
+
+/// Textures released by `PooledTexture`, to be reused by `Gpu::acquire_texture()`.
+/// At most `capacity()` textures are kept. Releasing a texture into a full pool
+/// destroys the texture that was released the longest ago, so textures of sizes
+/// that are no longer used, e.g. while resizing a window, do not pile up.
+#[derive(Debug)]
+pub struct TexturePool {
+    // Only the wgpu textures are kept, since a `Texture` would keep the `Gpu` alive.
+    // Ordered from the oldest to the most recently released
+    free: RefCell<VecDeque<(PoolKey, wgpu::Texture)>>,
+    capacity: Cell<usize>,
+}
+impl TexturePool {
+    /// Enough for a couple of post processing mip chains
+    pub const DEFAULT_CAPACITY: usize = 16;
+
+    /// The number of released textures waiting to be reused
+    pub fn len(&self) -> usize {
+        self.free.borrow().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The maximum number of released textures that are kept
+    pub fn capacity(&self) -> usize {
+        self.capacity.get()
+    }
+
+    /// Sets the maximum number of released textures that are kept, destroying the
+    /// oldest textures if there are more. A capacity of 0 disables reuse.
+    pub fn set_capacity(&self, capacity: usize) {
+        self.capacity.set(capacity);
+        self.trim();
+    }
+
+    /// Destroys the released textures, e.g. after resizing has finished.
+    pub fn clear(&self) {
+        self.free.borrow_mut().clear();
+    }
+
+    fn release(&self, key: PoolKey, texture: wgpu::Texture) {
+        self.free.borrow_mut().push_back((key, texture));
+        self.trim();
+    }
+
+    fn take(&self, key: &PoolKey) -> Option<wgpu::Texture> {
+        let mut free = self.free.borrow_mut();
+        let index = free.iter().rposition(|(k, _)| k == key)?;
+        free.remove(index).map(|(_, texture)| texture)
+    }
+
+    fn trim(&self) {
+        let mut free = self.free.borrow_mut();
+        let excess = free.len().saturating_sub(self.capacity.get());
+        free.drain(..excess);
+    }
+}
+impl Default for TexturePool {
+    fn default() -> Self {
+        Self {
+            free: RefCell::default(),
+            capacity: Cell::new(Self::DEFAULT_CAPACITY),
+        }
+    }
+}
+
+/// A texture from `Gpu::acquire_texture()`, which is released to the texture pool
+/// on drop instead of being destroyed.
+pub struct PooledTexture {
+    texture: Option<Texture<D2>>,
+}
+
+impl Deref for PooledTexture {
+    type Target = Texture<D2>;
+    fn deref(&self) -> &Self::Target {
+        self.texture.as_ref().unwrap()
+    }
+}
+impl DerefMut for PooledTexture {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.texture.as_mut().unwrap()
+    }
+}
+
+impl Drop for PooledTexture {
+    fn drop(&mut self) {
+        if let Some(texture) = self.texture.take() {
+            let key = (texture.size, *texture.format, texture.usage);
+            texture.gpu.texture_pool.release(key, texture.inner);
+        }
+    }
+}
+
+impl Gpu {
+    /// Takes a released texture with the same size, format and usage from
+    /// `GpuCtx::texture_pool`, or creates one if there is none, e.g. for the
+    /// intermediate textures of post processing that are recreated on resize.
+    /// The texture is released to the pool when the `PooledTexture` is dropped.
+    ///
+    /// A reused texture keeps the contents it was released with, so clear it or
+    /// overwrite every texel before reading it.
+    pub fn acquire_texture(
+        &self,
+        size: D2,
+        format: wgpu::TextureFormat,
+        usage: wgpu::TextureUsages,
+    ) -> PooledTexture {
+        let inner = match self.texture_pool.take(&(size, format, usage)) {
+            Some(inner) => inner,
+            None => self.device.create_texture(&wgpu::TextureDescriptor {
+                label: Some("pooled texture"),
+                size: size.as_extent(),
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format,
+                usage,
+            }),
+        };
+        PooledTexture {
+            texture: Some(Texture::from_raw(self.clone(), inner, format, size, usage)),
+        }
+    }
+}
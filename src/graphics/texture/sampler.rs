@@ -37,6 +37,33 @@ impl SamplerBuilder<'_, '_> {
         self
     }
 
+    /// Clamps out of bounds accesses to the edge of the texture.
+    /// This avoids bleeding across edges when sampling render targets or UI.
+    pub const fn clamp(mut self) -> Self {
+        self.inner.address_mode_u = wgpu::AddressMode::ClampToEdge;
+        self.inner.address_mode_v = wgpu::AddressMode::ClampToEdge;
+        self.inner.address_mode_w = wgpu::AddressMode::ClampToEdge;
+        self
+    }
+
+    /// Sets the address mode of the u (x) axis only
+    pub const fn address_u(mut self, mode: wgpu::AddressMode) -> Self {
+        self.inner.address_mode_u = mode;
+        self
+    }
+
+    /// Sets the address mode of the v (y) axis only
+    pub const fn address_v(mut self, mode: wgpu::AddressMode) -> Self {
+        self.inner.address_mode_v = mode;
+        self
+    }
+
+    /// Sets the address mode of the w (z) axis only
+    pub const fn address_w(mut self, mode: wgpu::AddressMode) -> Self {
+        self.inner.address_mode_w = mode;
+        self
+    }
+
     const fn clamp_to_border(mut self) -> Self {
         self.inner.address_mode_u = wgpu::AddressMode::ClampToBorder;
         self.inner.address_mode_v = wgpu::AddressMode::ClampToBorder;
@@ -59,13 +86,53 @@ impl SamplerBuilder<'_, '_> {
         self.clamp_to_border()
     }
 
-    pub const fn linear_filter(mut self) -> Self {
+    /// Filters linearly when magnifying, i.e. when a texel covers more than a pixel
+    pub const fn mag_linear(mut self) -> Self {
         self.inner.mag_filter = wgpu::FilterMode::Linear;
+        self
+    }
+
+    /// Filters linearly when minifying, i.e. when a pixel covers more than a texel
+    pub const fn min_linear(mut self) -> Self {
         self.inner.min_filter = wgpu::FilterMode::Linear;
+        self
+    }
+
+    /// Blends between the two nearest mip levels, instead of using the nearest one
+    pub const fn mipmap_linear(mut self) -> Self {
         self.inner.mipmap_filter = wgpu::FilterMode::Linear;
         self
     }
 
+    /// Filters linearly when magnifying, minifying and between mips (trilinear).
+    /// Filters are nearest by default, and can be enabled separately:
+    /// - Pixel art: none, nearest everywhere
+    /// - Bilinear: `mag_linear().min_linear()`, which snaps to the nearest mip
+    /// - Trilinear, e.g. for smooth 3D: `linear_filter()`
+    /// - Crisp texels without mip popping: `mipmap_linear()` only
+    pub const fn linear_filter(self) -> Self {
+        self.mag_linear().min_linear().mipmap_linear()
+    }
+
+    /// Enables anisotropic filtering with the given maximum anisotropy.
+    /// This also enables linear filtering, which anisotropic filtering requires.
+    /// This only has a visible effect when sampling a mipmapped texture.
+    /// A value of 1 disables anisotropic filtering.
+    /// # Panics
+    /// Panics if `max` is not a power of two between 1 and 16
+    pub const fn anisotropy(mut self, max: u16) -> Self {
+        assert!(
+            max.is_power_of_two() && max <= 16,
+            "Anisotropy must be 1, 2, 4, 8, or 16"
+        );
+        self.inner.anisotropy_clamp = if max > 1 {
+            std::num::NonZeroU8::new(max as u8)
+        } else {
+            None
+        };
+        self.linear_filter()
+    }
+
     pub const fn lod_range(mut self, range: std::ops::Range<f32>) -> Self {
         self.inner.lod_min_clamp = range.start;
         self.inner.lod_max_clamp = range.end;
@@ -7,6 +7,12 @@ pub use frame::*;
 mod render_pass;
 pub use render_pass::*;
 
+mod offscreen;
+pub use offscreen::*;
+
+mod render_bundle;
+pub use render_bundle::*;
+
 use std::{cell::RefCell, ops::Deref};
 
 use crate::{bitor, Gpu, GpuError, Texture};
@@ -29,7 +35,17 @@ pub struct Viewport {
     pub sc_desc: RefCell<wgpu::SurfaceConfiguration>,
     /// Uses RefCell for interior mutability.
     // pub swap_chain: RefCell<wgpu::SwapChain>,
-    pub depth_texture: RefCell<crate::Texture<crate::D2>>,
+    /// This is `None` if depth is disabled.
+    pub depth_texture: RefCell<Option<crate::Texture<crate::D2>>>,
+    /// The format of the depth texture, or `None` if depth is disabled.
+    /// Pipelines that use the frame's depth attachment must have a matching depth format.
+    pub depth_format: Option<wgpu::TextureFormat>,
+    /// The number of samples per pixel. MSAA is enabled if this is greater than 1.
+    pub sample_count: u32,
+    /// The multisampled color texture that is resolved into the surface texture.
+    /// This is `None` if MSAA is not enabled.
+    /// Uses RefCell for interior mutability.
+    pub msaa_texture: RefCell<Option<crate::Texture<crate::D2>>>,
     /// Data buffer for viewport properties.
     /// Binding 0: viewport size f32x2
     pub data_buffer: wgpu::Buffer,
@@ -40,12 +56,16 @@ pub struct Viewport {
 }
 impl<'a> Viewport {
     #[must_use]
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         gpu: Gpu,
         surface: wgpu::Surface,
         width: u32,
         height: u32,
         format: wgpu::TextureFormat,
+        depth_format: Option<wgpu::TextureFormat>,
+        sample_count: u32,
+        present_mode: wgpu::PresentMode,
         window: winit::window::Window,
     ) -> Self {
         let sc_desc = wgpu::SurfaceConfiguration {
@@ -55,11 +75,14 @@ impl<'a> Viewport {
             format,
             width,
             height,
-            present_mode: wgpu::PresentMode::Mailbox,
+            present_mode,
         };
         surface.configure(&gpu.device, &sc_desc);
 
-        let depth_texture = Self::create_depth_texture(&gpu, width, height);
+        let depth_texture = depth_format.map(|depth_format| {
+            Self::create_depth_texture(&gpu, depth_format, width, height, sample_count)
+        });
+        let msaa_texture = Self::create_msaa_texture(&gpu, format, width, height, sample_count);
 
         let data_buffer = gpu
             .new_buffer("Viewport buffer")
@@ -72,11 +95,15 @@ impl<'a> Viewport {
         let sc_desc = RefCell::new(sc_desc);
         // let swap_chain = RefCell::new(swap_chain);
         let depth_texture = RefCell::new(depth_texture);
+        let msaa_texture = RefCell::new(msaa_texture);
 
         Self {
             gpu,
             surface,
             depth_texture,
+            depth_format,
+            sample_count,
+            msaa_texture,
             sc_desc,
             data_buffer,
             resize_to: RefCell::new(None),
@@ -97,13 +124,38 @@ impl<'a> Viewport {
             .configure(&self.gpu.device, &self.sc_desc.borrow());
     }
 
-    fn create_depth_texture(gpu: &Gpu, width: u32, height: u32) -> Texture<crate::D2> {
+    fn create_depth_texture(
+        gpu: &Gpu,
+        format: wgpu::TextureFormat,
+        width: u32,
+        height: u32,
+        sample_count: u32,
+    ) -> Texture<crate::D2> {
         gpu.new_texture("Viewport depth texture")
             .as_render_target()
-            .with_format(crate::TextureFormat::Depth32Float)
+            .allow_copy_from()
+            .with_format(format)
+            .multisample(sample_count)
             .create_empty((width, height))
     }
 
+    /// Creates the multisampled color texture, if MSAA is enabled.
+    fn create_msaa_texture(
+        gpu: &Gpu,
+        format: wgpu::TextureFormat,
+        width: u32,
+        height: u32,
+        sample_count: u32,
+    ) -> Option<Texture<crate::D2>> {
+        (sample_count > 1).then(|| {
+            gpu.new_texture("Viewport MSAA texture")
+                .as_render_target()
+                .with_format(format)
+                .multisample(sample_count)
+                .create_empty((width, height))
+        })
+    }
+
     /// Queues a resize
     // fn resize_using_window(&self) -> bool {
     //     let (width, height) = self.window.inner_size().into();
@@ -153,9 +205,21 @@ impl<'a> Viewport {
         self.configure_surface();
 
         // depth
-        let depth_texture = Self::create_depth_texture(&self.gpu, width, height);
+        if let Some(depth_format) = self.depth_format {
+            let depth_texture = Self::create_depth_texture(
+                &self.gpu,
+                depth_format,
+                width,
+                height,
+                self.sample_count,
+            );
+            self.depth_texture.replace(Some(depth_texture));
+        }
 
-        self.depth_texture.replace(depth_texture);
+        // msaa
+        let msaa_texture =
+            Self::create_msaa_texture(&self.gpu, sc_desc.format, width, height, self.sample_count);
+        self.msaa_texture.replace(msaa_texture);
 
         // Update the data buffer
         self.gpu.queue.write_buffer(
@@ -184,32 +248,84 @@ impl<'a> Viewport {
         changed
     }
 
+    /// The number of times `begin_frame()` reconfigures the surface and retries
+    /// when the surface is lost or outdated, before returning the error.
+    const FRAME_RETRIES: u32 = 2;
+
+    /// Gets the next frame of the surface, applying a queued resize first.
+    /// A lost or outdated surface is reconfigured and retried.
+    ///
+    /// If the surface is suboptimal, e.g. stretched after a resize, the frame is still
+    /// returned, and a resize is queued if the window size differs from the configured
+    /// size. The surface is reconfigured at the start of the next `begin_frame()`, so
+    /// there is one frame of latency before frames are optimal again.
+    /// See `Frame::is_suboptimal()`.
+    /// # Errors
+    /// Errors with `SurfaceError` if no frame could be acquired
     pub fn begin_frame(&self) -> Result<Frame, GpuError> {
+        self.begin_frame_impl(Self::FRAME_RETRIES)
+    }
+
+    fn begin_frame_impl(&self, retries: u32) -> Result<Frame<'_>, GpuError> {
         self.resolve_resize();
 
         // TODO: Ideally we should not be creating a new depth texture view every frame.
         match Frame::new(
             &self.gpu,
             &self.surface,
+            (self.width(), self.height()),
             self.depth_texture
                 .borrow()
-                .create_view(&wgpu::TextureViewDescriptor::default()),
+                .as_ref()
+                .map(|depth| depth.create_view(&wgpu::TextureViewDescriptor::default())),
+            self.msaa_texture
+                .borrow()
+                .as_ref()
+                .map(|msaa| msaa.create_view(&wgpu::TextureViewDescriptor::default())),
         ) {
-            Ok(frame) => Ok(frame),
-            Err(GpuError::SurfaceError(wgpu::SurfaceError::Outdated)) => {
+            Ok(frame) => {
+                // Only a size change is fixed by reconfiguring. Some platforms report
+                // suboptimal frames permanently, which would otherwise reconfigure the
+                // surface every frame
+                if frame.is_suboptimal() && self.resize_to.borrow().is_none() {
+                    let (width, height) = self.window.inner_size().into();
+                    let configured = (self.width(), self.height());
+                    if width != 0 && height != 0 && (width, height) != configured {
+                        self.resize(width, height);
+                    }
+                }
+                Ok(frame)
+            }
+            Err(GpuError::SurfaceError(
+                e @ (wgpu::SurfaceError::Outdated | wgpu::SurfaceError::Lost),
+            )) if retries > 0 => {
                 // Attempt to resize the window if the surface is outdated.
                 // If the window is the same size, then a simple resize will
-                // not solve this error.
-                if self.resize_using_window() {
-                    self.begin_frame()
-                } else {
-                    Err(GpuError::SurfaceError(wgpu::SurfaceError::Outdated))
+                // not solve this error, so the surface is reconfigured as is.
+                // A lost surface always needs to be reconfigured.
+                if e == wgpu::SurfaceError::Lost || !self.resize_using_window() {
+                    self.configure_surface();
                 }
+                self.begin_frame_impl(retries - 1)
             }
             Err(e) => Err(e),
         }
     }
 
+    /// Reads the values of the depth buffer, for debugging.
+    /// See `Texture::read_depth()`, the depth texture is created with `allow_copy_from()`.
+    /// # Errors
+    /// Errors with `ValidationError` if depth is disabled or the viewport is multisampled,
+    /// or with `UnsupportedFormat` if the depth format cannot be copied
+    pub fn read_depth(&self) -> Result<Vec<f32>, GpuError> {
+        match self.depth_texture.borrow().as_ref() {
+            Some(depth) => depth.read_depth(),
+            None => Err(GpuError::ValidationError(
+                "viewport has no depth buffer".to_owned(),
+            )),
+        }
+    }
+
     #[allow(clippy::cast_precision_loss)]
     pub fn aspect_ratio(&self) -> f32 {
         let sc_desc = self.sc_desc.borrow();
@@ -233,6 +349,12 @@ impl<'a> Viewport {
     }
 }
 
+impl BeginRenderFrame for Viewport {
+    fn begin_frame(&self) -> Result<Frame<'_>, GpuError> {
+        Viewport::begin_frame(self)
+    }
+}
+
 impl Deref for Viewport {
     type Target = winit::window::Window;
 
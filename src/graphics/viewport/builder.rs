@@ -1,19 +1,30 @@
-use crate::{Gpu, Viewport};
+use crate::{Gpu, TexFormat, Viewport};
+use tracing::warn;
 use winit::window::Window;
 
 pub struct ViewportBuilder {
     pub gpu: Gpu,
     pub window: Window,
     pub format: Option<wgpu::TextureFormat>,
+    pub view_format: Option<wgpu::TextureFormat>,
+    pub depth_format: Option<wgpu::TextureFormat>,
     pub usages: wgpu::TextureUsages,
+    pub sample_count: u32,
+    pub present_mode: wgpu::PresentMode,
 }
 impl<'a> ViewportBuilder {
+    pub const DEFAULT_DEPTH_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Depth32Float;
+
     pub fn new(gpu: Gpu, window: Window) -> Self {
         Self {
             gpu,
             window,
             format: None,
+            view_format: None,
+            depth_format: Some(Self::DEFAULT_DEPTH_FORMAT),
             usages: wgpu::TextureUsages::empty(),
+            sample_count: 1,
+            present_mode: wgpu::PresentMode::Mailbox,
         }
     }
 
@@ -22,11 +33,55 @@ impl<'a> ViewportBuilder {
         self
     }
 
+    /// Sets the format that frames are rendered in, which must be the sRGB or linear
+    /// variant of the surface format. For example, use `Bgra8Unorm` on a
+    /// `Bgra8UnormSrgb` surface to write already gamma-corrected values when doing
+    /// your own tonemapping.
+    ///
+    /// wgpu cannot reinterpret the format of surface textures, so the surface itself
+    /// is configured with this format. An incompatible format is ignored with a warning.
+    pub fn with_view_format(mut self, format: wgpu::TextureFormat) -> Self {
+        self.view_format = Some(format);
+        self
+    }
+
+    /// Sets the format of the viewport's depth texture. Defaults to `Depth32Float`.
+    /// Use a format with a stencil aspect such as `Depth24PlusStencil8` to use
+    /// `Frame::attach_stencil()`, matching `PipelineBuilder::with_depth_stencil()`.
+    pub fn with_depth_format(mut self, format: wgpu::TextureFormat) -> Self {
+        self.depth_format = Some(format);
+        self
+    }
+
+    /// Disables the viewport's depth texture, which saves memory and allocations on
+    /// resize for apps that do not use depth, e.g. 2D apps.
+    /// `Frame::attach_depth()` and similar will panic if used.
+    pub fn no_depth(mut self) -> Self {
+        self.depth_format = None;
+        self
+    }
+
     pub fn with_usages(mut self, usages: wgpu::TextureUsages) -> Self {
         self.usages = usages;
         self
     }
 
+    /// Enables MSAA with the given number of samples per pixel.
+    /// Frames are rendered to a multisampled texture which is resolved into the
+    /// surface texture. Pipelines used with this viewport must be created with
+    /// a matching `PipelineBuilder::with_multisample()`.
+    pub fn with_msaa(mut self, samples: u32) -> Self {
+        self.sample_count = samples;
+        self
+    }
+
+    /// Sets how frames are presented to the window. Defaults to `Mailbox`.
+    /// wgpu falls back to `Fifo` if the surface does not support the mode.
+    pub fn with_present_mode(mut self, present_mode: wgpu::PresentMode) -> Self {
+        self.present_mode = present_mode;
+        self
+    }
+
     /// Build the Viewport.
     /// Note this builder is consumed to pass the GpuHandle to the built Viewport.
     pub fn create(self) -> Viewport {
@@ -37,6 +92,19 @@ impl<'a> ViewportBuilder {
         } else {
             surface.get_preferred_format(&self.gpu.adapter).unwrap()
         };
+        let format = match self.view_format {
+            Some(view_format) if TexFormat(view_format).linear() == TexFormat(format).linear() => {
+                view_format
+            }
+            Some(view_format) => {
+                warn!(
+                    "View format {:?} is not compatible with surface format {:?}, ignoring it",
+                    view_format, format
+                );
+                format
+            }
+            None => format,
+        };
 
         Viewport::new(
             self.gpu,
@@ -44,6 +112,9 @@ impl<'a> ViewportBuilder {
             size.width,
             size.height,
             format,
+            self.depth_format,
+            self.sample_count,
+            self.present_mode,
             self.window,
         )
     }
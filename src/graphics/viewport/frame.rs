@@ -1,4 +1,4 @@
-use crate::{CommandEncoder, Gpu, GpuError, RenderPassBuilder};
+use crate::{CommandEncoder, Gpu, GpuError, RenderAttachment, RenderPassBuilder};
 use std::mem::ManuallyDrop;
 
 /// Convenience wrapper for a frame buffer you render to.
@@ -9,15 +9,19 @@ use std::mem::ManuallyDrop;
 pub struct Frame<'a> {
     /// The gpu handle is ref'd because of the short lifetime of Frame
     pub(crate) gpu: &'a Gpu,
-    /// The surface texture provided by the surface
-    /// ManuallyDrop because we call `.present()` on it to present to screen
-    surface_texture: ManuallyDrop<wgpu::SurfaceTexture>,
-    /// The Optional depth texture
-    pub depth_texture: crate::TextureView<'a>,
+    /// The surface texture provided by the surface, which is presented on drop.
+    /// This is `None` for offscreen frames.
+    surface_texture: Option<wgpu::SurfaceTexture>,
+    /// The depth texture, or `None` if the viewport has no depth
+    pub depth_texture: Option<crate::TextureView<'a>>,
     pub view: crate::TextureView<'a>,
+    /// The multisampled view that is resolved into `view`, if MSAA is enabled
+    pub msaa_view: Option<crate::TextureView<'a>>,
     pub encoder: ManuallyDrop<CommandEncoder>,
     pub delta_time: Option<f32>,
     pub resized_to: Option<(u32, u32)>,
+    /// The size of the frame's view
+    size: (u32, u32),
 }
 
 impl Frame<'_> {
@@ -30,14 +34,11 @@ impl Frame<'_> {
                 // TODO: This will map to the surface depth
                 depth_stencil_attachment: None,
             },
-            init_color_attachments: Some(vec![wgpu::RenderPassColorAttachment {
-                view: &self.view,
-                resolve_target: None,
-                ops: wgpu::Operations {
-                    load: wgpu::LoadOp::Load,
-                    store: true,
-                },
-            }]),
+            init_color_attachments: Some(vec![color_attachment(
+                &self.view,
+                self.msaa_view.as_ref(),
+                wgpu::LoadOp::Load,
+            )]),
             init_pipeline: None,
         }
     }
@@ -47,80 +48,174 @@ impl Frame<'_> {
         label: &'f str,
         clear_color: u32,
     ) -> RenderPassBuilder {
+        self.cleared_pass_impl(label, clear_color, None)
+    }
+
+    /// Creates a render pass with the color cleared to `clear_color` and the
+    /// frame's depth texture cleared to 1.0.
+    /// Pipelines used in this pass must have a depth state (see `with_depth()`).
+    pub fn clear_pass<'f>(
+        &'f mut self,
+        label: &'f str,
+        clear_color: u32,
+    ) -> RenderPassBuilder<'f, 'f> {
+        self.cleared_pass_impl(label, clear_color, Some(1.0))
+    }
+
+    fn cleared_pass_impl<'f>(
+        &'f mut self,
+        label: &'f str,
+        clear_color: u32,
+        clear_depth: Option<f32>,
+    ) -> RenderPassBuilder<'f, 'f> {
         let [r, g, b, a] = clear_color.to_be_bytes();
         RenderPassBuilder {
             encoder: &mut self.encoder,
             desc: wgpu::RenderPassDescriptor {
                 label: Some(label),
                 color_attachments: &[],
-                depth_stencil_attachment: None,
+                depth_stencil_attachment: clear_depth.zip(self.depth_texture.as_ref()).map(
+                    |(depth, view)| wgpu::RenderPassDepthStencilAttachment {
+                        view,
+                        depth_ops: Some(wgpu::Operations {
+                            load: wgpu::LoadOp::Clear(depth),
+                            store: true,
+                        }),
+                        stencil_ops: None,
+                    },
+                ),
             },
-            init_color_attachments: Some(vec![wgpu::RenderPassColorAttachment {
-                view: &self.view,
-                resolve_target: None,
-                ops: wgpu::Operations {
-                    load: wgpu::LoadOp::Clear(wgpu::Color {
-                        r: r as f64 / 255.0,
-                        g: g as f64 / 255.0,
-                        b: b as f64 / 255.0,
-                        a: a as f64 / 255.0,
-                    }),
-                    store: true,
-                },
-            }]),
+            init_color_attachments: Some(vec![color_attachment(
+                &self.view,
+                self.msaa_view.as_ref(),
+                wgpu::LoadOp::Clear(wgpu::Color {
+                    r: r as f64 / 255.0,
+                    g: g as f64 / 255.0,
+                    b: b as f64 / 255.0,
+                    a: a as f64 / 255.0,
+                }),
+            )]),
             init_pipeline: None,
         }
     }
 
+    /// Whether the surface texture no longer matches the surface exactly, e.g. after a
+    /// resize. It can still be presented, but may be stretched or blurry.
+    /// `Viewport::begin_frame()` queues a reconfigure for the next frame in this case.
+    /// Always `false` for offscreen frames.
+    pub fn is_suboptimal(&self) -> bool {
+        match &self.surface_texture {
+            Some(texture) => texture.suboptimal,
+            None => false,
+        }
+    }
+
     pub fn create_encoder(&self, label: &str) -> CommandEncoder {
         self.gpu.create_command_encoder(label)
     }
+
+    /// The size of the frame in pixels, e.g. to recompute a projection in a draw
+    /// callback without capturing the viewport.
+    /// This is the size after the resize if the frame was `resized_to` a new size.
+    pub fn size(&self) -> (u32, u32) {
+        self.resized_to.unwrap_or(self.size)
+    }
+
+    /// The aspect ratio (width / height) of the frame, see `size()`.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn aspect_ratio(&self) -> f32 {
+        let (width, height) = self.size();
+        width as f32 / height as f32
+    }
 }
 
 impl<'a> Frame<'a> {
     /// Creates a new Frame from the Surface
-    /// We
+    /// `size` is the size the surface is configured with.
     pub fn new(
         gpu: &'a Gpu,
         surface: &wgpu::Surface,
-        depth: wgpu::TextureView,
+        size: (u32, u32),
+        depth: Option<wgpu::TextureView>,
+        msaa: Option<wgpu::TextureView>,
     ) -> Result<Self, GpuError> {
-        let frame = surface
+        let surface_texture = surface
             .get_current_texture()
             .map_err(GpuError::SurfaceError)?;
-        let frame_view = frame.texture.create_view(&wgpu::TextureViewDescriptor {
-            // TODO: Custom label
-            label: Some("Viewport frame view"),
-            ..Default::default()
-        });
+        let frame_view = surface_texture
+            .texture
+            .create_view(&wgpu::TextureViewDescriptor {
+                // TODO: Custom label
+                label: Some("Viewport frame view"),
+                ..Default::default()
+            });
+        let mut frame = Self::from_view(gpu, frame_view, size, depth, msaa);
+        frame.surface_texture = Some(surface_texture);
+        Ok(frame)
+    }
+
+    /// Creates a frame that renders to the given view instead of a surface texture.
+    /// The encoder is still submitted on drop, but nothing is presented.
+    pub(crate) fn from_view(
+        gpu: &'a Gpu,
+        view: wgpu::TextureView,
+        size: (u32, u32),
+        depth: Option<wgpu::TextureView>,
+        msaa: Option<wgpu::TextureView>,
+    ) -> Self {
         let mut encoder = gpu.create_command_encoder("Viewport render encoder");
 
         gpu.begin_profiler_section("Frame start", &mut encoder);
 
-        Ok(Frame {
+        Frame {
             gpu,
-            surface_texture: ManuallyDrop::new(frame),
-            depth_texture: gpu.wrap_view(depth),
-            view: gpu.wrap_view(frame_view),
+            surface_texture: None,
+            depth_texture: depth.map(|depth| gpu.wrap_view(depth)),
+            view: gpu.wrap_view(view),
+            msaa_view: msaa.map(|msaa| gpu.wrap_view(msaa)),
             encoder: ManuallyDrop::new(encoder),
             delta_time: None,
             resized_to: None,
-        })
+            size,
+        }
+    }
+}
+
+/// Creates a color attachment for the frame.
+/// If MSAA is enabled, the multisampled view is rendered to and resolved into
+/// the surface view.
+pub(crate) const fn color_attachment<'v>(
+    view: &'v crate::TextureView<'_>,
+    msaa_view: Option<&'v crate::TextureView<'_>>,
+    load: wgpu::LoadOp<wgpu::Color>,
+) -> RenderAttachment<'v> {
+    let ops = wgpu::Operations { load, store: true };
+    match msaa_view {
+        Some(msaa_view) => wgpu::RenderPassColorAttachment {
+            view: msaa_view.deref_const(),
+            resolve_target: Some(view.deref_const()),
+            ops,
+        },
+        None => wgpu::RenderPassColorAttachment {
+            view: view.deref_const(),
+            resolve_target: None,
+            ops,
+        },
     }
 }
 
 /// On drop, we submit the encoder to the queue and present the frame
 impl Drop for Frame<'_> {
     fn drop(&mut self) {
-        // Take ownership of the encoder field so that it can be consumed by submit()
-        // and the frame field so it can be consumed by present()
+        // Resolve the profiler queries of this frame so they can be read next frame
+        self.gpu.profiler.resolve(&mut self.encoder);
+        // Drop the encoder so that it is submitted to the queue
         // This is safe because we are dropping the struct right after this
-        let frame = unsafe { ManuallyDrop::take(&mut self.surface_texture) };
-
-        // First submit the encoder to the queue
         unsafe { ManuallyDrop::drop(&mut self.encoder) };
-        // Then present the frame to the screen
-        frame.present();
+        // Then present the frame to the screen, if it has a surface
+        if let Some(surface_texture) = self.surface_texture.take() {
+            surface_texture.present();
+        }
         self.gpu.profiler.clear();
     }
 }
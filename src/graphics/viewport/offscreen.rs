@@ -0,0 +1,81 @@
+use crate::{BeginRenderFrame, Frame, Gpu, GpuError, Texture, D2};
+
+/// A render target backed by a texture instead of a window surface.
+/// Frames from `begin_frame()` can be used like a `Viewport`'s frames, and their
+/// encoder is submitted on drop, but nothing is presented.
+/// This allows the same rendering code to run windowed and headless, e.g. in tests.
+pub struct OffscreenTarget {
+    pub gpu: Gpu,
+    /// The color texture that frames render to
+    pub texture: Texture<D2>,
+    /// The depth texture of the frames
+    pub depth_texture: Texture<D2>,
+}
+
+impl OffscreenTarget {
+    pub fn new(gpu: Gpu, width: u32, height: u32, format: wgpu::TextureFormat) -> Self {
+        let texture = gpu
+            .new_texture("Offscreen target texture")
+            .with_format(format)
+            .as_render_target()
+            .allow_binding()
+            .allow_copy_from()
+            .create_empty((width, height));
+        let depth_texture = gpu
+            .new_texture("Offscreen target depth texture")
+            .as_render_target()
+            .with_format(crate::ViewportBuilder::DEFAULT_DEPTH_FORMAT)
+            .create_empty((width, height));
+
+        Self {
+            gpu,
+            texture,
+            depth_texture,
+        }
+    }
+
+    /// Resizes the target. The contents are discarded.
+    pub fn resize(&mut self, width: u32, height: u32) {
+        self.texture.resize((width, height));
+        self.depth_texture.resize((width, height));
+    }
+
+    pub fn width(&self) -> u32 {
+        self.texture.size.0
+    }
+
+    pub fn height(&self) -> u32 {
+        self.texture.size.1
+    }
+}
+
+impl BeginRenderFrame for OffscreenTarget {
+    fn begin_frame(&self) -> Result<Frame<'_>, GpuError> {
+        let view = self.texture.create_view(&wgpu::TextureViewDescriptor {
+            label: Some("Offscreen frame view"),
+            ..Default::default()
+        });
+        let depth = self
+            .depth_texture
+            .create_view(&wgpu::TextureViewDescriptor::default());
+        Ok(Frame::from_view(
+            &self.gpu,
+            view,
+            self.texture.size,
+            Some(depth),
+            None,
+        ))
+    }
+}
+
+impl Gpu {
+    /// Creates a texture-backed render target for rendering without a window.
+    pub fn new_offscreen(
+        &self,
+        width: u32,
+        height: u32,
+        format: wgpu::TextureFormat,
+    ) -> OffscreenTarget {
+        OffscreenTarget::new(self.clone(), width, height, format)
+    }
+}
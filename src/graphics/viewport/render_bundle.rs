@@ -0,0 +1,175 @@
+use std::ops::{Deref, DerefMut};
+
+use crate::{Buffer, Gpu};
+
+impl Gpu {
+    /// Creates a builder for a render bundle, a recorded sequence of draws that can
+    /// be executed in render passes without being encoded again, e.g. for static
+    /// geometry or UI that is identical every frame.
+    /// The bundle can only be executed in passes whose color targets have the given
+    /// formats, and whose depth and sample count match the builder.
+    pub fn new_render_bundle<'a, 'b>(
+        &'a self,
+        label: &'a str,
+        formats: &'b [wgpu::TextureFormat],
+    ) -> RenderBundleBuilder<'a, 'b> {
+        RenderBundleBuilder {
+            gpu: self,
+            label,
+            formats,
+            depth_stencil: None,
+            sample_count: 1,
+        }
+    }
+}
+
+pub struct RenderBundleBuilder<'a, 'b> {
+    gpu: &'a Gpu,
+    label: &'a str,
+    formats: &'b [wgpu::TextureFormat],
+    depth_stencil: Option<wgpu::RenderBundleDepthStencil>,
+    sample_count: u32,
+}
+
+impl<'a> RenderBundleBuilder<'a, '_> {
+    /// Sets the format of the depth attachment of the passes the bundle is executed in.
+    /// Pipelines with a depth state write depth, others leave it read only.
+    pub fn with_depth(mut self, format: wgpu::TextureFormat) -> Self {
+        self.depth_stencil = Some(wgpu::RenderBundleDepthStencil {
+            format,
+            depth_read_only: false,
+            stencil_read_only: false,
+        });
+        self
+    }
+
+    /// Sets the sample count of the passes the bundle is executed in.
+    pub fn multisample(mut self, sample_count: u32) -> Self {
+        self.sample_count = sample_count;
+        self
+    }
+
+    pub fn begin(self) -> RenderBundleEncoder<'a> {
+        let inner =
+            self.gpu
+                .device
+                .create_render_bundle_encoder(&wgpu::RenderBundleEncoderDescriptor {
+                    label: Some(self.label),
+                    color_formats: self.formats,
+                    depth_stencil: self.depth_stencil,
+                    sample_count: self.sample_count,
+                    multiview: None,
+                });
+        RenderBundleEncoder {
+            inner,
+            label: self.label,
+        }
+    }
+}
+
+/// Records draws into a `wgpu::RenderBundle`, with the same chaining as `RenderPass`.
+/// Execute the bundle with `RenderPass::execute_bundle()`.
+pub struct RenderBundleEncoder<'a> {
+    inner: wgpu::RenderBundleEncoder<'a>,
+    label: &'a str,
+}
+
+impl RenderBundleEncoder<'_> {
+    /// Finishes recording the bundle.
+    #[must_use]
+    pub fn finish(self) -> wgpu::RenderBundle {
+        self.inner.finish(&wgpu::RenderBundleDescriptor {
+            label: Some(self.label),
+        })
+    }
+
+    pub fn draw_triangles(&mut self, count: u32) {
+        self.inner.draw(0..3, 0..count);
+    }
+
+    #[inline]
+    pub fn draw_triangle(&mut self) {
+        self.inner.draw(0..3, 0..1);
+    }
+
+    /// Draws a triangle covering the whole target with `SCREEN_VERTEX_SHADER`.
+    #[inline]
+    pub fn draw_fullscreen(&mut self) {
+        self.draw_triangle();
+    }
+
+    pub fn draw_one(&mut self, vertices: u32) {
+        self.inner.draw(0..vertices, 0..1);
+    }
+
+    pub fn draw_one_indexed(&mut self, vertices: u32) {
+        self.inner.draw_indexed(0..vertices, 0, 0..1);
+    }
+}
+
+impl<'a> RenderBundleEncoder<'a> {
+    /// Shadows wgpu::RenderBundleEncoder::set_bind_group and returns self for chaining
+    pub fn set_bind_group(
+        &mut self,
+        index: u32,
+        bind_group: &'a wgpu::BindGroup,
+        offsets: &[wgpu::DynamicOffset],
+    ) -> &mut Self {
+        self.inner.set_bind_group(index, bind_group, offsets);
+        self
+    }
+
+    /// Shadows wgpu::RenderBundleEncoder::set_pipeline and returns self for chaining
+    pub fn set_pipeline(&mut self, pipeline: &'a wgpu::RenderPipeline) -> &mut Self {
+        self.inner.set_pipeline(pipeline);
+        self
+    }
+
+    /// Sets the whole buffer as the index buffer, using the buffer's index format.
+    /// # Panics
+    /// Panics if the buffer does not have an index format. Set one with
+    /// `BufferBuilder::as_index_buffer_u16()` or `as_index_buffer_u32()`.
+    pub fn set_index_buffer_auto(&mut self, buffer: &'a Buffer) -> &mut Self {
+        let format = buffer
+            .index_format
+            .expect("Index buffer does not have an index format");
+        self.inner.set_index_buffer(buffer.slice(..), format);
+        self
+    }
+
+    /// Shadows wgpu::RenderBundleEncoder::set_vertex_buffer and returns self for chaining
+    pub fn set_vertex_buffer(
+        &mut self,
+        slot: u32,
+        buffer_slice: wgpu::BufferSlice<'a>,
+    ) -> &mut Self {
+        self.inner.set_vertex_buffer(slot, buffer_slice);
+        self
+    }
+
+    /// Draws primitives using the arguments in the buffer at the given offset.
+    /// See `RenderPass::draw_indirect()`.
+    pub fn draw_indirect(&mut self, buffer: &'a Buffer, offset: u64) -> &mut Self {
+        self.inner.draw_indirect(&buffer.inner, offset);
+        self
+    }
+
+    /// Draws indexed primitives using the arguments in the buffer at the given offset.
+    /// See `RenderPass::draw_indexed_indirect()`.
+    pub fn draw_indexed_indirect(&mut self, buffer: &'a Buffer, offset: u64) -> &mut Self {
+        self.inner.draw_indexed_indirect(&buffer.inner, offset);
+        self
+    }
+}
+
+impl<'a> Deref for RenderBundleEncoder<'a> {
+    type Target = wgpu::RenderBundleEncoder<'a>;
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+impl<'a> DerefMut for RenderBundleEncoder<'a> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.inner
+    }
+}
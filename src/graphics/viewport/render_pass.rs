@@ -1,6 +1,8 @@
 use std::ops::{Deref, DerefMut};
 
-use crate::{CommandEncoder, Frame, Gpu, RenderPipeline, Texture};
+use crate::{
+    Buffer, CommandEncoder, DebugGroupGuard, Frame, Gpu, RenderPipeline, Texture, VertexBuffer,
+};
 
 pub struct RenderPassBuilder<'a, 'b> {
     /// Encoder is used to create the render pass on build()
@@ -60,6 +62,16 @@ impl<'a> RenderAttachmentBuild for wgpu::RenderPassColorAttachment<'a> {
     }
 }
 
+/// Builder methods for depth attachments, such as `Frame::attach_depth()`.
+/// Attachments load the existing depth by default. Use `clear_depth()` for the usual
+/// depth test, or `clear_depth_val(0.0)` for reversed-Z with
+/// `PipelineBuilder::reverse_z()`:
+/// ```ignore
+/// encoder
+///     .render_pass("Reversed-Z pass", &[frame.attach_render().clear()])
+///     .with_depth(frame.attach_depth().clear_depth_val(0.0))
+///     .begin();
+/// ```
 pub trait DepthAttachmentBuild {
     fn clear_depth_val(self, val: f32) -> Self;
     fn clear_stencil_val(self, val: u32) -> Self;
@@ -148,11 +160,7 @@ impl<'a, 'b> RenderPassBuilder<'a, 'b> {
             self.encoder.begin_render_pass(desc)
         };
 
-        let pipeline_statistics = gpu.profiler.stats.is_some();
-
-        if pipeline_statistics {
-            gpu.begin_pipeline_statistics_query(&mut inner);
-        }
+        let pipeline_statistics = gpu.begin_pipeline_statistics_query(&mut inner);
 
         if let Some(pipeline) = self.init_pipeline {
             inner.set_pipeline(pipeline);
@@ -165,12 +173,24 @@ impl<'a, 'b> RenderPassBuilder<'a, 'b> {
     }
 }
 
+/// Size in bytes of the arguments for `draw_indirect()`
+pub const DRAW_INDIRECT_SIZE: u64 = 4 * 4;
+/// Size in bytes of the arguments for `draw_indexed_indirect()`
+pub const DRAW_INDEXED_INDIRECT_SIZE: u64 = 5 * 4;
+
 pub struct RenderPass<'a> {
     inner: wgpu::RenderPass<'a>,
     pipeline_statistics: bool,
 }
 
 impl RenderPass<'_> {
+    /// Groups the commands recorded through the returned guard under `label` in
+    /// graphics debuggers, until the guard is dropped.
+    pub fn debug_group(&mut self, label: &str) -> DebugGroupGuard<'_, Self> {
+        self.inner.push_debug_group(label);
+        DebugGroupGuard::new(self, |pass| pass.inner.pop_debug_group())
+    }
+
     pub fn draw_triangles(&mut self, count: u32) {
         self.inner.draw(0..3, 0..count);
     }
@@ -180,6 +200,13 @@ impl RenderPass<'_> {
         self.inner.draw(0..3, 0..1);
     }
 
+    /// Draws a triangle covering the whole target with `SCREEN_VERTEX_SHADER`.
+    /// This is the same as `draw_triangle()`, but makes the intent clearer.
+    #[inline]
+    pub fn draw_fullscreen(&mut self) {
+        self.draw_triangle();
+    }
+
     pub fn draw_one(&mut self, vertices: u32) {
         self.inner.draw(0..vertices, 0..1);
     }
@@ -187,6 +214,19 @@ impl RenderPass<'_> {
     pub fn draw_one_indexed(&mut self, vertices: u32) {
         self.inner.draw_indexed(0..vertices, 0, 0..1);
     }
+
+    /// Draws `instances` instances of the first `vertices` vertices.
+    /// Per-instance data is read from buffers with an `Instance` step mode,
+    /// e.g. those of a `#[derive(VertexLayoutInstance)]` type.
+    pub fn draw_instanced(&mut self, vertices: u32, instances: u32) {
+        self.inner.draw(0..vertices, 0..instances);
+    }
+
+    /// Draws `instances` instances of the first `indices` indices.
+    /// See `draw_instanced()`.
+    pub fn draw_indexed_instanced(&mut self, indices: u32, instances: u32) {
+        self.inner.draw_indexed(0..indices, 0, 0..instances);
+    }
 }
 impl<'a> RenderPass<'a> {
     /// Shadows wgpu::RenderPass::set_bind_group and returns self for chaining
@@ -222,6 +262,18 @@ impl<'a> RenderPass<'a> {
         self
     }
 
+    /// Sets the whole buffer as the index buffer, using the buffer's index format.
+    /// # Panics
+    /// Panics if the buffer does not have an index format. Set one with
+    /// `BufferBuilder::as_index_buffer_u16()` or `as_index_buffer_u32()`.
+    pub fn set_index_buffer_auto(&mut self, buffer: &'a Buffer) -> &mut Self {
+        let format = buffer
+            .index_format
+            .expect("Index buffer does not have an index format");
+        self.inner.set_index_buffer(buffer.slice(..), format);
+        self
+    }
+
     /// Shadows wgpu::RenderPass::set_vertex_buffer and returns self for chaining
     pub fn set_vertex_buffer(
         &mut self,
@@ -232,6 +284,25 @@ impl<'a> RenderPass<'a> {
         self
     }
 
+    /// Executes a recorded render bundle, see `Gpu::new_render_bundle()`.
+    /// The pipeline, bind groups and buffers set in this pass are reset afterwards,
+    /// so they must be set again before further draws.
+    pub fn execute_bundle(&mut self, bundle: &'a wgpu::RenderBundle) -> &mut Self {
+        self.inner.execute_bundles(std::iter::once(bundle));
+        self
+    }
+
+    /// Sets the whole typed vertex buffer at the given slot.
+    /// The pipeline's layout for the slot should come from `VertexBuffer::layout()`.
+    pub fn set_typed_vertex_buffer<V>(
+        &mut self,
+        slot: u32,
+        buffer: &'a VertexBuffer<V>,
+    ) -> &mut Self {
+        self.inner.set_vertex_buffer(slot, buffer.slice(..));
+        self
+    }
+
     /// Sets the scissor region.
     ///
     /// Subsequent draw calls will discard any fragments that fall outside this region.
@@ -239,6 +310,90 @@ impl<'a> RenderPass<'a> {
         self.inner.set_scissor_rect(x, y, width, height);
         self
     }
+
+    /// Sets the viewport used during the rasterization stage, in pixels.
+    ///
+    /// `min_depth` and `max_depth` map the normalized depth to the depth buffer.
+    /// Both must be in `0.0..=1.0`, and `min_depth` must not be greater than `max_depth`.
+    /// The default viewport covers the whole target with a depth range of `0.0..=1.0`.
+    pub fn set_viewport(
+        &mut self,
+        x: f32,
+        y: f32,
+        width: f32,
+        height: f32,
+        min_depth: f32,
+        max_depth: f32,
+    ) -> &mut Self {
+        self.inner
+            .set_viewport(x, y, width, height, min_depth, max_depth);
+        self
+    }
+
+    /// Sets the stencil reference value used by stencil compare and `Replace` operations.
+    pub fn set_stencil_reference(&mut self, reference: u32) -> &mut Self {
+        self.inner.set_stencil_reference(reference);
+        self
+    }
+
+    /// Draws primitives using the arguments in the buffer at the given offset.
+    /// The buffer must be created with `as_indirect_buffer()`.
+    /// The arguments are `vertex_count, instance_count, base_vertex, base_instance`
+    /// as `u32`s.
+    pub fn draw_indirect(&mut self, buffer: &'a Buffer, offset: u64) -> &mut Self {
+        self.inner.draw_indirect(&buffer.inner, offset);
+        self
+    }
+
+    /// Draws indexed primitives using the arguments in the buffer at the given offset.
+    /// The buffer must be created with `as_indirect_buffer()`.
+    /// The arguments are `index_count, instance_count, base_index, vertex_offset,
+    /// base_instance` as `u32`s, except `vertex_offset` which is an `i32`.
+    pub fn draw_indexed_indirect(&mut self, buffer: &'a Buffer, offset: u64) -> &mut Self {
+        self.inner.draw_indexed_indirect(&buffer.inner, offset);
+        self
+    }
+
+    /// Issues `count` consecutive indirect draws starting at the given offset.
+    /// If `Features::MULTI_DRAW_INDIRECT` is not enabled on the device, this
+    /// falls back to a `draw_indirect()` per draw.
+    pub fn multi_draw_indirect(
+        &mut self,
+        buffer: &'a Buffer,
+        offset: u64,
+        count: u32,
+    ) -> &mut Self {
+        if buffer.gpu.has_feature(wgpu::Features::MULTI_DRAW_INDIRECT) {
+            self.inner.multi_draw_indirect(&buffer.inner, offset, count);
+        } else {
+            for i in 0..count as u64 {
+                self.inner
+                    .draw_indirect(&buffer.inner, offset + i * DRAW_INDIRECT_SIZE);
+            }
+        }
+        self
+    }
+
+    /// Issues `count` consecutive indexed indirect draws starting at the given offset.
+    /// If `Features::MULTI_DRAW_INDIRECT` is not enabled on the device, this
+    /// falls back to a `draw_indexed_indirect()` per draw.
+    pub fn multi_draw_indexed_indirect(
+        &mut self,
+        buffer: &'a Buffer,
+        offset: u64,
+        count: u32,
+    ) -> &mut Self {
+        if buffer.gpu.has_feature(wgpu::Features::MULTI_DRAW_INDIRECT) {
+            self.inner
+                .multi_draw_indexed_indirect(&buffer.inner, offset, count);
+        } else {
+            for i in 0..count as u64 {
+                self.inner
+                    .draw_indexed_indirect(&buffer.inner, offset + i * DRAW_INDEXED_INDIRECT_SIZE);
+            }
+        }
+        self
+    }
 }
 
 impl<'a> Deref for RenderPass<'a> {
@@ -287,6 +442,26 @@ impl crate::CommandEncoder {
             init_color_attachments: None,
         }
     }
+
+    /// Creates a render pass with only a depth attachment and no color targets.
+    /// This is useful for shadow maps, and should be used with a pipeline that
+    /// has a depth state and no fragment targets.
+    pub fn depth_pass<'a>(
+        &'a mut self,
+        label: &'a str,
+        depth: DepthAttachment<'a>,
+    ) -> RenderPassBuilder<'a, 'a> {
+        RenderPassBuilder {
+            encoder: self,
+            desc: wgpu::RenderPassDescriptor {
+                color_attachments: &[],
+                label: Some(label),
+                depth_stencil_attachment: Some(depth),
+            },
+            init_pipeline: None,
+            init_color_attachments: None,
+        }
+    }
 }
 
 impl<D> Texture<D>
@@ -339,26 +514,49 @@ where
             }),
         }
     }
+
+    /// Clears every mip level and layer of the texture to zero.
+    /// This does not need a render pass and works for any format, but can only clear
+    /// to zero. Use `clear_with_pass()` to clear to a color.
+    /// # Errors
+    /// Errors if the device does not have `CLEAR_COMMANDS` enabled
+    pub fn clear(&self, encoder: &mut CommandEncoder) -> Result<(), crate::GpuError> {
+        if !self.gpu.has_feature(wgpu::Features::CLEAR_COMMANDS) {
+            return Err(crate::GpuError::MissingFeature(
+                wgpu::Features::CLEAR_COMMANDS,
+            ));
+        }
+        encoder.clear_texture(self, &wgpu::ImageSubresourceRange::default());
+        Ok(())
+    }
+
+    /// Clears the texture to a color (in `0xRRGGBBAA` format) with an empty render pass.
+    /// Only the texture's view is cleared, which is the first mip level.
+    /// The texture must be a color render target.
+    pub fn clear_with_pass(&self, encoder: &mut CommandEncoder, color: u32) {
+        encoder
+            .render_pass("clear pass", &[self.attach_render().clear_color(color)])
+            .begin();
+    }
 }
 
 pub type RenderAttachment<'a> = wgpu::RenderPassColorAttachment<'a>;
 pub type DepthAttachment<'a> = wgpu::RenderPassDepthStencilAttachment<'a>;
 
 impl Frame<'_> {
+    /// Attach the frame as a render target.
+    /// If MSAA is enabled, this renders to the multisampled texture and
+    /// resolves into the surface texture.
     pub const fn attach_render(&self) -> RenderAttachment<'_> {
-        wgpu::RenderPassColorAttachment {
-            view: self.view.deref_const(),
-            resolve_target: None,
-            ops: wgpu::Operations {
-                load: wgpu::LoadOp::Load,
-                store: true,
-            },
-        }
+        crate::color_attachment(&self.view, self.msaa_view.as_ref(), wgpu::LoadOp::Load)
     }
 
+    /// Attach the frame's depth.
+    /// # Panics
+    /// Panics if the viewport was created with `ViewportBuilder::no_depth()`.
     pub const fn attach_depth(&self) -> DepthAttachment<'_> {
         wgpu::RenderPassDepthStencilAttachment {
-            view: self.depth_texture.deref_const(),
+            view: self.depth_view(),
             depth_ops: Some(wgpu::Operations {
                 load: wgpu::LoadOp::Load,
                 store: true,
@@ -367,9 +565,13 @@ impl Frame<'_> {
         }
     }
 
+    /// Attach the frame's stencil. The viewport must be created with a depth format
+    /// that has a stencil aspect, see `ViewportBuilder::with_depth_format()`.
+    /// # Panics
+    /// Panics if the viewport was created with `ViewportBuilder::no_depth()`.
     pub const fn attach_stencil(&self) -> DepthAttachment<'_> {
         wgpu::RenderPassDepthStencilAttachment {
-            view: self.depth_texture.deref_const(),
+            view: self.depth_view(),
             depth_ops: None,
             stencil_ops: Some(wgpu::Operations {
                 load: wgpu::LoadOp::Load,
@@ -378,9 +580,12 @@ impl Frame<'_> {
         }
     }
 
+    /// Attach the frame's depth and stencil. See `attach_stencil()`.
+    /// # Panics
+    /// Panics if the viewport was created with `ViewportBuilder::no_depth()`.
     pub const fn attach_depth_stencil(&self) -> DepthAttachment<'_> {
         wgpu::RenderPassDepthStencilAttachment {
-            view: self.depth_texture.deref_const(),
+            view: self.depth_view(),
             depth_ops: Some(wgpu::Operations {
                 load: wgpu::LoadOp::Load,
                 store: true,
@@ -391,4 +596,11 @@ impl Frame<'_> {
             }),
         }
     }
+
+    const fn depth_view(&self) -> &wgpu::TextureView {
+        match &self.depth_texture {
+            Some(depth) => depth.deref_const(),
+            None => panic!("Viewport has no depth texture, it was created with no_depth()"),
+        }
+    }
 }
@@ -15,6 +15,10 @@ pub use wgpu;
 #[cfg(feature = "half")]
 pub use half::{bf16, f16};
 
+/// Export nalgebra crate
+#[cfg(feature = "nalgebra")]
+pub use nalgebra;
+
 pub(crate) mod macros;
 
 pub_const_flag!(QUERYSET_BUFFER_USAGE: wgpu::BufferUsages = MAP_READ | COPY_DST);
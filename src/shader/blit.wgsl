@@ -0,0 +1,12 @@
+// Samples the source texture across the whole target, for use with screen.vert.
+// This converts between formats, since the value is written in the target's format.
+
+[[group(0), binding(0)]]
+var t_src: texture_2d<f32>;
+[[group(0), binding(1)]]
+var s_src: sampler;
+
+[[stage(fragment)]]
+fn main([[location(0)]] uv: vec2<f32>) -> [[location(0)]] vec4<f32> {
+    return textureSample(t_src, s_src, uv);
+}
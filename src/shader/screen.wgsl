@@ -0,0 +1,17 @@
+// WGSL version of screen.vert, for backends that do not accept SPIR-V.
+
+struct VertexOutput {
+    [[location(0)]] uv: vec2<f32>;
+    [[builtin(position)]] position: vec4<f32>;
+};
+
+[[stage(vertex)]]
+fn main([[builtin(vertex_index)]] index: u32) -> VertexOutput {
+    var pos = array<vec2<f32>, 3>(vec2<f32>(-1.0, 1.0), vec2<f32>(3.0, 1.0), vec2<f32>(-1.0, -3.0));
+    var uv = array<vec2<f32>, 3>(vec2<f32>(0.0, 0.0), vec2<f32>(2.0, 0.0), vec2<f32>(0.0, 2.0));
+
+    var out: VertexOutput;
+    out.uv = uv[index];
+    out.position = vec4<f32>(pos[index], 0.0, 1.0);
+    return out;
+}
@@ -0,0 +1,6 @@
+// WGSL version of uv.frag, for backends that do not accept SPIR-V.
+
+[[stage(fragment)]]
+fn main([[location(0)]] uv: vec2<f32>) -> [[location(0)]] vec4<f32> {
+    return vec4<f32>(uv, 0.0, 1.0);
+}
@@ -2,14 +2,21 @@ pub use winit;
 use winit::event_loop::ControlFlow;
 
 use std::{
-    cell::{Cell, RefCell},
+    cell::{Cell, Ref, RefCell},
+    rc::Rc,
     time::{Duration, Instant},
 };
 
-use crate::{Frame, Gpu, GpuBuilder, GpuError, Viewport};
+use crate::{Frame, Gpu, GpuBuilder, GpuError, TexFormat, Viewport};
 
 mod display;
 
+mod input;
+pub use input::InputState;
+
+/// The number of delta time samples used to average the FPS
+pub const FPS_SAMPLES: usize = 32;
+
 /// A stateful opaque type that handles framerate and timing for continuous
 /// rendering.
 pub struct ProgramTime {
@@ -21,8 +28,15 @@ pub struct ProgramTime {
     frame_time_adjustment: Cell<Duration>,
     // The last time the frame was drawn. Used for calculating the adjusted frame time
     last_draw_time: Cell<Option<Instant>>,
+    // Ring buffer of the last delta times, used for calculating the average FPS
+    delta_samples: Cell<[Duration; FPS_SAMPLES]>,
+    // The total number of samples recorded. The next index is this modulo FPS_SAMPLES
+    delta_sample_count: Cell<usize>,
 }
 impl ProgramTime {
+    /// Target framerate which draws every frame without a cap. See `uncapped()`.
+    pub const UNCAPPED: f32 = f32::INFINITY;
+
     pub fn new(framerate: f32) -> Self {
         Self {
             target_framerate: framerate,
@@ -32,19 +46,49 @@ impl ProgramTime {
             delta_time: Cell::new(Duration::ZERO),
             frame_time_adjustment: Cell::new(Duration::ZERO),
             last_draw_time: Cell::new(None),
+            delta_samples: Cell::new([Duration::ZERO; FPS_SAMPLES]),
+            delta_sample_count: Cell::new(0),
+        }
+    }
+
+    /// Creates a `ProgramTime` that draws as fast as possible, e.g. for benchmarking,
+    /// while still tracking the delta time.
+    pub fn uncapped() -> Self {
+        Self::new(Self::UNCAPPED)
+    }
+
+    /// Whether the framerate is uncapped, see `uncapped()`.
+    pub fn is_uncapped(&self) -> bool {
+        self.target_framerate.is_infinite()
+    }
+
+    /// Sets the delta time and records it for the FPS average.
+    /// This is called automatically by `should_draw()`.
+    pub fn set_delta_time(&self, delta_time: Duration) {
+        self.delta_time.set(delta_time);
+
+        let count = self.delta_sample_count.get();
+        let mut samples = self.delta_samples.get();
+        samples[count % FPS_SAMPLES] = delta_time;
+        self.delta_samples.set(samples);
+        self.delta_sample_count.set(count + 1);
+    }
+
+    /// The average frames per second over the last `FPS_SAMPLES` frames.
+    /// Returns 0 if no frames have been drawn yet.
+    pub fn fps(&self) -> f32 {
+        let count = self.delta_sample_count.get().min(FPS_SAMPLES);
+        let total: Duration = self.delta_samples.get()[..count].iter().sum();
+        if total.is_zero() {
+            return 0.0;
         }
+        count as f32 / total.as_secs_f32()
     }
 
     pub fn should_draw(&self) -> bool {
         // Note that this is the only time where we get now() to minmize the runtime cost
         let now = Instant::now();
 
-        // Calculate the target frame time based on the framerate
-        let framerate_time = Duration::from_secs_f32(1.0 / self.target_framerate);
-
-        // Adjust the target frame time based on the last delta time
-        let target_frametime = framerate_time.saturating_sub(self.frame_time_adjustment.get());
-
         // * Use let-else (RFC 3137) when available
         let last_update_time = if let Some(last_update_time) = self.last_update_time.get() {
             last_update_time
@@ -56,6 +100,20 @@ impl ProgramTime {
 
         // Calculate the time since the last frame
         let time_since_last_frame = now - last_update_time;
+
+        // Always draw when uncapped, skipping the frame time calculation
+        if self.is_uncapped() {
+            self.last_update_time.set(Some(now));
+            self.set_delta_time(time_since_last_frame);
+            return true;
+        }
+
+        // Calculate the target frame time based on the framerate
+        let framerate_time = Duration::from_secs_f32(1.0 / self.target_framerate);
+
+        // Adjust the target frame time based on the last delta time
+        let target_frametime = framerate_time.saturating_sub(self.frame_time_adjustment.get());
+
         // Calculate adjusted wait time
         if let Some(last_draw_time) = self.last_draw_time.take() {
             let _last_draw_duration = now - last_draw_time;
@@ -67,7 +125,7 @@ impl ProgramTime {
         if time_since_last_frame >= target_frametime {
             self.last_update_time.set(Some(now));
             self.last_draw_time.set(Some(now));
-            self.delta_time.set(time_since_last_frame);
+            self.set_delta_time(time_since_last_frame);
 
             return true;
         }
@@ -95,10 +153,20 @@ pub struct GpuProgram {
     pub gpu: Gpu,
     pub viewport: Viewport,
     pub on_resize: RefCell<Option<ResizeFn>>,
+    pub on_exit: RefCell<Option<ExitFn>>,
     pub time: Option<ProgramTime>,
+    /// Keyboard and mouse state, if input tracking is enabled
+    pub input: Option<RefCell<InputState>>,
+    /// Set by `request_exit()`, the loop exits on the next event
+    exit_requested: Cell<bool>,
+    /// Set while the window has zero size, which pauses redrawing
+    minimized: Cell<bool>,
+    /// Additional windows created with `create_window()`
+    windows: RefCell<Vec<Rc<Viewport>>>,
 }
 
 type ResizeFn = Box<dyn FnMut(&GpuProgram, u32, u32)>;
+type ExitFn = Box<dyn FnOnce(&GpuProgram)>;
 
 impl GpuProgram {
     pub fn builder<'f>(title: &str) -> GpuProgramBuilder<'f> {
@@ -118,6 +186,21 @@ impl GpuProgram {
         })
     }
 
+    /// Like `run_draw()`, but the state is owned by the program loop and passed
+    /// to the closure each frame along with the program.
+    /// This avoids having to capture mutable state in the closure.
+    pub fn run_draw_with<S, F>(self, mut state: S, mut op: F) -> !
+    where
+        S: 'static,
+        F: 'static + FnMut(&mut S, &Self, Frame<'_>),
+    {
+        self.run(move |event, program, _, _| {
+            if let Event::RedrawFrame(frame) = event {
+                op(&mut state, program, frame);
+            }
+        })
+    }
+
     /// Run the program
     /// This is a wrapper over Run that does some stuff automatically like resizing
     /// and closing the window
@@ -138,13 +221,50 @@ impl GpuProgram {
             .take()
             .unwrap()
             .run(move |event, event_loop, control_flow| {
+                if let (Some(input), winit::event::Event::WindowEvent { event, .. }) =
+                    (&self.input, &event)
+                {
+                    input.borrow_mut().handle_event(event);
+                }
+
                 match event {
+                    // Closing an additional window only removes it from the program
+                    winit::event::Event::WindowEvent {
+                        event: winit::event::WindowEvent::CloseRequested,
+                        window_id,
+                    } if window_id != self.viewport.id() => {
+                        self.windows
+                            .borrow_mut()
+                            .retain(|viewport| viewport.id() != window_id);
+                    }
+
                     // Exit when close is requested
                     // This might not always be desired but it's fine for now.
                     winit::event::Event::WindowEvent {
                         event: winit::event::WindowEvent::CloseRequested,
                         ..
-                    } => *control_flow = ControlFlow::Exit,
+                    } => self.exit(control_flow),
+
+                    // The event handler may have set ControlFlow::Exit itself
+                    winit::event::Event::LoopDestroyed => {
+                        if let Some(handler) = self.on_exit.take() {
+                            handler(&self);
+                        }
+                    }
+
+                    // Resize additional windows, which pause redrawing on their own
+                    // while they have zero size
+                    winit::event::Event::WindowEvent {
+                        event: winit::event::WindowEvent::Resized(new_size),
+                        window_id,
+                    } if window_id != self.viewport.id() => {
+                        let (width, height) = new_size.into();
+                        if let (Some(viewport), true) =
+                            (self.additional_window(window_id), width != 0 && height != 0)
+                        {
+                            viewport.resize(width, height);
+                        }
+                    }
 
                     // Resize the viewport when the window is resized
                     winit::event::Event::WindowEvent {
@@ -152,27 +272,84 @@ impl GpuProgram {
                         ..
                     } => {
                         let (width, height) = new_size.into();
-                        self.viewport.resize(width, height);
-                        let mut on_resize = self.on_resize.borrow_mut();
-                        if let Some(handler) = on_resize.as_mut() {
-                            handler(&self, width, height);
+                        // The surface can not be configured with zero size, so drawing is
+                        // paused until the window is restored
+                        if width == 0 || height == 0 {
+                            self.minimized.set(true);
+                        } else {
+                            // The queued resize reconfigures the surface before the next
+                            // frame, which is always done even if the size is unchanged
+                            self.minimized.set(false);
+                            self.viewport.resize(width, height);
+                            let mut on_resize = self.on_resize.borrow_mut();
+                            if let Some(handler) = on_resize.as_mut() {
+                                handler(&self, width, height);
+                            }
                         }
                     }
 
+                    // Don't request frames that can't be drawn while minimized
+                    winit::event::Event::RedrawEventsCleared
+                        if self.minimized.get() && self.windows.borrow().is_empty() => {}
                     winit::event::Event::RedrawEventsCleared => {
                         // Clamp to some max framerate if the target framerate is set
                         if let Some(program_time) = &self.time {
                             if program_time.should_draw() {
-                                self.viewport.request_redraw()
+                                if !self.minimized.get() {
+                                    self.viewport.request_redraw();
+                                }
+                                for viewport in self.windows.borrow().iter() {
+                                    viewport.request_redraw();
+                                }
                             }
                         }
                     }
 
+                    // Additional windows get their own frame, but the event handler
+                    // is not called with the original event or a resize event
+                    winit::event::Event::RedrawRequested(w) if w != self.viewport.id() => {
+                        let viewport = match self.additional_window(w) {
+                            Some(viewport) => viewport,
+                            None => return,
+                        };
+                        let size = viewport.inner_size();
+                        if size.width == 0 || size.height == 0 {
+                            return;
+                        }
+
+                        let resized_to = *viewport.resize_to.borrow();
+                        let mut frame = match viewport.begin_frame() {
+                            Ok(frame) => frame,
+                            Err(e) => {
+                                tracing::warn!("Requested frame but {}. Redraw is suppressed", e);
+                                return;
+                            }
+                        };
+                        frame.delta_time = self
+                            .time
+                            .as_ref()
+                            .map(|time| time.delta_time.get().as_secs_f32());
+                        frame.resized_to = resized_to;
+                        self.handle_event(
+                            &mut event_handler,
+                            Event::RedrawWindowFrame(w, frame),
+                            event_loop,
+                            control_flow,
+                        );
+                        return;
+                    }
+
                     // Handle redrawing
                     // We manually call the event handler. This returns out of the closure iteration
+                    winit::event::Event::RedrawRequested(_) if self.minimized.get() => return,
                     winit::event::Event::RedrawRequested(w) => {
                         let resized_to = if let Some(new_size) = *self.viewport.resize_to.borrow() {
-                            event_handler(Event::Resize(new_size), &self, event_loop, control_flow);
+                            self.handle_event(
+                                &mut event_handler,
+                                Event::Resize(new_size),
+                                event_loop,
+                                control_flow,
+                            );
                             Some(new_size)
                         } else {
                             None
@@ -180,9 +357,9 @@ impl GpuProgram {
 
                         // We first call the event handler with the original event,
                         // in case the user wants to perform some operations before creating the Frame
-                        event_handler(
+                        self.handle_event(
+                            &mut event_handler,
                             Event::Winit(winit::event::Event::RedrawRequested(w)),
-                            &self,
                             event_loop,
                             control_flow,
                         );
@@ -205,7 +382,17 @@ impl GpuProgram {
                         frame.resized_to = resized_to;
 
                         // Then we call the event handler with the frame
-                        event_handler(Event::RedrawFrame(frame), &self, event_loop, control_flow);
+                        self.handle_event(
+                            &mut event_handler,
+                            Event::RedrawFrame(frame),
+                            event_loop,
+                            control_flow,
+                        );
+
+                        // Pressed and released input only lasts for one frame
+                        if let Some(input) = &self.input {
+                            input.borrow_mut().end_frame();
+                        }
 
                         // Return early so we don't call the event handler twice (once from 2 above)
                         return;
@@ -213,15 +400,122 @@ impl GpuProgram {
                     _ => {}
                 }
                 let event = Event::Winit(event);
-                event_handler(event, &self, event_loop, control_flow);
+                self.handle_event(&mut event_handler, event, event_loop, control_flow);
             })
     }
 
+    /// Creates an additional window that shares the program's gpu, e.g. for tool
+    /// palettes or secondary monitors. Its frames are passed to the `run()` closure as
+    /// `Event::RedrawWindowFrame` with the id of the window, and its viewport is resized
+    /// along with the window. The `on_resize` handler is only called for the main window.
+    ///
+    /// Closing the window removes it from the program instead of exiting. The window is
+    /// destroyed once the returned viewport is dropped as well.
+    /// # Errors
+    /// Errors with `DisplayNone` if the window could not be created.
+    /// # Panics
+    /// Panics if called after `run()`, since windows are created through the event loop.
+    pub fn create_window(
+        &self,
+        builder: winit::window::WindowBuilder,
+    ) -> Result<Rc<Viewport>, GpuError> {
+        let event_loop = self
+            .event_loop
+            .take()
+            .expect("Windows must be created before run()");
+        let window = builder.build(&event_loop);
+        self.event_loop.set(Some(event_loop));
+
+        let viewport = Rc::new(
+            self.gpu
+                .new_viewport(window.map_err(|_| GpuError::DisplayNone)?)
+                .create(),
+        );
+        self.windows.borrow_mut().push(viewport.clone());
+        Ok(viewport)
+    }
+
+    fn additional_window(&self, id: winit::window::WindowId) -> Option<Rc<Viewport>> {
+        self.windows
+            .borrow()
+            .iter()
+            .find(|viewport| viewport.id() == id)
+            .cloned()
+    }
+
+    /// The keyboard and mouse state. Can be used inside the `run()` and
+    /// `run_draw()` closures.
+    /// # Panics
+    /// Panics if the program was not built with `with_input_tracking()`.
+    pub fn input(&self) -> Ref<'_, InputState> {
+        self.input
+            .as_ref()
+            .expect("Input tracking is not enabled, see with_input_tracking()")
+            .borrow()
+    }
+
+    /// Whether the window is minimized, i.e. has zero size.
+    /// Frames are not drawn while minimized.
+    pub fn is_minimized(&self) -> bool {
+        self.minimized.get()
+    }
+
+    /// Sets a handler that is called when the window is resized.
+    /// It is not called when the window is minimized, since the size is zero.
     pub fn on_resize(&self, handler: impl FnMut(&GpuProgram, u32, u32) + 'static) {
         let mut on_resize = self.on_resize.borrow_mut();
         *on_resize = Some(Box::new(handler));
     }
 
+    /// Sets a handler that is called once before the program exits, e.g. to save state.
+    /// It runs on the main thread inside the event loop, so the gpu is still valid.
+    pub fn on_exit(&self, handler: impl FnOnce(&GpuProgram) + 'static) {
+        let mut on_exit = self.on_exit.borrow_mut();
+        *on_exit = Some(Box::new(handler));
+    }
+
+    /// Requests the program to exit. Can be called inside the `run()` and `run_draw()`
+    /// closures. The `on_exit` handler is called before the loop exits.
+    pub fn request_exit(&self) {
+        self.exit_requested.set(true);
+    }
+
+    /// Calls the event handler, then exits if it called `request_exit()`.
+    /// Exiting only sets the control flow, so the handler still receives the
+    /// remaining events, including `LoopDestroyed`.
+    fn handle_event<F>(
+        &self,
+        event_handler: &mut F,
+        event: Event<'_, ()>,
+        event_loop: &winit::event_loop::EventLoopWindowTarget<()>,
+        control_flow: &mut ControlFlow,
+    ) where
+        F: FnMut(
+            Event<'_, ()>,
+            &Self,
+            &winit::event_loop::EventLoopWindowTarget<()>,
+            &mut winit::event_loop::ControlFlow,
+        ),
+    {
+        event_handler(event, self, event_loop, control_flow);
+        if self.exit_requested.replace(false) {
+            self.exit(control_flow);
+        }
+    }
+
+    fn exit(&self, control_flow: &mut ControlFlow) {
+        if let Some(handler) = self.on_exit.take() {
+            handler(self);
+        }
+        *control_flow = ControlFlow::Exit;
+    }
+
+    /// The average frames per second, if a target framerate is set.
+    /// See `ProgramTime::fps()`.
+    pub fn fps(&self) -> Option<f32> {
+        self.time.as_ref().map(ProgramTime::fps)
+    }
+
     pub fn set_framerate(&mut self, target_framerate: f32) {
         self.time = Some(ProgramTime::new(target_framerate));
     }
@@ -241,11 +535,30 @@ impl GpuProgram {
         // if monitor is not found, or if list of video modes is empty, go back to default
         DEFAULT_FRAMERATE
     }
+
+    /// Sets the title of the window.
+    /// Like the other window controls, this can be called from inside the
+    /// `run()` and `run_draw()` closures.
+    pub fn set_title(&self, title: &str) {
+        self.viewport.window.set_title(title);
+    }
+
+    /// Shows or hides the cursor while it is over the window.
+    pub fn set_cursor_visible(&self, visible: bool) {
+        self.viewport.window.set_cursor_visible(visible);
+    }
+
+    /// Sets the window to fullscreen, or windowed if `None`.
+    pub fn set_fullscreen(&self, fullscreen: Option<winit::window::Fullscreen>) {
+        self.viewport.window.set_fullscreen(fullscreen);
+    }
 }
 
 pub enum Event<'a, T: 'static> {
     Winit(winit::event::Event<'a, T>),
     RedrawFrame(Frame<'a>),
+    /// A frame for an additional window created with `GpuProgram::create_window()`.
+    RedrawWindowFrame(winit::window::WindowId, Frame<'a>),
     /// Called before resolving a pending resize.
     /// This is different from winit's ResizeRequested.
     /// This is only called right before drawing, so there is a promise that the
@@ -261,6 +574,14 @@ pub struct GpuProgramBuilder<'a> {
     /// Force the gpu surface to be srgb (or linear)
     /// Defaults to system preferred
     pub srgb: Option<bool>,
+    /// Track keyboard and mouse state, see `GpuProgram::input()`
+    pub input_tracking: bool,
+    /// How frames are presented, see `ViewportBuilder::with_present_mode()`
+    pub present_mode: Option<wgpu::PresentMode>,
+    /// Let the display pace frames instead of the software framerate cap
+    pub adaptive_present: bool,
+    /// The format of the viewport's depth texture, see `ViewportBuilder::with_depth_format()`
+    pub depth_format: Option<wgpu::TextureFormat>,
 }
 
 impl GpuProgramBuilder<'_> {
@@ -275,34 +596,106 @@ impl GpuProgramBuilder<'_> {
     }
 
     /// Sets the window to continuously draw at the given framerate.
+    /// This is a software cap on top of the present mode's pacing, see
+    /// `with_present_mode_adaptive()`.
     pub fn with_framerate(mut self, framerate: f32) -> Self {
         self.framerate = Some(framerate);
         self
     }
 
+    /// Sets the window to continuously draw as fast as possible, e.g. for benchmarking.
+    /// Delta times are still tracked. See `ProgramTime::uncapped()`.
+    pub fn uncapped(mut self) -> Self {
+        self.framerate = Some(ProgramTime::UNCAPPED);
+        self
+    }
+
+    /// Sets how frames are presented to the window.
+    /// See `ViewportBuilder::with_present_mode()`.
+    pub fn with_present_mode(mut self, present_mode: wgpu::PresentMode) -> Self {
+        self.present_mode = Some(present_mode);
+        self
+    }
+
+    /// Presents frames with `Immediate` for the lowest latency, which lets a display
+    /// with variable refresh rate (VRR) pace the frames.
+    ///
+    /// The software cap from `with_framerate()` runs independently of the present mode,
+    /// so with a `Fifo` or `Mailbox` present mode the two can double-throttle. This
+    /// disables the software cap, so the program draws continuously as if `uncapped()`
+    /// when a framerate is set.
+    ///
+    /// wgpu 0.12 can not query the present modes a surface supports and has no
+    /// `FifoRelaxed`, so if `Immediate` is not supported wgpu falls back to `Fifo`,
+    /// which is then paced by vsync.
+    pub fn with_present_mode_adaptive(mut self) -> Self {
+        self.present_mode = Some(wgpu::PresentMode::Immediate);
+        self.adaptive_present = true;
+        self
+    }
+
+    /// Sets the format of the viewport's depth texture, which is resized with the
+    /// window. See `ViewportBuilder::with_depth_format()`.
+    pub fn with_depth_format(mut self, format: wgpu::TextureFormat) -> Self {
+        self.depth_format = Some(format);
+        self
+    }
+
     /// Forces the gpu surface to be srgb (or linear)
     pub fn with_srgb(mut self, srgb: bool) -> Self {
         self.srgb = Some(srgb);
         self
     }
 
+    /// Tracks keyboard and mouse state, which is queried with `GpuProgram::input()`.
+    pub fn with_input_tracking(mut self) -> Self {
+        self.input_tracking = true;
+        self
+    }
+
     pub fn build(self) -> Result<GpuProgram, GpuError> {
         let event_loop = winit::event_loop::EventLoop::new();
         let window = self.window.build(&event_loop).unwrap();
 
         let gpu = self.gpu.clone();
         let gpu = gpu.with_profiler().build(&window)?;
-        let viewport = gpu.new_viewport(window).create();
+        let mut viewport = gpu.new_viewport(window);
+        if let Some(present_mode) = self.present_mode {
+            viewport = viewport.with_present_mode(present_mode);
+        }
+        if let Some(depth_format) = self.depth_format {
+            viewport = viewport.with_depth_format(depth_format);
+        }
+        if let (Some(srgb), Some(format)) = (self.srgb, gpu.preferred_format) {
+            let format = TexFormat(format);
+            let format = if srgb { format.srgb() } else { format.linear() };
+            viewport = viewport.with_view_format(*format);
+        }
+        let viewport = viewport.create();
 
         // Create time module if there is a target framerate
-        let time = self.framerate.map(ProgramTime::new);
+        // The display paces frames in adaptive mode, so the software cap is disabled
+        let time = self.framerate.map(|framerate| {
+            if self.adaptive_present {
+                ProgramTime::uncapped()
+            } else {
+                ProgramTime::new(framerate)
+            }
+        });
 
         Ok(GpuProgram {
             event_loop: Cell::new(Some(event_loop)),
             viewport,
             gpu,
             on_resize: RefCell::new(None),
+            on_exit: RefCell::new(None),
             time,
+            input: self
+                .input_tracking
+                .then(|| RefCell::new(InputState::default())),
+            exit_requested: Cell::new(false),
+            minimized: Cell::new(false),
+            windows: RefCell::new(Vec::new()),
         })
     }
 }
@@ -411,6 +804,10 @@ impl GpuProgramBuilder<'_> {
     }
 
     /// Sets whether the background of the window should be transparent.
+    ///
+    /// Note that the surface configuration in wgpu 0.12 has no composite alpha
+    /// mode, so whether the window actually blends with the desktop depends on
+    /// the platform's default surface compositing.
     #[inline]
     pub fn with_transparent(mut self, transparent: bool) -> Self {
         self.window.window.transparent = transparent;
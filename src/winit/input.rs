@@ -0,0 +1,114 @@
+use std::collections::HashSet;
+
+use winit::event::{ElementState, MouseButton, VirtualKeyCode, WindowEvent};
+
+/// A simplified view of the keyboard and mouse, accumulated from window events.
+/// Enable it with `GpuProgramBuilder::with_input_tracking()` and query it with
+/// `GpuProgram::input()`.
+///
+/// "Pressed" and "released" only hold for the frame the change happened in,
+/// while "down" holds for as long as the key or button is held.
+#[derive(Clone, Debug, Default)]
+pub struct InputState {
+    keys_down: HashSet<VirtualKeyCode>,
+    keys_pressed: HashSet<VirtualKeyCode>,
+    keys_released: HashSet<VirtualKeyCode>,
+    buttons_down: HashSet<MouseButton>,
+    buttons_pressed: HashSet<MouseButton>,
+    buttons_released: HashSet<MouseButton>,
+    mouse_pos: Option<(f64, f64)>,
+}
+impl InputState {
+    /// Whether the key is currently held down
+    pub fn key_down(&self, key: VirtualKeyCode) -> bool {
+        self.keys_down.contains(&key)
+    }
+
+    /// Whether the key was pressed this frame. Key repeats are not counted.
+    pub fn key_pressed(&self, key: VirtualKeyCode) -> bool {
+        self.keys_pressed.contains(&key)
+    }
+
+    /// Whether the key was released this frame
+    pub fn key_released(&self, key: VirtualKeyCode) -> bool {
+        self.keys_released.contains(&key)
+    }
+
+    /// Whether the mouse button is currently held down
+    pub fn mouse_down(&self, button: MouseButton) -> bool {
+        self.buttons_down.contains(&button)
+    }
+
+    /// Whether the mouse button was pressed this frame
+    pub fn mouse_pressed(&self, button: MouseButton) -> bool {
+        self.buttons_pressed.contains(&button)
+    }
+
+    /// Whether the mouse button was released this frame
+    pub fn mouse_released(&self, button: MouseButton) -> bool {
+        self.buttons_released.contains(&button)
+    }
+
+    /// The position of the cursor in physical pixels, relative to the top left
+    /// of the window. `None` if the cursor is not over the window.
+    pub const fn mouse_pos(&self) -> Option<(f64, f64)> {
+        self.mouse_pos
+    }
+
+    /// Updates the state from a window event.
+    /// This is called automatically by `GpuProgram::run()`.
+    pub fn handle_event(&mut self, event: &WindowEvent) {
+        match event {
+            WindowEvent::KeyboardInput { input, .. } => {
+                let key = match input.virtual_keycode {
+                    Some(key) => key,
+                    None => return,
+                };
+                match input.state {
+                    // insert() is false for key repeats
+                    ElementState::Pressed => {
+                        if self.keys_down.insert(key) {
+                            self.keys_pressed.insert(key);
+                        }
+                    }
+                    ElementState::Released => {
+                        if self.keys_down.remove(&key) {
+                            self.keys_released.insert(key);
+                        }
+                    }
+                }
+            }
+            WindowEvent::MouseInput { state, button, .. } => match state {
+                ElementState::Pressed => {
+                    if self.buttons_down.insert(*button) {
+                        self.buttons_pressed.insert(*button);
+                    }
+                }
+                ElementState::Released => {
+                    if self.buttons_down.remove(button) {
+                        self.buttons_released.insert(*button);
+                    }
+                }
+            },
+            WindowEvent::CursorMoved { position, .. } => {
+                self.mouse_pos = Some((position.x, position.y));
+            }
+            WindowEvent::CursorLeft { .. } => self.mouse_pos = None,
+            // Releases won't be received while unfocused, so forget held input
+            WindowEvent::Focused(false) => {
+                self.keys_down.clear();
+                self.buttons_down.clear();
+            }
+            _ => {}
+        }
+    }
+
+    /// Clears the per-frame pressed and released state.
+    /// This is called automatically by `GpuProgram::run()` after each frame.
+    pub fn end_frame(&mut self) {
+        self.keys_pressed.clear();
+        self.keys_released.clear();
+        self.buttons_pressed.clear();
+        self.buttons_released.clear();
+    }
+}
@@ -0,0 +1,295 @@
+#![cfg(test)]
+
+mod common;
+
+#[test]
+fn bind_group_explicit_indices() {
+    let gpu = match common::headless_gpu() {
+        Some(gpu) => gpu,
+        None => return,
+    };
+
+    let a = gpu
+        .new_buffer("binding 0")
+        .as_uniform_buffer()
+        .create(&[0_u32; 4]);
+    let b = gpu
+        .new_buffer("binding 3")
+        .as_uniform_buffer()
+        .create(&[0_u32; 4]);
+
+    let mut bind_group = gpu
+        .build_bind_group()
+        .with_label("explicit indices")
+        .binding(0, a.bind())
+        .binding(3, b.bind())
+        .create();
+    assert_eq!(bind_group.binding_indices(), &[0, 3]);
+
+    // Rebinding keeps the explicit indices
+    bind_group.rebind(&[b.bind(), a.bind()]).unwrap();
+    let instance = bind_group.instance(&[a.bind(), b.bind()]);
+    assert_eq!(instance.binding_indices(), &[0, 3]);
+}
+
+#[test]
+fn storage_texture_access() {
+    let gpu = match common::headless_gpu() {
+        Some(gpu) => gpu,
+        None => return,
+    };
+
+    let texture = gpu
+        .new_texture("storage texture test")
+        .allow_storage_binding()
+        .with_format(wgpu::TextureFormat::R32Float)
+        .create_empty((4, 4));
+    let binding = texture.bind_storage_texture().unwrap();
+    assert!(matches!(
+        binding.ty,
+        wgpu::BindingType::StorageTexture {
+            access: wgpu::StorageTextureAccess::WriteOnly,
+            ..
+        }
+    ));
+    // Read access is not a guaranteed format feature
+    assert!(matches!(
+        texture.bind_storage_texture_readonly(),
+        Err(agpu::GpuError::UnsupportedFormat(
+            wgpu::TextureFormat::R32Float
+        ))
+    ));
+
+    // Bgra8 formats cannot be storage textures
+    let texture = gpu
+        .new_texture("bgra storage texture test")
+        .with_format(wgpu::TextureFormat::Bgra8Unorm)
+        .create_empty((4, 4));
+    assert!(matches!(
+        texture.bind_storage_texture(),
+        Err(agpu::GpuError::UnsupportedFormat(
+            wgpu::TextureFormat::Bgra8Unorm
+        ))
+    ));
+}
+
+#[test]
+fn storage_texture_read_access() {
+    let gpu = match agpu::Gpu::builder()
+        .with_optional_features(wgpu::Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES)
+        .build_headless()
+    {
+        Ok(gpu) => gpu,
+        Err(e) => return common::no_gpu(e),
+    };
+    let format = wgpu::TextureFormat::R32Float;
+    if !gpu
+        .format_features(format)
+        .flags
+        .contains(wgpu::TextureFormatFeatureFlags::STORAGE_READ_WRITE)
+    {
+        return common::unsupported("R32Float does not support storage read access");
+    }
+
+    let texture = gpu
+        .new_texture("readable storage texture test")
+        .allow_storage_binding()
+        .with_format(format)
+        .create_empty((4, 4));
+    let binding = texture.bind_storage_texture_readonly().unwrap();
+    assert!(matches!(
+        binding.ty,
+        wgpu::BindingType::StorageTexture {
+            access: wgpu::StorageTextureAccess::ReadOnly,
+            ..
+        }
+    ));
+}
+
+#[test]
+fn buffer_range_bindings() {
+    let gpu = match common::headless_gpu() {
+        Some(gpu) => gpu,
+        None => return,
+    };
+
+    let alignment = gpu.device.limits().min_uniform_buffer_offset_alignment as u64;
+    let buffer = gpu
+        .new_buffer("range binding")
+        .as_uniform_buffer()
+        .create_uninit(alignment * 2);
+
+    let a = buffer.bind_uniform_range(0, 16).unwrap();
+    let b = buffer.bind_uniform_range(alignment, 16).unwrap();
+    let _bind_group = gpu.create_bind_group(&[a, b]);
+
+    assert!(buffer.bind_uniform_range(4, 16).is_err());
+    assert!(buffer.bind_uniform_range(alignment, alignment + 1).is_err());
+    assert!(buffer.bind_uniform_range(0, 0).is_err());
+}
+
+#[test]
+fn bind_group_rebind_validation() {
+    let gpu = match common::headless_gpu() {
+        Some(gpu) => gpu,
+        None => return,
+    };
+
+    let uniform = gpu
+        .new_buffer("rebind uniform")
+        .as_uniform_buffer()
+        .create(&[0_u32; 4]);
+    let storage = gpu
+        .new_buffer("rebind storage")
+        .as_storage_buffer()
+        .create(&[0_u32; 4]);
+
+    let mut bind_group = gpu.create_bind_group(&[uniform.bind()]);
+    assert!(bind_group.rebind(&[uniform.bind()]).is_ok());
+    assert!(matches!(
+        bind_group.rebind(&[storage.bind_storage()]),
+        Err(agpu::GpuError::ValidationError(_))
+    ));
+    assert!(matches!(
+        bind_group.rebind(&[uniform.bind(), uniform.bind()]),
+        Err(agpu::GpuError::ValidationError(_))
+    ));
+}
+
+#[test]
+fn bind_group_fragment_visibility() {
+    let gpu = match common::headless_gpu() {
+        Some(gpu) => gpu,
+        None => return,
+    };
+
+    let uniform = gpu
+        .new_buffer("vertex uniform")
+        .as_uniform_buffer()
+        .create(&[0.0_f32; 4]);
+    let targets = [wgpu::TextureFormat::Rgba8Unorm.into()];
+
+    // The shader reads the uniform in the vertex stage
+    let both = gpu.create_bind_group(&[uniform.bind_uniform()]);
+    let result = gpu
+        .new_pipeline("vertex visible")
+        .with_vertex_fragment(include_bytes!("shader/vertex-uniform.wgsl"))
+        .with_fragment_targets(&targets)
+        .with_bind_groups(&[&both.layout])
+        .try_create();
+    assert!(result.is_ok());
+
+    let fragment = gpu.create_bind_group_fragment(&[uniform.bind_uniform()]);
+    let result = gpu
+        .new_pipeline("fragment visible")
+        .with_vertex_fragment(include_bytes!("shader/vertex-uniform.wgsl"))
+        .with_fragment_targets(&targets)
+        .with_bind_groups(&[&fragment.layout])
+        .try_create();
+    assert!(matches!(result, Err(agpu::GpuError::ValidationError(_))));
+}
+
+#[test]
+fn bind_group_labeled() {
+    let gpu = match common::headless_gpu() {
+        Some(gpu) => gpu,
+        None => return,
+    };
+
+    let uniform = gpu
+        .new_buffer("labeled uniform")
+        .as_uniform_buffer()
+        .create(&[0_u32; 4]);
+    let sampler = gpu.new_sampler("labeled sampler").create();
+    let bind_group =
+        gpu.create_bind_group_labeled("Labeled bind group", &[uniform.bind(), sampler.bind()]);
+    assert_eq!(bind_group.binding_indices(), &[0, 1]);
+}
+
+#[test]
+fn depth_comparison_binding() {
+    let gpu = match common::headless_gpu() {
+        Some(gpu) => gpu,
+        None => return,
+    };
+
+    let shadow_map = gpu
+        .new_texture("shadow map")
+        .as_depth()
+        .allow_binding()
+        .create_empty((16, 16));
+    let sampler = gpu
+        .new_sampler("shadow sampler")
+        .comparator(wgpu::CompareFunction::LessEqual)
+        .create();
+    let bind_group =
+        gpu.create_bind_group_fragment(&[shadow_map.bind_depth().unwrap(), sampler.bind()]);
+
+    // The layout must match `texture_depth_2d` and `sampler_comparison`
+    let result = gpu
+        .new_pipeline("shadow compare")
+        .with_fragment(include_bytes!("shader/shadow-compare.wgsl"))
+        .with_bind_groups(&[&bind_group.layout])
+        .try_create();
+    assert!(result.is_ok());
+
+    let stencil = gpu
+        .new_texture("depth stencil")
+        .as_depth_stencil()
+        .allow_binding()
+        .create_empty((16, 16));
+    assert!(matches!(
+        stencil.bind_depth(),
+        Err(agpu::GpuError::UnsupportedFormat(_))
+    ));
+}
+
+#[test]
+fn texture_binding_array() {
+    let gpu = match common::headless_gpu() {
+        Some(gpu) => gpu,
+        None => return,
+    };
+    let texture = gpu
+        .new_texture("array element")
+        .allow_binding()
+        .create_empty((4, 4));
+    if !gpu
+        .features()
+        .contains(wgpu::Features::TEXTURE_BINDING_ARRAY)
+    {
+        assert!(matches!(
+            gpu.create_texture_array_binding(&[&texture]),
+            Err(agpu::GpuError::MissingFeature(_))
+        ));
+    }
+
+    let gpu = match agpu::Gpu::builder()
+        .with_features(wgpu::Features::TEXTURE_BINDING_ARRAY)
+        .build_headless()
+    {
+        Ok(gpu) => gpu,
+        Err(e) => return common::unsupported(e),
+    };
+    let textures = (0..3)
+        .map(|_| {
+            gpu.new_texture("array element")
+                .allow_binding()
+                .create_empty((4, 4))
+        })
+        .collect::<Vec<_>>();
+    let texture_refs = textures.iter().collect::<Vec<_>>();
+    let array = gpu.create_texture_array_binding(&texture_refs).unwrap();
+    assert_eq!(array.len(), 3);
+    // The layout entry takes its count from the array
+    let _bind_group = gpu.create_bind_group_fragment(&[array.bind()]);
+
+    let larger = gpu
+        .new_texture("larger element")
+        .allow_binding()
+        .create_empty((8, 8));
+    assert!(matches!(
+        gpu.create_texture_array_binding(&[&textures[0], &larger]),
+        Err(agpu::GpuError::ValidationError(_))
+    ));
+}
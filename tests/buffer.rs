@@ -0,0 +1,381 @@
+#![cfg(test)]
+
+mod common;
+
+#[test]
+fn buffer_read_range() {
+    let gpu = match common::headless_gpu() {
+        Some(gpu) => gpu,
+        None => return,
+    };
+
+    let data = (0..1024_u32).collect::<Vec<_>>();
+    let buffer = gpu
+        .new_buffer("read_range test")
+        .as_storage_buffer()
+        .allow_copy_from()
+        .create(&data);
+
+    let size = std::mem::size_of::<u32>() as u64;
+    let read = buffer.read_range(100 * size..110 * size).unwrap();
+    let read: &[u32] = bytemuck::cast_slice(&read);
+
+    assert_eq!(read, &data[100..110]);
+}
+
+#[test]
+fn buffer_read_range_invalid() {
+    let gpu = match common::headless_gpu() {
+        Some(gpu) => gpu,
+        None => return,
+    };
+
+    let buffer = gpu
+        .new_buffer("read_range invalid test")
+        .as_storage_buffer()
+        .create(&[0_u32; 16]);
+    assert!(matches!(
+        buffer.read_range(0..4),
+        Err(agpu::GpuError::MissingBufferUsage(_))
+    ));
+
+    let buffer = gpu
+        .new_buffer("read_range invalid test")
+        .as_storage_buffer()
+        .allow_copy_from()
+        .create(&[0_u32; 16]);
+    assert!(matches!(
+        buffer.read_range(2..8),
+        Err(agpu::GpuError::InvalidBufferRange(_))
+    ));
+    assert!(matches!(
+        buffer.read_range(0..128),
+        Err(agpu::GpuError::InvalidBufferRange(_))
+    ));
+}
+
+#[test]
+fn align_to_power_of_two() {
+    assert_eq!(agpu::align_to(0, 256), 0);
+    assert_eq!(agpu::align_to(1, 256), 256);
+    assert_eq!(agpu::align_to(64, 256), 256);
+    assert_eq!(agpu::align_to(256, 256), 256);
+    assert_eq!(agpu::align_to(257, 256), 512);
+    assert_eq!(agpu::align_to(20, 4), 20);
+}
+
+#[test]
+fn dynamic_uniform_offsets() {
+    let gpu = match common::headless_gpu() {
+        Some(gpu) => gpu,
+        None => return,
+    };
+
+    let uniform = gpu
+        .new_dynamic_uniform::<[f32; 4]>("dynamic uniform test")
+        .capacity(3)
+        .create();
+    let alignment = gpu.limits().min_uniform_buffer_offset_alignment as u64;
+    assert_eq!(uniform.stride % alignment, 0);
+    assert!(uniform.stride >= 16);
+    assert_eq!(uniform.buffer.size, uniform.stride * 3);
+
+    let stride = uniform.stride as u32;
+    assert_eq!(uniform.push([0.0; 4]), Some(0));
+    assert_eq!(uniform.push([1.0; 4]), Some(stride));
+    assert_eq!(uniform.push([2.0; 4]), Some(2 * stride));
+    // Pushing capacity + 1 values does not wrap around onto the first value
+    assert_eq!(uniform.push([3.0; 4]), None);
+    assert_eq!(uniform.push([3.0; 4]), None);
+
+    uniform.reset();
+    assert_eq!(uniform.push([4.0; 4]), Some(0));
+}
+
+#[test]
+fn dynamic_uniform_zero_capacity() {
+    let gpu = match common::headless_gpu() {
+        Some(gpu) => gpu,
+        None => return,
+    };
+
+    let uniform = gpu
+        .new_dynamic_uniform::<[f32; 4]>("zero capacity test")
+        .capacity(0)
+        .create();
+    assert_eq!(uniform.capacity, 1);
+    assert_eq!(uniform.buffer.size, uniform.stride);
+    assert_eq!(uniform.push([0.0; 4]), Some(0));
+    assert_eq!(uniform.push([1.0; 4]), None);
+}
+
+#[test]
+fn index_buffer_format() {
+    let gpu = match common::headless_gpu() {
+        Some(gpu) => gpu,
+        None => return,
+    };
+
+    let u16_indices = gpu
+        .new_buffer("u16 indices")
+        .as_index_buffer()
+        .create(&[0u16, 1, 2]);
+    assert_eq!(u16_indices.index_format, Some(wgpu::IndexFormat::Uint16));
+
+    let u32_indices = gpu
+        .new_buffer("u32 indices")
+        .as_index_buffer()
+        .create(&[0u32, 1, 2]);
+    assert_eq!(u32_indices.index_format, Some(wgpu::IndexFormat::Uint32));
+
+    // An explicit format takes precedence over the inferred one
+    let explicit = gpu
+        .new_buffer("explicit indices")
+        .as_index_buffer_u32()
+        .create(&[0u16, 1, 2, 3]);
+    assert_eq!(explicit.index_format, Some(wgpu::IndexFormat::Uint32));
+
+    // Other types are not inferred from their size
+    let pairs = gpu
+        .new_buffer("u16 pairs")
+        .as_index_buffer()
+        .create(&[[0u16, 1], [2, 3]]);
+    assert_eq!(pairs.index_format, None);
+
+    let u16_triangles = gpu
+        .new_buffer("u16 triangles")
+        .create_indices(&[[0u16, 1, 2], [2, 1, 3]]);
+    assert_eq!(u16_triangles.index_format, Some(wgpu::IndexFormat::Uint16));
+
+    let u32_triangles = gpu
+        .new_buffer("u32 triangles")
+        .create_indices(&[[0u32, 1, 2]]);
+    assert_eq!(u32_triangles.index_format, Some(wgpu::IndexFormat::Uint32));
+
+    let vertices = gpu
+        .new_buffer("vertices")
+        .as_vertex_buffer()
+        .create(&[0u16, 1, 2]);
+    assert_eq!(vertices.index_format, None);
+}
+
+#[test]
+fn buffer_download_async() {
+    let gpu = match common::headless_gpu() {
+        Some(gpu) => gpu,
+        None => return,
+    };
+
+    let data = (0..64_u32).collect::<Vec<_>>();
+    let buffer = gpu
+        .new_buffer("download_async test")
+        .allow_copy_from()
+        .create(&data);
+
+    let download = buffer.download_async();
+    gpu.poll(wgpu::Maintain::Wait);
+    let read = futures::executor::block_on(download).unwrap();
+    assert_eq!(bytemuck::cast_slice::<_, u32>(&read), &data[..]);
+}
+
+#[test]
+fn staging_belt_write() {
+    let gpu = match common::headless_gpu() {
+        Some(gpu) => gpu,
+        None => return,
+    };
+
+    let buffer = gpu
+        .new_buffer("staging belt test")
+        .allow_copy()
+        .create(&[0_u32; 16]);
+
+    let mut encoder = gpu.create_command_encoder("staging belt test");
+    {
+        let mut belt = gpu.staging_belt();
+        belt.write_buffer(&mut encoder, &buffer, 0, &[1_u32, 2, 3, 4]);
+        belt.write_buffer(&mut encoder, &buffer, 32, &[5_u32, 6]);
+        belt.finish();
+    }
+    gpu.queue.submit([encoder.finish()]);
+    gpu.staging_belt().recall();
+
+    let read = buffer.download_immediately().unwrap();
+    let read: &[u32] = bytemuck::cast_slice(&read);
+    assert_eq!(&read[..4], &[1, 2, 3, 4]);
+    assert_eq!(&read[4..8], &[0; 4]);
+    assert_eq!(&read[8..10], &[5, 6]);
+}
+
+#[test]
+fn zeroed_buffer_size() {
+    let gpu = match common::headless_gpu() {
+        Some(gpu) => gpu,
+        None => return,
+    };
+
+    let buffer = gpu
+        .new_buffer("zeroed buffer")
+        .as_storage_buffer()
+        .allow_copy_from()
+        .create_zeroed::<[f32; 4]>(10);
+    assert_eq!(buffer.size, 10 * std::mem::size_of::<[f32; 4]>() as u64);
+
+    let data = buffer.download_immediately().unwrap();
+    assert!(data.iter().all(|&byte| byte == 0));
+}
+
+#[test]
+fn buffer_mapped_write() {
+    let gpu = match common::headless_gpu() {
+        Some(gpu) => gpu,
+        None => return,
+    };
+
+    let upload = gpu
+        .new_buffer("mapped write")
+        .allow_map_write()
+        .allow_copy_from()
+        .create_uninit(64);
+    let readback = gpu
+        .new_buffer("mapped read")
+        .allow_map_read()
+        .allow_copy_to()
+        .create_uninit(64);
+
+    let written = upload
+        .with_mapped_write(0..64, |bytes| {
+            bytes.copy_from_slice(&[7; 64]);
+            bytes.len()
+        })
+        .unwrap();
+    assert_eq!(written, 64);
+
+    let mut encoder = gpu.create_command_encoder("mapped copy");
+    encoder.copy_buffer_to_buffer(&upload, 0, &readback, 0, 64);
+    gpu.queue.submit([encoder.finish()]);
+
+    let read = readback.with_mapped_read(8..16, |bytes| bytes.to_vec());
+    assert_eq!(read.unwrap(), vec![7; 8]);
+
+    assert!(matches!(
+        readback.with_mapped_write(0..64, |_| ()),
+        Err(agpu::GpuError::MissingBufferUsage(
+            wgpu::BufferUsages::MAP_WRITE
+        ))
+    ));
+    assert!(matches!(
+        upload.with_mapped_write(4..64, |_| ()),
+        Err(agpu::GpuError::InvalidBufferRange(_))
+    ));
+}
+
+#[test]
+fn buffer_raw_bytes() {
+    let gpu = match common::headless_gpu() {
+        Some(gpu) => gpu,
+        None => return,
+    };
+
+    // A std140 block of a vec3 and a float, packed by hand
+    let mut block = Vec::new();
+    for value in [1.0_f32, 2.0, 3.0] {
+        block.extend_from_slice(&value.to_ne_bytes());
+    }
+    block.extend_from_slice(&7_u32.to_ne_bytes());
+
+    let mut buffer = gpu
+        .new_buffer("raw bytes test")
+        .allow_copy_from()
+        .allow_copy_to()
+        .create_bytes(&block);
+    assert_eq!(buffer.size(), 16);
+    assert_eq!(&*buffer.read_range(0..16).unwrap(), &block[..]);
+
+    // Writing more bytes grows the buffer
+    block.extend_from_slice(&[0xff; 16]);
+    buffer.write_bytes(&block);
+    assert_eq!(buffer.size(), 32);
+    assert_eq!(&*buffer.read_range(0..32).unwrap(), &block[..]);
+}
+
+#[test]
+fn buffer_resize_without_copy_src() {
+    let gpu = match common::headless_gpu() {
+        Some(gpu) => gpu,
+        None => return,
+    };
+
+    // The contents cannot be copied out, so they are discarded instead of
+    // causing a validation error
+    let mut buffer = gpu
+        .new_buffer("resize without COPY_SRC")
+        .as_vertex_buffer()
+        .create(&[1_u32; 4]);
+    buffer.resize(64);
+    gpu.poll(wgpu::Maintain::Wait);
+    assert_eq!(buffer.size(), 64);
+
+    // Growing keeps the contents if they can be copied
+    let mut buffer = gpu
+        .new_buffer("grow")
+        .as_storage_buffer()
+        .allow_copy_from()
+        .create(&[1_u32, 2, 3, 4]);
+    assert!(!buffer.grow(8));
+    assert_eq!(buffer.size(), 16);
+    assert!(buffer.grow(32));
+    assert_eq!(buffer.size(), 32);
+    let read = buffer.read_range(0..16).unwrap();
+    assert_eq!(bytemuck::cast_slice::<_, u32>(&read), [1, 2, 3, 4]);
+}
+
+#[test]
+fn buffer_clear() {
+    let gpu = match common::headless_gpu() {
+        Some(gpu) => gpu,
+        None => return,
+    };
+
+    let data = (1..=64_u32).collect::<Vec<_>>();
+    let buffer = gpu
+        .new_buffer("clear test")
+        .as_storage_buffer()
+        .allow_copy_to()
+        .allow_copy_from()
+        .create(&data);
+
+    let size = std::mem::size_of::<u32>() as u64;
+    let mut encoder = gpu.create_command_encoder("clear range");
+    buffer
+        .clear_range(&mut encoder, 8 * size, 8 * size)
+        .unwrap();
+    drop(encoder);
+    let read = buffer.read_range(0..buffer.size).unwrap();
+    let read: &[u32] = bytemuck::cast_slice(&read);
+    assert_eq!(&read[..8], &data[..8]);
+    assert_eq!(&read[8..16], &[0; 8]);
+    assert_eq!(&read[16..], &data[16..]);
+
+    let mut encoder = gpu.create_command_encoder("clear");
+    buffer.clear(&mut encoder).unwrap();
+    assert!(matches!(
+        buffer.clear_range(&mut encoder, 2, 4),
+        Err(agpu::GpuError::InvalidBufferRange(_))
+    ));
+    drop(encoder);
+    let read = buffer.read_range(0..buffer.size).unwrap();
+    assert!(read.iter().all(|&b| b == 0));
+
+    let buffer = gpu
+        .new_buffer("clear without copy dst")
+        .as_storage_buffer()
+        .create(&data);
+    let mut encoder = gpu.create_command_encoder("clear invalid");
+    assert!(matches!(
+        buffer.clear(&mut encoder),
+        Err(agpu::GpuError::MissingBufferUsage(
+            wgpu::BufferUsages::COPY_DST
+        ))
+    ));
+}
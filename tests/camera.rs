@@ -0,0 +1,39 @@
+#![cfg(test)]
+#![cfg(feature = "nalgebra")]
+
+use agpu::nalgebra::{Point3, Vector3, Vector4};
+use agpu::Camera;
+
+fn clip(camera: &Camera, point: [f32; 3]) -> Vector4<f32> {
+    let clip = camera.matrix() * Vector4::new(point[0], point[1], point[2], 1.0);
+    clip / clip.w
+}
+
+#[test]
+fn camera_perspective_depth_range() {
+    let mut camera = Camera::perspective(90.0_f32.to_radians(), 1.0, 1.0, 10.0);
+    camera.look_at(Point3::origin(), Point3::new(0.0, 0.0, -1.0), Vector3::y());
+
+    // wgpu clip space depth is 0..1
+    assert!(clip(&camera, [0.0, 0.0, -1.0]).z.abs() < 1e-5);
+    assert!((clip(&camera, [0.0, 0.0, -10.0]).z - 1.0).abs() < 1e-5);
+    // 90 degree fov reaches the edge of the screen at depth 1
+    assert!((clip(&camera, [0.0, 1.0, -1.0]).y - 1.0).abs() < 1e-5);
+
+    camera.update_aspect(2.0);
+    assert!((clip(&camera, [2.0, 0.0, -1.0]).x - 1.0).abs() < 1e-5);
+    assert_eq!(camera.as_uniform_bytes().len(), 64);
+}
+
+#[test]
+fn camera_orthographic_keeps_height() {
+    let mut camera = Camera::orthographic(4.0, 1.0, 0.0, 10.0);
+    camera.look_at(Point3::origin(), Point3::new(0.0, 0.0, -1.0), Vector3::y());
+    assert!((clip(&camera, [2.0, 2.0, -5.0]).x - 1.0).abs() < 1e-5);
+
+    camera.update_aspect(2.0);
+    let p = clip(&camera, [4.0, 2.0, -5.0]);
+    assert!((p.x - 1.0).abs() < 1e-5);
+    assert!((p.y - 1.0).abs() < 1e-5);
+    assert!((p.z - 0.5).abs() < 1e-5);
+}
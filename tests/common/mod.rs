@@ -0,0 +1,37 @@
+#![allow(dead_code)]
+
+use std::fmt::Display;
+
+/// Set this environment variable to fail GPU tests when no adapter is available,
+/// instead of skipping them, e.g. on machines that are expected to have a GPU.
+pub const REQUIRE_GPU: &str = "AGPU_REQUIRE_GPU";
+
+/// Creates a headless `Gpu` for tests that need a device.
+/// Returns `None` when no adapter is available (e.g. CI without a GPU), in
+/// which case the test should return early instead of failing. See `no_gpu()`.
+pub fn headless_gpu() -> Option<agpu::Gpu> {
+    match agpu::Gpu::builder().build_headless() {
+        Ok(gpu) => Some(gpu),
+        Err(e) => {
+            no_gpu(e);
+            None
+        }
+    }
+}
+
+/// Reports that a test is skipped because no `Gpu` could be created.
+/// Skips are printed to stderr, which `cargo test -- --show-output` shows.
+/// # Panics
+/// Panics if `AGPU_REQUIRE_GPU` is set, so the test fails instead
+pub fn no_gpu(reason: impl Display) {
+    if std::env::var_os(REQUIRE_GPU).is_some() {
+        panic!("no gpu available and {} is set: {}", REQUIRE_GPU, reason);
+    }
+    eprintln!("skipped, no gpu available: {}", reason);
+}
+
+/// Reports that a test is skipped because the adapter lacks an optional feature.
+/// This is not a failure even with `AGPU_REQUIRE_GPU` set.
+pub fn unsupported(reason: impl Display) {
+    eprintln!("skipped: {}", reason);
+}
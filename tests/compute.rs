@@ -0,0 +1,233 @@
+#![cfg(test)]
+
+use agpu::workgroup_count;
+
+mod common;
+
+#[test]
+fn workgroup_count_rounds_up() {
+    assert_eq!(workgroup_count([64, 1, 1], [64, 1, 1]), [1, 1, 1]);
+    assert_eq!(workgroup_count([65, 1, 1], [64, 1, 1]), [2, 1, 1]);
+    assert_eq!(workgroup_count([100, 30, 2], [8, 8, 1]), [13, 4, 2]);
+}
+
+#[test]
+fn compute_push_constants() {
+    if common::headless_gpu().is_none() {
+        return;
+    }
+    let gpu = match agpu::Gpu::builder()
+        .with_features(wgpu::Features::PUSH_CONSTANTS)
+        .with_limits(wgpu::Limits {
+            max_push_constant_size: 4,
+            ..Default::default()
+        })
+        .build_headless()
+    {
+        Ok(gpu) => gpu,
+        Err(e) => return common::unsupported(e),
+    };
+
+    let data = (0..100_u32).collect::<Vec<_>>();
+    let buffer = gpu
+        .new_buffer("push constant test")
+        .as_storage_buffer()
+        .allow_copy_from()
+        .create(&data);
+    let bind_group = gpu.create_bind_group(&[buffer.bind_storage().in_compute()]);
+
+    let scale = 3_u32;
+    gpu.new_compute()
+        .with_shader(include_bytes!("shader/push-constant.wgsl"))
+        .with_push_constants(&[wgpu::PushConstantRange {
+            stages: wgpu::ShaderStages::COMPUTE,
+            range: 0..4,
+        }])
+        .create_with_bindings(&[&bind_group])
+        .with_push_constant_data(bytemuck::bytes_of(&scale))
+        .dispatch_for([data.len() as u32, 1, 1], [64, 1, 1]);
+
+    let result = buffer.download_immediately().unwrap();
+    let result: &[u32] = bytemuck::cast_slice(&result);
+    let expected = data.iter().map(|x| x * scale).collect::<Vec<_>>();
+    assert_eq!(result, &expected[..]);
+}
+
+#[test]
+fn compute_passes_on_one_encoder() {
+    let gpu = match common::headless_gpu() {
+        Some(gpu) => gpu,
+        None => return,
+    };
+
+    let data = (0..100_u32).collect::<Vec<_>>();
+    let buffer = gpu
+        .new_buffer("compute pass test")
+        .as_storage_buffer()
+        .allow_copy_from()
+        .create(&data);
+    let bind_group = gpu.create_bind_group(&[buffer.bind_storage().in_compute()]);
+    let bind_groups = [&bind_group];
+    let pipeline = gpu
+        .new_compute()
+        .with_shader(include_bytes!("shader/double.wgsl"))
+        .create_with_bindings(&bind_groups);
+
+    // Both passes are sequenced on the same encoder, so the values double twice
+    let mut encoder = gpu.create_command_encoder("compute pass test");
+    for _ in 0..2 {
+        encoder
+            .compute_pass("double pass")
+            .with_pipeline(&pipeline)
+            .begin()
+            .dispatch_for([data.len() as u32, 1, 1], [64, 1, 1]);
+    }
+    gpu.queue.submit([encoder.finish()]);
+
+    let result = buffer.download_immediately().unwrap();
+    let result: &[u32] = bytemuck::cast_slice(&result);
+    let expected = data.iter().map(|x| x * 4).collect::<Vec<_>>();
+    assert_eq!(result, &expected[..]);
+}
+
+#[test]
+fn compute_entry_points() {
+    let gpu = match common::headless_gpu() {
+        Some(gpu) => gpu,
+        None => return,
+    };
+
+    let data = (0..100_u32).collect::<Vec<_>>();
+    let buffer = gpu
+        .new_buffer("entry point test")
+        .as_storage_buffer()
+        .allow_copy_from()
+        .create(&data);
+    let bind_group = gpu.create_bind_group(&[buffer.bind_storage().in_compute()]);
+    let bind_groups = [&bind_group];
+
+    // Add one, then triple
+    for entry in ["kernel_a", "kernel_b"] {
+        gpu.new_compute()
+            .with_shader(include_bytes!("shader/kernels.wgsl"))
+            .with_entry_point(entry)
+            .create_with_bindings(&bind_groups)
+            .dispatch_for([data.len() as u32, 1, 1], [64, 1, 1]);
+    }
+
+    let result = buffer.download_immediately().unwrap();
+    let result: &[u32] = bytemuck::cast_slice(&result);
+    let expected = data.iter().map(|x| (x + 1) * 3).collect::<Vec<_>>();
+    assert_eq!(result, &expected[..]);
+}
+
+#[test]
+fn submit_batch_in_order() {
+    let gpu = match common::headless_gpu() {
+        Some(gpu) => gpu,
+        None => return,
+    };
+
+    let data = (0..100_u32).collect::<Vec<_>>();
+    let buffer = gpu
+        .new_buffer("batch source")
+        .as_storage_buffer()
+        .allow_copy_from()
+        .create(&data);
+    let copy = gpu
+        .new_buffer("batch copy")
+        .allow_copy_from()
+        .allow_copy_to()
+        .create_empty::<u32>(data.len());
+    let bind_group = gpu.create_bind_group(&[buffer.bind_storage().in_compute()]);
+    let bind_groups = [&bind_group];
+    let pipeline = gpu
+        .new_compute()
+        .with_shader(include_bytes!("shader/double.wgsl"))
+        .create_with_bindings(&bind_groups);
+
+    // The copy is recorded in a later encoder, so it sees the doubled values
+    let mut compute_encoder = gpu.create_command_encoder("batch compute");
+    compute_encoder
+        .compute_pass("double pass")
+        .with_pipeline(&pipeline)
+        .begin()
+        .dispatch_for([data.len() as u32, 1, 1], [64, 1, 1]);
+    let mut copy_encoder = gpu.create_command_encoder("batch copy");
+    buffer.copy_to(&mut copy_encoder, &copy);
+    gpu.submit_batch(vec![compute_encoder, copy_encoder]);
+
+    let result = copy.download_immediately().unwrap();
+    let result: &[u32] = bytemuck::cast_slice(&result);
+    let expected = data.iter().map(|x| x * 2).collect::<Vec<_>>();
+    assert_eq!(result, &expected[..]);
+}
+
+#[test]
+fn discarded_encoder_does_not_run() {
+    let gpu = match common::headless_gpu() {
+        Some(gpu) => gpu,
+        None => return,
+    };
+
+    let data = (0..100_u32).collect::<Vec<_>>();
+    let buffer = gpu
+        .new_buffer("discard test")
+        .as_storage_buffer()
+        .allow_copy_from()
+        .create(&data);
+    let bind_group = gpu.create_bind_group(&[buffer.bind_storage().in_compute()]);
+    let bind_groups = [&bind_group];
+    let pipeline = gpu
+        .new_compute()
+        .with_shader(include_bytes!("shader/double.wgsl"))
+        .create_with_bindings(&bind_groups);
+
+    let mut encoder = gpu.create_command_encoder("discarded encoder");
+    encoder
+        .compute_pass("double pass")
+        .with_pipeline(&pipeline)
+        .begin()
+        .dispatch_for([data.len() as u32, 1, 1], [64, 1, 1]);
+    encoder.discard();
+
+    let result = buffer.download_immediately().unwrap();
+    let result: &[u32] = bytemuck::cast_slice(&result);
+    assert_eq!(result, &data[..]);
+}
+
+#[test]
+fn command_list_replays_each_submit() {
+    let gpu = match common::headless_gpu() {
+        Some(gpu) => gpu,
+        None => return,
+    };
+
+    let data = (0..100_u32).collect::<Vec<_>>();
+    let buffer = gpu
+        .new_buffer("command list test")
+        .as_storage_buffer()
+        .allow_copy_from()
+        .create(&data);
+    let bind_group = gpu.create_bind_group(&[buffer.bind_storage().in_compute()]);
+    let bind_groups = [&bind_group];
+    let pipeline = gpu
+        .new_compute()
+        .with_shader(include_bytes!("shader/double.wgsl"))
+        .create_with_bindings(&bind_groups);
+
+    let list = gpu.new_command_list("double list", |encoder| {
+        encoder
+            .compute_pass("double pass")
+            .with_pipeline(&pipeline)
+            .begin()
+            .dispatch_for([data.len() as u32, 1, 1], [64, 1, 1]);
+    });
+    list.submit();
+    list.submit();
+
+    let result = buffer.download_immediately().unwrap();
+    let result: &[u32] = bytemuck::cast_slice(&result);
+    let expected = data.iter().map(|x| x * 4).collect::<Vec<_>>();
+    assert_eq!(result, &expected[..]);
+}
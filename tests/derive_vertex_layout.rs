@@ -1,6 +1,8 @@
 #![cfg(test)]
 
-use agpu::{u8n, VertexLayout, VertexLayoutInstance};
+use agpu::{u8n, RenderAttachmentBuild, VertexLayout, VertexLayoutInstance};
+
+mod common;
 
 #[derive(VertexLayout)]
 struct MixedTypes {
@@ -75,3 +77,156 @@ fn vertex_layout_per_instance() {
         }
     );
 }
+
+#[repr(C)]
+#[derive(Clone, Copy, bytemuck::Pod, bytemuck::Zeroable, VertexLayout)]
+struct TypedVertex {
+    _position: [f32; 2],
+}
+
+#[test]
+fn typed_vertex_buffer_layout() {
+    let gpu = match common::headless_gpu() {
+        Some(gpu) => gpu,
+        None => return,
+    };
+
+    let vertices = [TypedVertex {
+        _position: [0.0, 1.0],
+    }; 3];
+    let vertex_buffer = gpu.new_vertex_buffer("typed vertices").create(&vertices);
+    assert_eq!(vertex_buffer.len(), 3);
+    assert_eq!(
+        vertex_buffer.layout::<1>(),
+        TypedVertex::vertex_buffer_layout::<1>()
+    );
+}
+
+#[test]
+fn typed_vertex_buffer_len_follows_writes() {
+    let gpu = match common::headless_gpu() {
+        Some(gpu) => gpu,
+        None => return,
+    };
+
+    let vertex = TypedVertex {
+        _position: [0.0, 1.0],
+    };
+    let mut vertex_buffer = gpu
+        .new_vertex_buffer("written vertices")
+        .allow_copy_to()
+        .create(&[vertex; 3]);
+    // Fewer vertices do not shrink the buffer, but are all that is drawn
+    vertex_buffer.write(&[vertex; 1]);
+    assert_eq!(vertex_buffer.len(), 1);
+    vertex_buffer.write(&[vertex; 5]);
+    assert_eq!(vertex_buffer.len(), 5);
+    vertex_buffer.write(&[]);
+    assert!(vertex_buffer.is_empty());
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, bytemuck::Pod, bytemuck::Zeroable, VertexLayoutInstance)]
+struct InstanceTransform {
+    _offset: [f32; 2],
+    _scale: f32,
+    _rotation: f32,
+    _color: [f32; 4],
+}
+
+#[test]
+fn instanced_pipeline_draw() {
+    let gpu = match common::headless_gpu() {
+        Some(gpu) => gpu,
+        None => return,
+    };
+
+    let vertex_buffer = gpu.new_vertex_buffer("triangle").create(&[
+        TypedVertex {
+            _position: [-1.0, -1.0],
+        },
+        TypedVertex {
+            _position: [1.0, -1.0],
+        },
+        TypedVertex {
+            _position: [0.0, 1.0],
+        },
+    ]);
+    // Each instance covers the center of one pixel of a 2x1 target
+    let instance = |x: f32, color: [f32; 4]| InstanceTransform {
+        _offset: [x, 0.0],
+        _scale: 0.5,
+        _rotation: 0.0,
+        _color: color,
+    };
+    let instance_buffer = gpu.new_vertex_buffer("instances").create(&[
+        instance(-0.5, [1.0, 0.0, 0.0, 1.0]),
+        instance(0.5, [0.0, 1.0, 0.0, 1.0]),
+    ]);
+
+    let vertex_layouts = agpu::VertexLayouts::new()
+        .add::<TypedVertex>()
+        .add::<InstanceTransform>();
+    let vertex_layouts = vertex_layouts.layouts();
+    let targets = [wgpu::TextureFormat::Rgba8Unorm.into()];
+    let pipeline = gpu
+        .new_pipeline("instanced pipeline")
+        .with_vertex_fragment(include_bytes!("../examples/shader/instancing.wgsl"))
+        .with_vertex_layouts(&vertex_layouts)
+        .with_fragment_targets(&targets)
+        .try_create()
+        .unwrap();
+
+    let texture = gpu
+        .new_texture("instanced target")
+        .with_format(wgpu::TextureFormat::Rgba8Unorm)
+        .as_render_target()
+        .allow_copy_from()
+        .create_empty((2, 1));
+    let mut encoder = gpu.create_command_encoder("instanced encoder");
+    encoder
+        .render_pass("instanced pass", &[texture.attach_render().clear()])
+        .with_pipeline(&pipeline)
+        .begin()
+        .set_typed_vertex_buffer(0, &vertex_buffer)
+        .set_typed_vertex_buffer(1, &instance_buffer)
+        .draw_instanced(3, instance_buffer.len());
+    gpu.queue.submit([encoder.finish()]);
+
+    assert_eq!(texture.read_pixel(0, 0).unwrap(), vec![255, 0, 0, 255]);
+    assert_eq!(texture.read_pixel(1, 0).unwrap(), vec![0, 255, 0, 255]);
+}
+
+#[test]
+fn vertex_layouts_consecutive_locations() {
+    let layouts = agpu::VertexLayouts::new()
+        .add::<MixedTypes>()
+        .add_instance::<MixedTypes>()
+        .add::<PerInstanceData>();
+    assert_eq!(layouts.next_location(), 10);
+
+    let layouts = layouts.layouts();
+    let locations = |i: usize| {
+        layouts[i]
+            .attributes
+            .iter()
+            .map(|a| a.shader_location)
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(locations(0), [0, 1, 2, 3]);
+    assert_eq!(locations(1), [4, 5, 6, 7]);
+    assert_eq!(locations(2), [8, 9]);
+
+    // The offsets and formats are unchanged
+    assert_eq!(
+        layouts[1].attributes[1],
+        wgpu::VertexAttribute {
+            offset: 12,
+            shader_location: 5,
+            format: wgpu::VertexFormat::Unorm8x4,
+        }
+    );
+    assert_eq!(layouts[0].step_mode, wgpu::VertexStepMode::Vertex);
+    assert_eq!(layouts[1].step_mode, wgpu::VertexStepMode::Instance);
+    assert_eq!(layouts[2].step_mode, wgpu::VertexStepMode::Instance);
+}
@@ -0,0 +1,43 @@
+#![cfg(test)]
+#![cfg(feature = "egui")]
+
+use agpu::RenderAttachmentBuild;
+
+mod common;
+
+#[test]
+fn egui_draws_label() {
+    let gpu = match common::headless_gpu() {
+        Some(gpu) => gpu,
+        None => return,
+    };
+
+    let mut egui = agpu::egui::Egui::new(gpu.clone(), 64, 64);
+    // The first run sets the font atlas, which the label is drawn with
+    egui.run(egui::RawInput::default(), |ctx| {
+        egui::CentralPanel::default().show(ctx, |ui| ui.label("Hello World!"));
+    });
+
+    let texture = gpu
+        .new_texture("egui target")
+        .with_format(wgpu::TextureFormat::Bgra8UnormSrgb)
+        .as_render_target()
+        .allow_copy_from()
+        .create_empty((64, 64));
+    let mut encoder = gpu.create_command_encoder("egui encoder");
+    {
+        let mut pass = encoder
+            .render_pass("egui pass", &[texture.attach_render().clear()])
+            .begin();
+        egui.draw(&mut pass, 64, 64);
+    }
+    gpu.queue.submit([encoder.finish()]);
+
+    // The panel is filled, and the label is drawn over it in a different color
+    let pixels = texture.read_immediately_rgba().unwrap();
+    let mut colors = pixels.chunks_exact(4).collect::<Vec<_>>();
+    colors.sort_unstable();
+    colors.dedup();
+    assert!(!colors.contains(&&[0, 0, 0, 0][..]));
+    assert!(colors.len() > 1);
+}
@@ -0,0 +1,25 @@
+#![cfg(test)]
+
+use agpu::GpuError;
+
+fn surface() -> Result<(), GpuError> {
+    Err(wgpu::SurfaceError::Outdated)?;
+    Ok(())
+}
+
+#[test]
+fn gpu_error_display() {
+    let e = surface().unwrap_err();
+    assert!(matches!(
+        e,
+        GpuError::SurfaceError(wgpu::SurfaceError::Outdated)
+    ));
+    assert!(e.to_string().starts_with("failed to get surface texture"));
+    assert!(std::error::Error::source(&e).is_some());
+
+    let e = GpuError::MissingFeature(wgpu::Features::PUSH_CONSTANTS);
+    assert!(e.to_string().contains("PUSH_CONSTANTS"));
+
+    let e = GpuError::UnsupportedFormat(wgpu::TextureFormat::Rgba8Unorm);
+    assert!(e.to_string().contains("Rgba8Unorm"));
+}
@@ -0,0 +1,95 @@
+#![cfg(test)]
+
+mod common;
+
+#[test]
+fn adapter_selection_by_name() {
+    let adapters = agpu::GpuBuilder::list_adapters(wgpu::Backends::PRIMARY);
+    let last = match adapters.last() {
+        Some(info) => info,
+        None => return common::no_gpu("no adapters listed"),
+    };
+
+    let gpu = agpu::Gpu::builder()
+        .with_adapter_name(&last.name)
+        .build_headless()
+        .unwrap();
+    assert_eq!(gpu.adapter.get_info().name, last.name);
+
+    // An unknown name falls back to the power preference
+    let gpu = agpu::Gpu::builder()
+        .with_adapter_name("no such adapter")
+        .build_headless();
+    assert!(gpu.is_ok());
+}
+
+#[test]
+fn limit_presets_are_clamped() {
+    // Only a missing adapter skips the test. Limits that are not clamped to the
+    // adapter fail to create the device, which must fail the test
+    if common::headless_gpu().is_none() {
+        return;
+    }
+    for builder in [
+        agpu::Gpu::builder().with_downlevel_limits(),
+        agpu::Gpu::builder().with_webgl_limits(),
+    ] {
+        let gpu = builder.build_headless().unwrap();
+        let limits = gpu.device.limits();
+        let supported = gpu.adapter.limits();
+        assert!(limits.max_texture_dimension_2d <= supported.max_texture_dimension_2d);
+        assert!(limits.max_bind_groups <= supported.max_bind_groups);
+        assert!(
+            limits.min_uniform_buffer_offset_alignment
+                >= supported.min_uniform_buffer_offset_alignment
+        );
+    }
+}
+
+#[test]
+fn uncaptured_error_handler() {
+    use std::sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    };
+
+    let caught = Arc::new(AtomicBool::new(false));
+    let handler_caught = caught.clone();
+    let gpu = match agpu::Gpu::builder()
+        .with_error_handler(Box::new(move |_| {
+            handler_caught.store(true, Ordering::SeqCst)
+        }))
+        .build_headless()
+    {
+        Ok(gpu) => gpu,
+        Err(e) => return common::no_gpu(e),
+    };
+
+    // A buffer cannot be both mappable for reading and used as a storage buffer
+    let _ = gpu.device.create_buffer(&wgpu::BufferDescriptor {
+        label: None,
+        size: 4,
+        usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::STORAGE,
+        mapped_at_creation: false,
+    });
+    assert!(caught.load(Ordering::SeqCst));
+}
+
+#[test]
+fn granted_features_and_limits() {
+    let gpu = match agpu::Gpu::builder()
+        .with_optional_features(wgpu::Features::all_native_mask())
+        .build_headless()
+    {
+        Ok(gpu) => gpu,
+        Err(e) => return common::no_gpu(e),
+    };
+
+    // Optional features are only granted if the adapter supports them
+    assert!(gpu.adapter.features().contains(gpu.features()));
+    assert!(gpu.has_feature(wgpu::Features::empty()));
+    assert_eq!(
+        gpu.limits().max_bind_groups,
+        wgpu::Limits::default().max_bind_groups
+    );
+}
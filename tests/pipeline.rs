@@ -0,0 +1,392 @@
+#![cfg(test)]
+
+use std::rc::Rc;
+
+mod common;
+
+#[test]
+fn pipeline_cache_hit() {
+    let gpu = match common::headless_gpu() {
+        Some(gpu) => gpu,
+        None => return,
+    };
+
+    let builder = gpu.new_pipeline("Cached pipeline");
+    let first = gpu.new_pipeline_cached(&builder);
+    let second = gpu.new_pipeline_cached(&gpu.new_pipeline("Same pipeline"));
+    assert!(Rc::ptr_eq(&first, &second));
+    assert_eq!(gpu.pipeline_cache.len(), 1);
+
+    let culled = gpu.new_pipeline_cached(&builder.cull_back());
+    assert!(!Rc::ptr_eq(&first, &culled));
+    assert_eq!(gpu.pipeline_cache.len(), 2);
+}
+
+#[test]
+fn pipeline_shader_auto_invalid() {
+    // Neither SPIR-V nor UTF-8
+    assert!(matches!(
+        agpu::PipelineBuilder::shader_auto(&[0xff, 0xfe, 0xfd]),
+        Err(agpu::GpuError::ShaderParseError)
+    ));
+    assert!(agpu::PipelineBuilder::shader_auto_load("does/not/exist.wgsl").is_err());
+}
+
+#[test]
+fn pipeline_try_create_bad_shader() {
+    let gpu = match common::headless_gpu() {
+        Some(gpu) => gpu,
+        None => return,
+    };
+
+    let result = gpu
+        .new_pipeline("Bad shader")
+        .with_vertex_fragment(b"fn vs_main( { this is not wgsl")
+        .try_create();
+    assert!(matches!(result, Err(agpu::GpuError::ShaderParseError)));
+}
+
+#[test]
+fn pipeline_watch_keeps_old_on_error() {
+    let gpu = match common::headless_gpu() {
+        Some(gpu) => gpu,
+        None => return,
+    };
+
+    let path = std::env::temp_dir().join("agpu_watch_test.wgsl");
+    std::fs::write(
+        &path,
+        include_str!("../examples/shader/hello-triangle.wgsl"),
+    )
+    .unwrap();
+    let path = path.to_str().unwrap();
+
+    let mut watched = gpu
+        .new_pipeline("Watched pipeline")
+        .load_vertex(path)
+        .load_fragment(path)
+        .with_vertex_entry("vs_main")
+        .with_fragment_entry("fs_main")
+        .watch();
+    assert_eq!(watched.paths().count(), 2);
+    assert!(!watched.poll_reload());
+
+    // Make sure the mtime changes on filesystems with coarse timestamps
+    std::thread::sleep(std::time::Duration::from_millis(1100));
+    std::fs::write(path, "this is not wgsl").unwrap();
+    assert!(!watched.poll_reload());
+    // The old pipeline is still usable
+    let _: &wgpu::RenderPipeline = &watched;
+}
+
+/// Creates an empty temp directory for preprocessor tests
+fn include_dir(name: &str) -> std::path::PathBuf {
+    let dir = std::env::temp_dir().join(name);
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[test]
+fn preprocess_include_and_define() {
+    let dir = include_dir("agpu_preprocess_include");
+    std::fs::write(
+        dir.join("lighting.wgsl"),
+        "#include \"common.wgsl\"\nfn light() -> f32 { return AMBIENT; }",
+    )
+    .unwrap();
+    std::fs::write(dir.join("common.wgsl"), "#define AMBIENT 0.1").unwrap();
+
+    let source = "#include \"lighting.wgsl\"\nlet scale: f32 = SCALE;\nlet scaled = SCALE_2;";
+    let out = agpu::preprocess_wgsl(source, &dir, &[("SCALE", "2.0")]).unwrap();
+
+    assert!(out.contains("fn light() -> f32 { return 0.1; }"));
+    assert!(out.contains("let scale: f32 = 2.0;"));
+    // Only whole words are substituted
+    assert!(out.contains("let scaled = SCALE_2;"));
+    assert!(!out.contains("#include"));
+    assert!(!out.contains("#define"));
+}
+
+#[test]
+fn preprocess_define_precedence() {
+    let dir = include_dir("agpu_preprocess_precedence");
+    let out = agpu::preprocess_wgsl("#define N 1\nN", &dir, &[("N", "2")]).unwrap();
+    assert_eq!(out.trim(), "2");
+}
+
+#[test]
+fn preprocess_cyclic_include() {
+    let dir = include_dir("agpu_preprocess_cycle");
+    std::fs::write(dir.join("a.wgsl"), "#include \"b.wgsl\"").unwrap();
+    std::fs::write(dir.join("b.wgsl"), "#include \"a.wgsl\"").unwrap();
+
+    assert!(matches!(
+        agpu::preprocess_wgsl("#include \"a.wgsl\"", &dir, &[]),
+        Err(agpu::GpuError::ShaderParseError)
+    ));
+    assert!(matches!(
+        agpu::preprocess_wgsl("#include \"missing.wgsl\"", &dir, &[]),
+        Err(agpu::GpuError::ShaderParseError)
+    ));
+}
+
+#[test]
+fn reflect_cube_shader() {
+    let source =
+        agpu::PipelineBuilder::make_wgsl(include_str!("../examples/shader/cube.wgsl")).unwrap();
+    let groups = agpu::reflect_layout_entries(&[
+        (&source, "vs_main", wgpu::ShaderStages::VERTEX),
+        (&source, "fs_main", wgpu::ShaderStages::FRAGMENT),
+    ])
+    .unwrap();
+
+    assert_eq!(groups.len(), 1);
+    let entries = &groups[0];
+    assert_eq!(entries.len(), 2);
+
+    assert_eq!(entries[0].binding, 0);
+    assert_eq!(entries[0].visibility, wgpu::ShaderStages::VERTEX);
+    assert!(matches!(
+        entries[0].ty,
+        wgpu::BindingType::Buffer {
+            ty: wgpu::BufferBindingType::Uniform,
+            ..
+        }
+    ));
+
+    assert_eq!(entries[1].binding, 1);
+    assert_eq!(entries[1].visibility, wgpu::ShaderStages::FRAGMENT);
+    assert_eq!(
+        entries[1].ty,
+        wgpu::BindingType::Texture {
+            sample_type: wgpu::TextureSampleType::Uint,
+            view_dimension: wgpu::TextureViewDimension::D2,
+            multisampled: false,
+        }
+    );
+}
+
+#[test]
+fn reflect_missing_entry_point() {
+    let source =
+        agpu::PipelineBuilder::make_wgsl(include_str!("../examples/shader/cube.wgsl")).unwrap();
+    assert!(matches!(
+        agpu::reflect_layout_entries(&[(&source, "vs_main", wgpu::ShaderStages::FRAGMENT)]),
+        Err(agpu::GpuError::ReflectionError(_))
+    ));
+}
+
+#[test]
+fn pipeline_auto_layout() {
+    let gpu = match common::headless_gpu() {
+        Some(gpu) => gpu,
+        None => return,
+    };
+
+    let pipeline = gpu
+        .new_pipeline("auto layout test")
+        .with_vertex_fragment(include_bytes!("../examples/shader/cube.wgsl"))
+        .with_vertex_layouts(&[wgpu::VertexBufferLayout {
+            array_stride: 24,
+            step_mode: wgpu::VertexStepMode::Vertex,
+            attributes: &wgpu::vertex_attr_array![0 => Float32x4, 1 => Float32x2],
+        }])
+        .with_auto_layout()
+        .try_create()
+        .unwrap();
+    assert_eq!(pipeline.bind_group_layouts.len(), 1);
+}
+
+#[test]
+fn pipeline_multiview_requires_feature() {
+    let gpu = match common::headless_gpu() {
+        Some(gpu) => gpu,
+        None => return,
+    };
+    if gpu.features().contains(wgpu::Features::MULTIVIEW) {
+        return common::unsupported("the missing MULTIVIEW error needs a device without it");
+    }
+
+    let result = gpu
+        .new_pipeline("Multiview pipeline")
+        .with_multiview(2)
+        .try_create();
+    assert!(matches!(
+        result,
+        Err(agpu::GpuError::MissingFeature(wgpu::Features::MULTIVIEW))
+    ));
+}
+
+#[test]
+fn pipeline_recreate_for_format() {
+    let gpu = match common::headless_gpu() {
+        Some(gpu) => gpu,
+        None => return,
+    };
+
+    let targets = [wgpu::TextureFormat::Rgba8Unorm.into()];
+    let builder = gpu
+        .new_pipeline("Recreated pipeline")
+        .with_fragment_targets(&targets);
+
+    let mut pipeline = builder.create();
+    assert!(matches!(
+        pipeline.recreate(wgpu::TextureFormat::Rgba16Float),
+        Err(agpu::GpuError::ValidationError(_))
+    ));
+
+    let mut pipeline = builder.retain_descriptor().create();
+    pipeline.recreate(wgpu::TextureFormat::Rgba16Float).unwrap();
+
+    // The recreated pipeline must be compatible with the new format
+    let texture = gpu
+        .new_texture("Recreated target")
+        .with_format(wgpu::TextureFormat::Rgba16Float)
+        .as_render_target()
+        .create_empty((4, 4));
+    let mut encoder = gpu.create_command_encoder("Recreated encoder");
+    encoder
+        .render_pass("Recreated pass", &[texture.attach_render()])
+        .with_pipeline(&pipeline)
+        .begin()
+        .draw(0..3, 0..1);
+    gpu.queue.submit([encoder.finish()]);
+}
+
+#[test]
+fn pipeline_fullscreen() {
+    let gpu = match common::headless_gpu() {
+        Some(gpu) => gpu,
+        None => return,
+    };
+
+    let pipeline = gpu.fullscreen_pipeline(include_bytes!("../src/shader/blit.wgsl"));
+    // The texture and sampler of the blit shader
+    assert_eq!(pipeline.bind_group_layouts.len(), 1);
+}
+
+#[test]
+fn render_bundle_execute() {
+    use agpu::RenderAttachmentBuild;
+
+    let gpu = match common::headless_gpu() {
+        Some(gpu) => gpu,
+        None => return,
+    };
+
+    let format = wgpu::TextureFormat::Bgra8UnormSrgb;
+    let pipeline = gpu.new_pipeline("Bundle pipeline").create();
+    let bundle = {
+        let mut encoder = gpu.new_render_bundle("Bundle", &[format]).begin();
+        encoder.set_pipeline(&pipeline).draw_fullscreen();
+        encoder.finish()
+    };
+
+    let texture = gpu
+        .new_texture("Bundle target")
+        .with_format(format)
+        .as_render_target()
+        .allow_copy_from()
+        .create_empty((4, 4));
+    let mut encoder = gpu.create_command_encoder("Bundle encoder");
+    encoder
+        .render_pass("Bundle pass", &[texture.attach_render().clear()])
+        .begin()
+        .execute_bundle(&bundle);
+    gpu.queue.submit([encoder.finish()]);
+
+    // The cleared texture is transparent black until the bundle draws over it
+    assert_ne!(texture.read_pixel(2, 2).unwrap(), [0, 0, 0, 0]);
+}
+
+#[test]
+fn pipeline_stencil_test() {
+    use agpu::{DepthAttachmentBuild, RenderAttachmentBuild};
+    use wgpu::{CompareFunction, StencilFaceState, StencilOperation};
+
+    let gpu = match common::headless_gpu() {
+        Some(gpu) => gpu,
+        None => return,
+    };
+
+    // Draws only where the stencil equals the reference
+    let pipeline = gpu
+        .new_pipeline("Stencil pipeline")
+        .with_depth_stencil()
+        .depth_write(false)
+        .depth_compare(CompareFunction::Always)
+        .stencil(StencilFaceState {
+            compare: CompareFunction::Equal,
+            fail_op: StencilOperation::Keep,
+            depth_fail_op: StencilOperation::Keep,
+            pass_op: StencilOperation::Keep,
+        })
+        .create();
+
+    let texture = gpu
+        .new_texture("Stencil target")
+        .with_format(wgpu::TextureFormat::Bgra8UnormSrgb)
+        .as_render_target()
+        .allow_copy_from()
+        .create_empty((4, 4));
+    let stencil = gpu
+        .new_texture("Stencil texture")
+        .as_depth_stencil()
+        .create_empty((4, 4));
+
+    let draw = |reference| {
+        let mut encoder = gpu.create_command_encoder("Stencil encoder");
+        encoder
+            .render_pass("Stencil pass", &[texture.attach_render().clear()])
+            .with_depth(stencil.attach_depth_stencil().clear())
+            .begin()
+            .set_stencil_reference(reference)
+            .set_pipeline(&pipeline)
+            .draw_fullscreen();
+        gpu.queue.submit([encoder.finish()]);
+        texture.read_pixel(2, 2).unwrap()
+    };
+
+    // The stencil is cleared to 0
+    assert_eq!(draw(1), [0, 0, 0, 0]);
+    assert_ne!(draw(0), [0, 0, 0, 0]);
+}
+
+#[test]
+fn pipeline_wgsl_screen_shader() {
+    assert!(matches!(
+        agpu::PipelineBuilder::shader_auto(agpu::SCREEN_VERTEX_SHADER_WGSL),
+        Ok(wgpu::ShaderSource::Wgsl(_))
+    ));
+
+    let gpu = match common::headless_gpu() {
+        Some(gpu) => gpu,
+        None => return,
+    };
+
+    // The WGSL version is interchangeable with the SPIR-V default
+    let pipeline = gpu
+        .new_pipeline("WGSL screen pipeline")
+        .with_vertex(agpu::SCREEN_VERTEX_SHADER_WGSL)
+        .try_create();
+    assert!(pipeline.is_ok());
+}
+
+#[test]
+fn pipeline_reverse_z() {
+    let gpu = match common::headless_gpu() {
+        Some(gpu) => gpu,
+        None => return,
+    };
+
+    let pipeline = gpu
+        .new_pipeline("Reversed-Z pipeline")
+        .with_depth()
+        .reverse_z()
+        .create();
+    assert_eq!(
+        pipeline.depth_stencil.unwrap().depth_compare,
+        wgpu::CompareFunction::Greater
+    );
+}
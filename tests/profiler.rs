@@ -0,0 +1,136 @@
+#![cfg(test)]
+
+use agpu::RenderAttachmentBuild;
+
+mod common;
+
+#[test]
+fn nested_profile_scopes() {
+    // Timestamp queries are only available with the profiler features
+    if common::headless_gpu().is_none() {
+        return;
+    }
+    let gpu = match agpu::Gpu::builder().with_profiler().build_headless() {
+        Ok(gpu) => gpu,
+        Err(e) => return common::unsupported(e),
+    };
+
+    let mut encoder = gpu.create_command_encoder("profile scope test");
+    {
+        let mut outer = gpu.profile_scope("outer", &mut encoder);
+        let _inner = gpu.profile_scope("inner", &mut outer);
+    }
+    gpu.profiler.resolve(&mut encoder);
+    gpu.queue.submit([encoder.finish()]);
+
+    let report = gpu.timestamp_report();
+    let labels: Vec<_> = report.iter().map(|(label, _)| label.as_str()).collect();
+    assert_eq!(labels, ["outer", "inner"]);
+}
+
+#[test]
+fn profiler_query_overflow() {
+    if common::headless_gpu().is_none() {
+        return;
+    }
+    let gpu = match agpu::Gpu::builder().with_profiler().build_headless() {
+        Ok(gpu) => gpu,
+        Err(e) => return common::unsupported(e),
+    };
+
+    // Each scope writes two timestamps
+    let scopes = agpu::MAX_QUERIES / 2 + 8;
+    let mut encoder = gpu.create_command_encoder("profiler overflow test");
+    for _ in 0..scopes {
+        let _scope = gpu.profile_scope("scope", &mut encoder);
+    }
+    assert!(gpu.profiler.query_count() > agpu::MAX_QUERIES);
+    assert_eq!(gpu.profiler.written_query_count(), agpu::MAX_QUERIES);
+    gpu.profiler.resolve(&mut encoder);
+    gpu.queue.submit([encoder.finish()]);
+
+    let report = gpu.timestamp_report();
+    assert_eq!(report.len() as u32, agpu::MAX_QUERIES / 2);
+    gpu.profiler.clear();
+    assert_eq!(gpu.profiler.query_count(), 0);
+}
+
+#[test]
+fn pipeline_statistics_report() {
+    if common::headless_gpu().is_none() {
+        return;
+    }
+    let gpu = match agpu::Gpu::builder().with_profiler().build_headless() {
+        Ok(gpu) => gpu,
+        Err(e) => return common::unsupported(e),
+    };
+    let targets = [wgpu::TextureFormat::Rgba8Unorm.into()];
+    let pipeline = gpu
+        .new_pipeline("Statistics pipeline")
+        .with_fragment_targets(&targets)
+        .create();
+    let texture = gpu
+        .new_texture("Statistics target")
+        .with_format(wgpu::TextureFormat::Rgba8Unorm)
+        .as_render_target()
+        .create_empty((4, 4));
+
+    let mut encoder = gpu.create_command_encoder("Statistics encoder");
+    encoder
+        .render_pass("Statistics pass", &[texture.attach_render()])
+        .with_pipeline(&pipeline)
+        .begin()
+        .draw(0..3, 0..1);
+    gpu.profiler.resolve(&mut encoder);
+    gpu.queue.submit([encoder.finish()]);
+
+    let report = gpu.statistics_report();
+    // Pipeline statistics are optional even with the profiler
+    if report.is_empty() {
+        return common::unsupported("pipeline statistics queries are unsupported");
+    }
+    assert_eq!(report.len(), agpu::PIPELINE_STATISTICS_LABELS.len());
+    let (_, vertex_invocations) = report
+        .iter()
+        .find(|(label, _)| *label == "Vertex shader invocations")
+        .unwrap();
+    assert!(*vertex_invocations > 0);
+}
+
+#[test]
+fn nested_debug_groups() {
+    use std::sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    };
+
+    // Unbalanced debug groups are validation errors
+    let caught = Arc::new(AtomicBool::new(false));
+    let handler_caught = caught.clone();
+    let gpu = match agpu::Gpu::builder()
+        .with_error_handler(Box::new(move |_| {
+            handler_caught.store(true, Ordering::SeqCst)
+        }))
+        .build_headless()
+    {
+        Ok(gpu) => gpu,
+        Err(e) => return common::no_gpu(e),
+    };
+
+    let texture = gpu
+        .new_texture("debug group target")
+        .as_render_target()
+        .create_empty((4, 4));
+    let mut encoder = gpu.create_command_encoder("debug group encoder");
+    {
+        let mut outer = encoder.debug_group("outer");
+        let mut inner = outer.debug_group("inner");
+        let mut pass = inner
+            .render_pass("debug group pass", &[texture.attach_render().clear()])
+            .begin();
+        let _pass_group = pass.debug_group("pass group");
+    }
+    gpu.queue.submit([encoder.finish()]);
+    gpu.device.poll(wgpu::Maintain::Wait);
+    assert!(!caught.load(Ordering::SeqCst));
+}
@@ -0,0 +1,47 @@
+#![cfg(test)]
+
+use agpu::{GpuError, RenderGraph};
+
+#[test]
+fn render_graph_orders_by_dependency() {
+    let mut graph = RenderGraph::new();
+    graph
+        .import("scene")
+        .add_pass("composite", &["blurred", "scene"], &["output"], |_| {})
+        .add_pass("blur", &["bright"], &["blurred"], |_| {})
+        .add_pass("threshold", &["scene"], &["bright"], |_| {})
+        .add_pass("ui", &[], &["ui"], |_| {});
+
+    assert_eq!(
+        graph.order().unwrap(),
+        &["threshold", "blur", "composite", "ui"]
+    );
+}
+
+#[test]
+fn render_graph_validation() {
+    let mut graph = RenderGraph::new();
+    graph.add_pass("a", &["missing"], &["a"], |_| {});
+    assert!(matches!(
+        graph.order(),
+        Err(GpuError::InvalidRenderGraph(_))
+    ));
+
+    let mut graph = RenderGraph::new();
+    graph
+        .add_pass("a", &[], &["x"], |_| {})
+        .add_pass("b", &[], &["x"], |_| {});
+    assert!(matches!(
+        graph.order(),
+        Err(GpuError::InvalidRenderGraph(_))
+    ));
+
+    let mut graph = RenderGraph::new();
+    graph
+        .add_pass("a", &["y"], &["x"], |_| {})
+        .add_pass("b", &["x"], &["y"], |_| {});
+    assert!(matches!(
+        graph.order(),
+        Err(GpuError::InvalidRenderGraph(_))
+    ));
+}
@@ -0,0 +1,22 @@
+struct Data {
+    values: [[stride(4)]] array<u32>;
+};
+
+[[group(0), binding(0)]]
+var<storage, read_write> data: Data;
+
+[[stage(compute), workgroup_size(64)]]
+fn kernel_a([[builtin(global_invocation_id)]] global_id: vec3<u32>) {
+    if (global_id.x >= arrayLength(&data.values)) {
+        return;
+    }
+    data.values[global_id.x] = data.values[global_id.x] + 1u;
+}
+
+[[stage(compute), workgroup_size(64)]]
+fn kernel_b([[builtin(global_invocation_id)]] global_id: vec3<u32>) {
+    if (global_id.x >= arrayLength(&data.values)) {
+        return;
+    }
+    data.values[global_id.x] = data.values[global_id.x] * 3u;
+}
@@ -0,0 +1,10 @@
+[[group(0), binding(0)]]
+var t_shadow: texture_depth_2d;
+[[group(0), binding(1)]]
+var s_shadow: sampler_comparison;
+
+[[stage(fragment)]]
+fn main([[location(0)]] uv: vec2<f32>) -> [[location(0)]] vec4<f32> {
+    let lit = textureSampleCompare(t_shadow, s_shadow, uv, 0.5);
+    return vec4<f32>(vec3<f32>(lit), 1.0);
+}
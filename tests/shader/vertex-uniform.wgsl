@@ -0,0 +1,16 @@
+struct Uniforms {
+    position: vec4<f32>;
+};
+
+[[group(0), binding(0)]]
+var<uniform> uniforms: Uniforms;
+
+[[stage(vertex)]]
+fn vs_main() -> [[builtin(position)]] vec4<f32> {
+    return uniforms.position;
+}
+
+[[stage(fragment)]]
+fn fs_main() -> [[location(0)]] vec4<f32> {
+    return vec4<f32>(1.0);
+}
@@ -0,0 +1,775 @@
+#![cfg(test)]
+
+use agpu::{mip_level_count, D2Array, GpuError, TextureDimensions};
+
+mod common;
+
+#[test]
+fn texture_array_dimensions() {
+    let size = D2Array(64, 32, 4);
+    assert_eq!(size.dim(), wgpu::TextureDimension::D2);
+    assert_eq!(size.view_dim(), wgpu::TextureViewDimension::D2Array);
+    assert_eq!(size.as_extent().depth_or_array_layers, 4);
+
+    // A 3-tuple is still a true 3D texture
+    let size = (64, 32, 4);
+    assert_eq!(size.dim(), wgpu::TextureDimension::D3);
+    assert_eq!(size.view_dim(), wgpu::TextureViewDimension::D3);
+}
+
+#[test]
+fn texture_array_bind() {
+    let gpu = match common::headless_gpu() {
+        Some(gpu) => gpu,
+        None => return,
+    };
+
+    let texture = gpu
+        .new_texture("texture array test")
+        .allow_binding()
+        .create_empty(D2Array(64, 64, 4));
+    let _bind_group = gpu.create_bind_group(&[texture.bind_texture_array().in_fragment()]);
+
+    assert_eq!(texture.size.depth(), 4);
+    assert!(matches!(
+        texture.bind_texture().ty,
+        wgpu::BindingType::Texture {
+            view_dimension: wgpu::TextureViewDimension::D2Array,
+            ..
+        }
+    ));
+}
+
+#[test]
+fn sampler_anisotropy() {
+    let gpu = match common::headless_gpu() {
+        Some(gpu) => gpu,
+        None => return,
+    };
+
+    let builder = gpu.new_sampler("anisotropy test").anisotropy(16);
+    assert_eq!(builder.inner.anisotropy_clamp.map(|a| a.get()), Some(16));
+    assert_eq!(builder.inner.min_filter, wgpu::FilterMode::Linear);
+    let _ = builder.create();
+
+    let builder = gpu.new_sampler("no anisotropy test").anisotropy(1);
+    assert_eq!(builder.inner.anisotropy_clamp, None);
+}
+
+#[test]
+fn sampler_address_modes() {
+    let gpu = match common::headless_gpu() {
+        Some(gpu) => gpu,
+        None => return,
+    };
+
+    let builder = gpu.new_sampler("clamp test").clamp();
+    assert_eq!(builder.inner.address_mode_u, wgpu::AddressMode::ClampToEdge);
+    assert_eq!(builder.inner.address_mode_v, wgpu::AddressMode::ClampToEdge);
+    assert_eq!(builder.inner.address_mode_w, wgpu::AddressMode::ClampToEdge);
+
+    let builder = gpu
+        .new_sampler("per axis test")
+        .clamp()
+        .address_u(wgpu::AddressMode::Repeat)
+        .address_w(wgpu::AddressMode::MirrorRepeat);
+    assert_eq!(builder.inner.address_mode_u, wgpu::AddressMode::Repeat);
+    assert_eq!(builder.inner.address_mode_v, wgpu::AddressMode::ClampToEdge);
+    assert_eq!(
+        builder.inner.address_mode_w,
+        wgpu::AddressMode::MirrorRepeat
+    );
+}
+
+#[test]
+fn texture_write_mip() {
+    let gpu = match common::headless_gpu() {
+        Some(gpu) => gpu,
+        None => return,
+    };
+
+    let texture = gpu
+        .new_texture("write_mip test")
+        .with_format(wgpu::TextureFormat::Rgba8Unorm)
+        .allow_copy_from()
+        .mips(2)
+        .create_empty((4, 4));
+    assert_eq!(texture.mip_level_count, 2);
+
+    // Mip 1 is 2x2
+    let data: [u32; 4] = [0x11111111, 0x22222222, 0x33333333, 0x44444444];
+    texture.write_mip(1, (2, 2), &data);
+
+    // Rows must be padded to COPY_BYTES_PER_ROW_ALIGNMENT
+    let row = wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+    let buffer = gpu
+        .new_buffer("write_mip readback")
+        .allow_copy_to()
+        .allow_copy_from()
+        .create_uninit(2 * row as u64);
+    let mut encoder = gpu.create_command_encoder("write_mip readback encoder");
+    encoder.copy_texture_to_buffer(
+        wgpu::ImageCopyTexture {
+            texture: &texture,
+            mip_level: 1,
+            origin: wgpu::Origin3d::ZERO,
+            aspect: wgpu::TextureAspect::All,
+        },
+        wgpu::ImageCopyBuffer {
+            buffer: &buffer,
+            layout: wgpu::ImageDataLayout {
+                offset: 0,
+                bytes_per_row: std::num::NonZeroU32::new(row),
+                rows_per_image: None,
+            },
+        },
+        wgpu::Extent3d {
+            width: 2,
+            height: 2,
+            depth_or_array_layers: 1,
+        },
+    );
+    drop(encoder);
+
+    let read = buffer.download_immediately().unwrap();
+    let read: &[u32] = bytemuck::cast_slice(&read);
+    let row = row as usize / 4;
+    assert_eq!(&read[..2], &data[..2]);
+    assert_eq!(&read[row..row + 2], &data[2..]);
+}
+
+#[test]
+fn texture_resize_keeps_mips() {
+    let gpu = match common::headless_gpu() {
+        Some(gpu) => gpu,
+        None => return,
+    };
+
+    let mut texture = gpu
+        .new_texture("resize mips test")
+        .allow_binding()
+        .mips(3)
+        .create_empty((16, 16));
+    texture.resize((32, 32));
+    assert_eq!(texture.mip_level_count, 3);
+
+    texture.resize_with_copy((64, 64));
+    assert_eq!(texture.mip_level_count, 3);
+}
+
+#[test]
+fn texture_resize_multisampled() {
+    let gpu = match common::headless_gpu() {
+        Some(gpu) => gpu,
+        None => return,
+    };
+
+    let mut texture = gpu
+        .new_texture("resize msaa test")
+        .as_render_target()
+        .with_format(wgpu::TextureFormat::Rgba8Unorm)
+        .multisample(4)
+        .create_empty((16, 16));
+    assert_eq!(texture.sample_count, 4);
+
+    // Must not attempt an illegal multisampled copy
+    texture.resize_with_copy((32, 32));
+    assert_eq!(texture.sample_count, 4);
+    assert_eq!(texture.size, (32, 32));
+
+    texture.resize((8, 8));
+    assert_eq!(texture.sample_count, 4);
+}
+
+#[test]
+fn gbuffer_targets_in_order() {
+    let gpu = match common::headless_gpu() {
+        Some(gpu) => gpu,
+        None => return,
+    };
+
+    let formats = [
+        wgpu::TextureFormat::Rgba8Unorm,
+        wgpu::TextureFormat::Rgba16Float,
+        wgpu::TextureFormat::R32Float,
+    ];
+    let mut gbuffer = gpu
+        .new_gbuffer((16, 16))
+        .with_target("albedo", formats[0])
+        .with_target("normal", formats[1])
+        .with_target("depth", formats[2])
+        .create();
+
+    let color_targets = gbuffer.color_targets();
+    assert_eq!(gbuffer.attachments().len(), formats.len());
+    for (i, format) in formats.iter().enumerate() {
+        assert_eq!(color_targets[i].format, *format);
+        assert_eq!(*gbuffer[i].format, *format);
+    }
+
+    gbuffer.resize((32, 8));
+    assert!(gbuffer.targets.iter().all(|target| target.size == (32, 8)));
+}
+
+#[test]
+fn texture_sub_resource_views() {
+    let gpu = match common::headless_gpu() {
+        Some(gpu) => gpu,
+        None => return,
+    };
+
+    let texture = gpu
+        .new_texture("mip view test")
+        .mips(3)
+        .allow_binding()
+        .create_empty((64, 64));
+    let view = texture.create_view_mip(1);
+    let _bind_group = gpu.create_bind_group(&[agpu::Binding {
+        resource: wgpu::BindingResource::TextureView(&view),
+        ..texture.bind_texture()
+    }]);
+
+    let array = gpu
+        .new_texture("layer view test")
+        .allow_binding()
+        .create_empty(D2Array(64, 64, 4));
+    let view = array.create_view_layer(3);
+    let _bind_group = gpu.create_bind_group(&[agpu::Binding {
+        resource: wgpu::BindingResource::TextureView(&view),
+        ty: wgpu::BindingType::Texture {
+            sample_type: wgpu::TextureSampleType::Float { filterable: true },
+            view_dimension: wgpu::TextureViewDimension::D2,
+            multisampled: false,
+        },
+        ..array.bind_texture()
+    }]);
+}
+
+#[test]
+fn format_capabilities() {
+    let gpu = match common::headless_gpu() {
+        Some(gpu) => gpu,
+        None => return,
+    };
+
+    assert!(gpu.supports_storage(wgpu::TextureFormat::Rgba8Unorm));
+    assert!(!gpu.supports_storage(wgpu::TextureFormat::Bgra8UnormSrgb));
+    assert!(gpu.supports_filtering(wgpu::TextureFormat::Rgba8UnormSrgb));
+    assert!(gpu.supports_msaa(wgpu::TextureFormat::Rgba8UnormSrgb, 4));
+    assert!(!gpu.supports_msaa(wgpu::TextureFormat::Rgba8UnormSrgb, 3));
+    assert!(gpu.supports_msaa(wgpu::TextureFormat::Rgba8UnormSrgb, 1));
+    // Compressed formats cannot be render targets at all
+    assert!(!gpu.supports_msaa(wgpu::TextureFormat::Bc1RgbaUnorm, 1));
+    assert!(!gpu.supports_msaa(wgpu::TextureFormat::Bc1RgbaUnorm, 4));
+}
+
+#[test]
+fn texture_clear() {
+    let gpu = match common::headless_gpu() {
+        Some(gpu) => gpu,
+        None => return,
+    };
+
+    let texture = gpu
+        .new_texture("clear test")
+        .as_render_target()
+        .allow_copy_from()
+        .create((4, 4), &[0xffff_ffff_u32; 16]);
+
+    let mut encoder = gpu.create_command_encoder("clear test");
+    if gpu.features().contains(wgpu::Features::CLEAR_COMMANDS) {
+        texture.clear(&mut encoder).unwrap();
+        gpu.queue.submit([encoder.finish()]);
+        let data = texture.read_immediately().unwrap();
+        assert!(data.iter().take(16).all(|&byte| byte == 0));
+    } else {
+        assert!(texture.clear(&mut encoder).is_err());
+    }
+
+    let mut encoder = gpu.create_command_encoder("clear pass test");
+    texture.clear_with_pass(&mut encoder, 0xff00_00ff);
+    gpu.queue.submit([encoder.finish()]);
+    let data = texture.read_immediately().unwrap();
+    assert_eq!(&data[0..4], &[0xff, 0x00, 0x00, 0xff]);
+}
+
+#[test]
+fn texture_copy_matching_format() {
+    let gpu = match common::headless_gpu() {
+        Some(gpu) => gpu,
+        None => return,
+    };
+
+    let data = (0..16_u32).collect::<Vec<_>>();
+    let src = gpu
+        .new_texture("copy src")
+        .allow_copy_from()
+        .create((4, 4), &data);
+    let dst = gpu
+        .new_texture("copy dst")
+        .allow_copy_from()
+        .create_empty((4, 4));
+    gpu.copy_texture(&src, &dst).unwrap();
+
+    let copied = dst.read_immediately().unwrap();
+    assert_eq!(&copied[0..16], bytemuck::cast_slice::<u32, u8>(&data[0..4]));
+
+    let small = gpu.new_texture("copy small").create_empty((2, 2));
+    assert!(matches!(
+        gpu.copy_texture(&src, &small),
+        Err(GpuError::ValidationError(_))
+    ));
+}
+
+#[test]
+fn texture_copy_mismatched_format() {
+    let gpu = match common::headless_gpu() {
+        Some(gpu) => gpu,
+        None => return,
+    };
+
+    let src = gpu
+        .new_texture("blit src")
+        .allow_copy_from()
+        .allow_binding()
+        .create((4, 4), &[0xff00_00ff_u32; 16]);
+    let dst = gpu
+        .new_texture("blit dst")
+        .with_format(wgpu::TextureFormat::Rgba8Unorm)
+        .as_render_target()
+        .allow_copy_from()
+        .create_empty((4, 4));
+    assert!(matches!(
+        gpu.copy_texture(&src, &dst),
+        Err(GpuError::UnsupportedFormat(wgpu::TextureFormat::Rgba8Unorm))
+    ));
+
+    gpu.blit_texture(&src, &dst).unwrap();
+    let blitted = dst.read_immediately().unwrap();
+    assert_eq!(&blitted[0..4], &src.read_immediately().unwrap()[0..4]);
+}
+
+#[test]
+fn texture_read_rgba_swizzle() {
+    let gpu = match common::headless_gpu() {
+        Some(gpu) => gpu,
+        None => return,
+    };
+
+    // Blue, green, red, alpha
+    let bgra = [0x10_u8, 0x20, 0x30, 0xff];
+    let texture = gpu
+        .new_texture("bgra texture")
+        .with_format(wgpu::TextureFormat::Bgra8Unorm)
+        .allow_copy_from()
+        .create((3, 2), &[u32::from_ne_bytes(bgra); 6]);
+    let rgba = texture.read_immediately_rgba().unwrap();
+    assert_eq!(rgba.len(), 3 * 2 * 4);
+    assert_eq!(&rgba[..4], &[0x30, 0x20, 0x10, 0xff]);
+    assert_eq!(&rgba[20..], &[0x30, 0x20, 0x10, 0xff]);
+
+    let texture = gpu
+        .new_texture("r32 texture")
+        .with_format(wgpu::TextureFormat::R32Float)
+        .allow_copy_from()
+        .create_empty((2, 2));
+    assert!(matches!(
+        texture.read_immediately_rgba(),
+        Err(GpuError::UnsupportedFormat(wgpu::TextureFormat::R32Float))
+    ));
+}
+
+#[test]
+fn blit_caches_pipeline_per_format() {
+    let gpu = match common::headless_gpu() {
+        Some(gpu) => gpu,
+        None => return,
+    };
+
+    let src = gpu
+        .new_texture("hdr src")
+        .with_format(wgpu::TextureFormat::Rgba16Float)
+        .allow_binding()
+        .create_empty((4, 4));
+    let dst = gpu
+        .new_texture("bgra dst")
+        .with_format(wgpu::TextureFormat::Bgra8UnormSrgb)
+        .as_render_target()
+        .create_empty((8, 8));
+
+    let mut encoder = gpu.create_command_encoder("blit encoder");
+    gpu.blit(&mut encoder, &src, &dst.view, *dst.format)
+        .unwrap();
+    gpu.blit(&mut encoder, &src, &dst.view, *dst.format)
+        .unwrap();
+    assert_eq!(gpu.blit_cache.len(), 1);
+
+    let src_target = gpu
+        .new_texture("hdr target")
+        .with_format(wgpu::TextureFormat::Rgba16Float)
+        .as_render_target()
+        .create_empty((4, 4));
+    gpu.blit(&mut encoder, &src, &src_target.view, *src_target.format)
+        .unwrap();
+    assert_eq!(gpu.blit_cache.len(), 2);
+
+    // Integer textures cannot be sampled by the blit shader
+    let int_src = gpu
+        .new_texture("int src")
+        .with_format(wgpu::TextureFormat::R32Uint)
+        .allow_binding()
+        .create_empty((4, 4));
+    assert!(matches!(
+        gpu.blit(&mut encoder, &int_src, &dst.view, *dst.format),
+        Err(GpuError::UnsupportedFormat(wgpu::TextureFormat::R32Uint))
+    ));
+}
+
+#[cfg(feature = "image")]
+#[test]
+fn texture_from_image() {
+    let gpu = match common::headless_gpu() {
+        Some(gpu) => gpu,
+        None => return,
+    };
+
+    let img = image::RgbaImage::from_pixel(8, 4, image::Rgba([255, 0, 0, 255]));
+    let texture = gpu
+        .new_texture("image texture")
+        .allow_binding()
+        .create_from_image(&img);
+    assert_eq!(texture.size, (8, 4));
+    assert_eq!(*texture.format, wgpu::TextureFormat::Rgba8UnormSrgb);
+
+    assert!(matches!(
+        agpu::Texture::load_from_path(&gpu, "does/not/exist.png"),
+        Err(GpuError::ImageError(_))
+    ));
+}
+
+#[test]
+fn offscreen_target_frame() {
+    use agpu::BeginRenderFrame;
+
+    let gpu = match common::headless_gpu() {
+        Some(gpu) => gpu,
+        None => return,
+    };
+
+    let target = gpu.new_offscreen(16, 16, wgpu::TextureFormat::Rgba8Unorm);
+    {
+        let mut frame = target.begin_frame().unwrap();
+        // Offscreen frames have no surface to become suboptimal
+        assert!(!frame.is_suboptimal());
+        frame.clear_pass("offscreen clear", 0x00ff_00ff).begin();
+    }
+
+    let data = target.texture.read_immediately().unwrap();
+    assert_eq!(&data[0..4], &[0x00, 0xff, 0x00, 0xff]);
+}
+
+#[test]
+fn frame_size() {
+    use agpu::BeginRenderFrame;
+
+    let gpu = match common::headless_gpu() {
+        Some(gpu) => gpu,
+        None => return,
+    };
+
+    let target = gpu.new_offscreen(32, 16, wgpu::TextureFormat::Rgba8Unorm);
+    let mut frame = target.begin_frame().unwrap();
+    assert_eq!(frame.size(), (32, 16));
+    assert_eq!(frame.aspect_ratio(), 2.0);
+
+    frame.resized_to = Some((8, 16));
+    assert_eq!(frame.size(), (8, 16));
+    assert_eq!(frame.aspect_ratio(), 0.5);
+}
+
+#[test]
+fn texture_read_pixel() {
+    let gpu = match common::headless_gpu() {
+        Some(gpu) => gpu,
+        None => return,
+    };
+
+    let ids = (0..16_u32).map(|id| id * 0x0101_0101).collect::<Vec<_>>();
+    let texture = gpu
+        .new_texture("picking ids")
+        .allow_copy_from()
+        .create((4, 4), &ids);
+    assert_eq!(texture.read_pixel(2, 1).unwrap(), [6, 6, 6, 6]);
+    assert_eq!(texture.read_pixel(3, 3).unwrap(), [15, 15, 15, 15]);
+    assert!(matches!(
+        texture.read_pixel(4, 0),
+        Err(agpu::GpuError::ValidationError(_))
+    ));
+}
+
+#[test]
+fn texture_write_block_stride() {
+    let gpu = match common::headless_gpu() {
+        Some(gpu) => gpu,
+        None => return,
+    };
+
+    let texture = gpu
+        .new_texture("write block target")
+        .allow_copy_from()
+        .create_empty((256, 256));
+    // The block rows are 4 texels wide, not the width of the texture
+    let block = (0..16_u32).map(|id| id * 0x0101_0101).collect::<Vec<_>>();
+    texture.write_block((126, 126), (4, 4), &block);
+
+    assert_eq!(texture.read_pixel(126, 126).unwrap(), [0, 0, 0, 0]);
+    assert_eq!(texture.read_pixel(127, 126).unwrap(), [1, 1, 1, 1]);
+    assert_eq!(texture.read_pixel(126, 127).unwrap(), [4, 4, 4, 4]);
+    assert_eq!(texture.read_pixel(129, 129).unwrap(), [15, 15, 15, 15]);
+}
+
+#[test]
+fn texture_read_depth() {
+    use agpu::DepthAttachmentBuild;
+
+    let gpu = match common::headless_gpu() {
+        Some(gpu) => gpu,
+        None => return,
+    };
+
+    // Each row of 3 f32s is padded to the copy alignment
+    let depth = gpu
+        .new_texture("read depth")
+        .as_depth()
+        .allow_copy_from()
+        .create_empty((3, 2));
+    let mut encoder = gpu.create_command_encoder("read depth encoder");
+    encoder
+        .depth_pass(
+            "read depth clear",
+            depth.attach_depth().clear_depth_val(0.25),
+        )
+        .begin();
+    gpu.queue.submit([encoder.finish()]);
+    assert_eq!(depth.read_depth().unwrap(), [0.25; 6]);
+
+    let depth24 = gpu
+        .new_texture("read depth24")
+        .as_depth24()
+        .allow_copy_from()
+        .create_empty((3, 2));
+    assert!(matches!(
+        depth24.read_depth(),
+        Err(agpu::GpuError::UnsupportedFormat(_))
+    ));
+}
+
+#[test]
+fn mip_level_count_matches_wgpu() {
+    assert_eq!(mip_level_count(1, 1), 1);
+    assert_eq!(mip_level_count(0, 0), 1);
+    assert_eq!(mip_level_count(1024, 1024), 11);
+    assert_eq!(mip_level_count(1280, 720), 11);
+    assert_eq!(mip_level_count(720, 1280), 11);
+    assert_eq!(mip_level_count(1023, 1), 10);
+    assert_eq!(mip_level_count(1025, 1), 11);
+    assert_eq!(mip_level_count(1920, 1080), 11);
+    assert_eq!(mip_level_count(u32::MAX, 1), 32);
+    for size in 1..=4096 {
+        let extent = wgpu::Extent3d {
+            width: size,
+            height: size / 2 + 1,
+            depth_or_array_layers: 1,
+        };
+        assert_eq!(mip_level_count(size, size / 2 + 1), extent.max_mips());
+    }
+}
+
+#[test]
+fn texture_full_mips() {
+    let gpu = match common::headless_gpu() {
+        Some(gpu) => gpu,
+        None => return,
+    };
+
+    let texture = gpu
+        .new_texture("full mips")
+        .with_full_mips()
+        .allow_binding()
+        .create_empty((1280, 720));
+    assert_eq!(texture.mip_level_count, 11);
+    // The last mip is 1x1
+    let _view = texture.create_view_mip(10);
+
+    let array = gpu
+        .new_texture("full mips array")
+        .with_full_mips()
+        .allow_binding()
+        .create_empty(D2Array(16, 8, 64));
+    assert_eq!(array.mip_level_count, 5);
+
+    let texture = gpu
+        .new_texture("explicit mips")
+        .with_full_mips()
+        .mips(2)
+        .create_empty((1280, 720));
+    assert_eq!(texture.mip_level_count, 2);
+}
+
+#[test]
+fn texture_full_mips_from_data() {
+    let gpu = match common::headless_gpu() {
+        Some(gpu) => gpu,
+        None => return,
+    };
+
+    let data = vec![0xffff_ffff_u32; 16 * 16];
+    let texture = gpu
+        .new_texture("full mips from data")
+        .with_format(wgpu::TextureFormat::Rgba8Unorm)
+        .with_full_mips()
+        .allow_binding()
+        .create((16, 16), &data);
+    assert_eq!(texture.mip_level_count, 5);
+
+    // Upload the rest of the chain
+    for mip in 1..texture.mip_level_count {
+        let size = (16 >> mip, 16 >> mip);
+        texture.write_mip(mip, size, &data[..(size.0 * size.1) as usize]);
+    }
+}
+
+#[test]
+fn texture_from_raw() {
+    let gpu = match common::headless_gpu() {
+        Some(gpu) => gpu,
+        None => return,
+    };
+
+    let usage = wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_SRC;
+    let raw = gpu.device.create_texture(&wgpu::TextureDescriptor {
+        label: Some("raw texture"),
+        size: wgpu::Extent3d {
+            width: 8,
+            height: 8,
+            depth_or_array_layers: 4,
+        },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: wgpu::TextureFormat::Rgba8Unorm,
+        usage,
+    });
+    let texture = agpu::Texture::from_raw(
+        gpu.clone(),
+        raw,
+        wgpu::TextureFormat::Rgba8Unorm,
+        D2Array(8, 8, 4),
+        usage,
+    );
+    let _bind_group = gpu.create_bind_group(&[texture.bind_texture_array().in_fragment()]);
+    assert_eq!(texture.size.depth(), 4);
+
+    // The wrapped texture can be read back like any other
+    assert_eq!(texture.read_pixel(0, 0).unwrap(), vec![0; 4]);
+}
+
+#[test]
+fn texture_pool_reuses_released_textures() {
+    let gpu = match common::headless_gpu() {
+        Some(gpu) => gpu,
+        None => return,
+    };
+
+    let format = wgpu::TextureFormat::Rgba8Unorm;
+    let usage = wgpu::TextureUsages::TEXTURE_BINDING
+        | wgpu::TextureUsages::COPY_SRC
+        | wgpu::TextureUsages::COPY_DST;
+    let texture = gpu.acquire_texture((4, 4), format, usage);
+    texture.write((4, 4), &[0x1122_3344_u32; 16]);
+    assert!(gpu.texture_pool.is_empty());
+    drop(texture);
+    assert_eq!(gpu.texture_pool.len(), 1);
+
+    // A different key creates a new texture
+    let other = gpu.acquire_texture((4, 8), format, usage);
+    assert_eq!(other.read_pixel(0, 0).unwrap(), vec![0; 4]);
+    assert_eq!(gpu.texture_pool.len(), 1);
+
+    // The reused texture keeps the contents it was released with
+    let reused = gpu.acquire_texture((4, 4), format, usage);
+    assert_eq!(
+        reused.read_pixel(0, 0).unwrap(),
+        vec![0x44, 0x33, 0x22, 0x11]
+    );
+    assert!(gpu.texture_pool.is_empty());
+    let _bind_group = gpu.create_bind_group(&[reused.bind()]);
+
+    drop(reused);
+    drop(other);
+    assert_eq!(gpu.texture_pool.len(), 2);
+    gpu.texture_pool.clear();
+    assert!(gpu.texture_pool.is_empty());
+}
+
+#[test]
+fn sampler_filters_are_independent() {
+    let gpu = match common::headless_gpu() {
+        Some(gpu) => gpu,
+        None => return,
+    };
+
+    let bilinear = gpu.new_sampler("bilinear").mag_linear().min_linear();
+    assert_eq!(bilinear.inner.mag_filter, wgpu::FilterMode::Linear);
+    assert_eq!(bilinear.inner.min_filter, wgpu::FilterMode::Linear);
+    assert_eq!(bilinear.inner.mipmap_filter, wgpu::FilterMode::Nearest);
+
+    let point_mips = gpu.new_sampler("point mips").mipmap_linear();
+    assert_eq!(point_mips.inner.mag_filter, wgpu::FilterMode::Nearest);
+    assert_eq!(point_mips.inner.min_filter, wgpu::FilterMode::Nearest);
+    assert_eq!(point_mips.inner.mipmap_filter, wgpu::FilterMode::Linear);
+    // Any linear filter makes the sampler a filtering sampler
+    assert!(matches!(
+        point_mips.create().bind().ty,
+        wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering)
+    ));
+
+    let trilinear = gpu.new_sampler("trilinear").linear_filter();
+    assert_eq!(trilinear.inner.mag_filter, wgpu::FilterMode::Linear);
+    assert_eq!(trilinear.inner.min_filter, wgpu::FilterMode::Linear);
+    assert_eq!(trilinear.inner.mipmap_filter, wgpu::FilterMode::Linear);
+
+    let pixel_art = gpu.new_sampler("pixel art").create();
+    assert!(matches!(
+        pixel_art.bind().ty,
+        wgpu::BindingType::Sampler(wgpu::SamplerBindingType::NonFiltering)
+    ));
+}
+
+#[test]
+fn texture_pool_is_bounded() {
+    let gpu = match common::headless_gpu() {
+        Some(gpu) => gpu,
+        None => return,
+    };
+
+    let format = wgpu::TextureFormat::Rgba16Float;
+    let usage = wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING;
+    // Resize a mip chain through several sizes, as when dragging a window
+    let mut mips = Vec::new();
+    for width in (100..200).step_by(10) {
+        mips.clear();
+        mips = (0..mip_level_count(width, 100))
+            .map(|i| gpu.acquire_texture(((width >> i).max(1), (100 >> i).max(1)), format, usage))
+            .collect::<Vec<_>>();
+        assert!(gpu.texture_pool.len() <= gpu.texture_pool.capacity());
+    }
+    drop(mips);
+    assert_eq!(gpu.texture_pool.len(), agpu::TexturePool::DEFAULT_CAPACITY);
+
+    gpu.texture_pool.set_capacity(2);
+    assert_eq!(gpu.texture_pool.len(), 2);
+    gpu.texture_pool.set_capacity(0);
+    drop(gpu.acquire_texture((4, 4), format, usage));
+    assert!(gpu.texture_pool.is_empty());
+}
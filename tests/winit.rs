@@ -0,0 +1,131 @@
+#![cfg(test)]
+#![cfg(feature = "winit")]
+
+use std::time::Duration;
+
+use agpu::{InputState, ProgramTime, FPS_SAMPLES};
+use winit::event::{ElementState, KeyboardInput, MouseButton, VirtualKeyCode, WindowEvent};
+
+#[test]
+fn program_time_fps() {
+    let time = ProgramTime::new(60.0);
+    assert_eq!(time.fps(), 0.0);
+
+    time.set_delta_time(Duration::from_millis(10));
+    time.set_delta_time(Duration::from_millis(30));
+    // 2 frames over 40ms
+    assert!((time.fps() - 50.0).abs() < 0.01);
+    assert_eq!(time.delta_time.get(), Duration::from_millis(30));
+}
+
+#[test]
+fn program_time_fps_rolling() {
+    let time = ProgramTime::new(60.0);
+
+    // Slow frames that should be pushed out of the ring buffer
+    for _ in 0..FPS_SAMPLES {
+        time.set_delta_time(Duration::from_millis(100));
+    }
+    assert!((time.fps() - 10.0).abs() < 0.01);
+
+    for _ in 0..FPS_SAMPLES {
+        time.set_delta_time(Duration::from_millis(20));
+    }
+    assert!((time.fps() - 50.0).abs() < 0.01);
+}
+
+#[allow(deprecated)]
+fn key_event(key: VirtualKeyCode, state: ElementState) -> WindowEvent<'static> {
+    WindowEvent::KeyboardInput {
+        device_id: unsafe { winit::event::DeviceId::dummy() },
+        input: KeyboardInput {
+            scancode: 0,
+            state,
+            virtual_keycode: Some(key),
+            modifiers: Default::default(),
+        },
+        is_synthetic: false,
+    }
+}
+
+#[test]
+fn input_key_pressed_and_held() {
+    let mut input = InputState::default();
+
+    input.handle_event(&key_event(VirtualKeyCode::A, ElementState::Pressed));
+    assert!(input.key_down(VirtualKeyCode::A));
+    assert!(input.key_pressed(VirtualKeyCode::A));
+    input.end_frame();
+
+    // Key repeats are held, not pressed again
+    input.handle_event(&key_event(VirtualKeyCode::A, ElementState::Pressed));
+    assert!(input.key_down(VirtualKeyCode::A));
+    assert!(!input.key_pressed(VirtualKeyCode::A));
+    input.end_frame();
+
+    input.handle_event(&key_event(VirtualKeyCode::A, ElementState::Released));
+    assert!(!input.key_down(VirtualKeyCode::A));
+    assert!(input.key_released(VirtualKeyCode::A));
+    input.end_frame();
+    assert!(!input.key_released(VirtualKeyCode::A));
+}
+
+#[test]
+#[allow(deprecated)]
+fn input_mouse() {
+    let device_id = unsafe { winit::event::DeviceId::dummy() };
+    let mut input = InputState::default();
+    assert_eq!(input.mouse_pos(), None);
+
+    input.handle_event(&WindowEvent::CursorMoved {
+        device_id,
+        position: winit::dpi::PhysicalPosition::new(10.0, 20.0),
+        modifiers: Default::default(),
+    });
+    assert_eq!(input.mouse_pos(), Some((10.0, 20.0)));
+
+    input.handle_event(&WindowEvent::MouseInput {
+        device_id,
+        state: ElementState::Pressed,
+        button: MouseButton::Left,
+        modifiers: Default::default(),
+    });
+    assert!(input.mouse_pressed(MouseButton::Left));
+    input.end_frame();
+    assert!(input.mouse_down(MouseButton::Left));
+    assert!(!input.mouse_pressed(MouseButton::Left));
+
+    // Held input is forgotten when focus is lost
+    input.handle_event(&WindowEvent::Focused(false));
+    assert!(!input.mouse_down(MouseButton::Left));
+
+    input.handle_event(&WindowEvent::CursorLeft { device_id });
+    assert_eq!(input.mouse_pos(), None);
+}
+
+#[test]
+fn program_time_uncapped() {
+    let time = ProgramTime::uncapped();
+    assert!(time.is_uncapped());
+    assert!(!ProgramTime::new(60.0).is_uncapped());
+
+    // The first update only starts the timer
+    assert!(time.should_draw());
+    std::thread::sleep(Duration::from_millis(5));
+    assert!(time.should_draw());
+    assert!(time.delta_time.get() >= Duration::from_millis(5));
+    assert!(time.should_draw());
+}
+
+#[test]
+fn program_builder_adaptive_present() {
+    let builder = agpu::GpuProgramBuilder::new()
+        .with_framerate(60.0)
+        .with_present_mode_adaptive();
+    assert_eq!(builder.present_mode, Some(wgpu::PresentMode::Immediate));
+    assert!(builder.adaptive_present);
+
+    let builder = agpu::GpuProgramBuilder::new().with_present_mode(wgpu::PresentMode::Fifo);
+    assert_eq!(builder.present_mode, Some(wgpu::PresentMode::Fifo));
+    assert!(!builder.adaptive_present);
+}